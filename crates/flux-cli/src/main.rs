@@ -1,6 +1,11 @@
-use miette::{IntoDiagnostic, Result};
+use flux_errors::FluxError;
+use flux_sema::{FileId, FluxDatabase, Schema, SemaDatabase, SourceDatabase, Strictness, Vfs};
+use flux_wasm::WasmCodegen;
+use miette::{IntoDiagnostic, NamedSource, Report, Result};
 use std::env;
 use std::fs;
+use std::path::Path;
+use std::sync::Arc;
 
 fn main() -> Result<()> {
     let args: Vec<String> = env::args().collect();
@@ -19,23 +24,25 @@ fn main() -> Result<()> {
             parse_file(&args[2])?;
         }
         "compile" => {
-            if args.len() < 3 {
-                eprintln!("Usage: flux compile <file.flux> [output.wasm]");
+            let files = positional(&args);
+            let Some(input) = files.first() else {
+                eprintln!(
+                    "Usage: flux compile <file.flux> [output.wasm] [--strictness strict|lenient|off] [--schema <columns>]"
+                );
                 return Ok(());
-            }
-            let output = if args.len() > 3 {
-                &args[3]
-            } else {
-                "output.wasm"
             };
-            compile_file(&args[2], output)?;
+            let output = files.get(1).copied().unwrap_or("output.wasm");
+            compile_file(input, output, strictness(&args)?, schema(&args)?)?;
         }
         "check" => {
-            if args.len() < 3 {
-                eprintln!("Usage: flux check <file.flux>");
+            let files = positional(&args);
+            let Some(input) = files.first() else {
+                eprintln!(
+                    "Usage: flux check <file.flux> [--strictness strict|lenient|off] [--schema <columns>]"
+                );
                 return Ok(());
-            }
-            check_file(&args[2])?;
+            };
+            check_file(input, strictness(&args)?, schema(&args)?)?;
         }
         "--version" | "-v" => {
             println!("flux 0.1.0");
@@ -52,6 +59,53 @@ fn main() -> Result<()> {
     Ok(())
 }
 
+/// Options that take a value
+const OPTIONS: &[&str] = &["--strictness", "--schema"];
+
+/// The arguments after the command that are not options or their values
+fn positional(args: &[String]) -> Vec<&str> {
+    let mut positional = Vec::new();
+    let mut rest = args.iter().skip(2);
+    while let Some(arg) = rest.next() {
+        if OPTIONS.contains(&arg.as_str()) {
+            rest.next();
+        } else {
+            positional.push(arg.as_str());
+        }
+    }
+    positional
+}
+
+/// `Some` when `flag` is given, with the value after it if there is one
+fn option<'a>(args: &'a [String], flag: &str) -> Option<Option<&'a str>> {
+    let index = args.iter().position(|arg| arg == flag)?;
+    Some(args.get(index + 1).map(String::as_str))
+}
+
+/// How missing annotations are reported, given with `--strictness`
+fn strictness(args: &[String]) -> Result<Strictness> {
+    match option(args, "--strictness") {
+        Some(level) => level
+            .ok_or("missing strictness level".to_string())
+            .and_then(str::parse)
+            .map_err(|e| miette::miette!("{}", e)),
+        None => Ok(Strictness::default()),
+    }
+}
+
+/// The input schema given with `--schema`, as `name:type` pairs separated
+/// by commas
+fn schema(args: &[String]) -> Result<Option<Schema>> {
+    match option(args, "--schema") {
+        Some(columns) => columns
+            .ok_or("missing schema columns".to_string())
+            .and_then(str::parse)
+            .map(Some)
+            .map_err(|e| miette::miette!("{}", e)),
+        None => Ok(None),
+    }
+}
+
 fn print_usage() {
     println!(
         r#"Flux - A functional, columnar-first language
@@ -62,7 +116,13 @@ Usage:
 Commands:
     parse <file.flux>              Parse and display AST
     compile <file.flux> [out.wasm] Compile to WebAssembly Component
-    check <file.flux>              Check syntax without compilation
+    check <file.flux>              Check syntax and types without compilation
+
+Options of compile and check:
+    --strictness <level>           Missing annotations: strict (error, default),
+                                   lenient (warning) or off
+    --schema <columns>             Check `#column` references against the input
+                                   schema, given as `name:type,..`
     --version, -v                  Show version
     --help, -h                     Show this help
 
@@ -70,6 +130,7 @@ Examples:
     flux parse examples/plan.flux
     flux compile examples/plan.flux output.wasm
     flux check examples/plan.flux
+    flux check examples/plan.flux --schema "score:int,active:bool"
 "#
     );
 }
@@ -91,10 +152,34 @@ fn parse_file(path: &str) -> Result<()> {
     }
 }
 
-fn compile_file(input_path: &str, output_path: &str) -> Result<()> {
-    let content = fs::read_to_string(input_path).into_diagnostic()?;
+fn compile_file(
+    input_path: &str,
+    output_path: &str,
+    strictness: Strictness,
+    schema: Option<Schema>,
+) -> Result<()> {
+    let vfs = Vfs::new();
+    let file_id = vfs.load_file(Path::new(input_path)).into_diagnostic()?;
+    let mut db = FluxDatabase::new();
+    db.set_strictness(strictness);
+    db.set_schema(schema.map(Arc::new));
+    db.load_std_lib(&vfs);
+    db.set_file_text(file_id, &file_content(&vfs, file_id));
+    db.load_imports(&vfs, file_id);
+
+    // Every module of the program must check as `flux check` checks it
+    let errors = program_errors(&db, &vfs, file_id);
+    if !errors.is_empty() {
+        eprintln!("✗ Compilation error:");
+        let count = errors.len();
+        for (file, error) in errors {
+            report(&vfs, file, error);
+        }
+        return Err(miette::miette!("{} error(s) found", count));
+    }
+    let program = db.link_program(file_id);
 
-    match flux_wasm::compile_to_component(&content) {
+    match WasmCodegen::new().compile_component(&program) {
         Ok(wasm) => {
             fs::write(output_path, &wasm).into_diagnostic()?;
             println!("✓ Successfully compiled {} to {}", input_path, output_path);
@@ -108,28 +193,77 @@ fn compile_file(input_path: &str, output_path: &str) -> Result<()> {
     }
 }
 
-fn check_file(path: &str) -> Result<()> {
-    let content = fs::read_to_string(path).into_diagnostic()?;
+fn check_file(path: &str, strictness: Strictness, schema: Option<Schema>) -> Result<()> {
+    let vfs = Vfs::new();
+    let file_id = vfs.load_file(Path::new(path)).into_diagnostic()?;
 
-    match flux_syntax::parse(&content) {
-        Ok(ast) => {
-            println!("✓ {} is valid", path);
-            println!("  {} items found", ast.items.len());
-
-            // List functions
-            for item in &ast.items {
-                match item {
-                    flux_syntax::Item::Function(func) => {
-                        let export_marker = if func.is_export { "export " } else { "" };
-                        println!("  - {}fn {}", export_marker, func.name);
-                    }
-                }
+    // Syntax errors are reported together with the problems found in the
+    // parts of the file that did parse, and in every module it imports
+    let mut db = FluxDatabase::new();
+    db.set_strictness(strictness);
+    db.set_schema(schema.map(Arc::new));
+    db.load_std_lib(&vfs);
+    db.set_file_text(file_id, &file_content(&vfs, file_id));
+    db.load_imports(&vfs, file_id);
+    let errors = program_errors(&db, &vfs, file_id);
+
+    if !errors.is_empty() {
+        eprintln!("✗ {} contains errors:", path);
+        let count = errors.len();
+        for (file, error) in errors {
+            report(&vfs, file, error);
+        }
+        return Err(miette::miette!("{} error(s) found", count));
+    }
+
+    let ast = &db.parse(file_id).ast;
+    println!("✓ {} is valid", path);
+    println!("  {} items found", ast.items.len());
+
+    // List imports, types and functions
+    for item in &ast.items {
+        match item {
+            flux_syntax::Item::Import(import) => println!("  - import {}", import.module()),
+            flux_syntax::Item::Function(func) => {
+                let export_marker = if func.is_export { "export " } else { "" };
+                println!("  - {}fn {}", export_marker, func.name);
             }
-            Ok(())
+            flux_syntax::Item::TypeAlias(alias) => println!("  - type {}", alias.name),
+            flux_syntax::Item::Struct(def) => println!("  - struct {}", def.name),
+            flux_syntax::Item::Enum(def) => println!("  - enum {}", def.name),
         }
-        Err(e) => {
-            eprintln!("✗ {} contains errors:", path);
-            Err(e).into_diagnostic()
+    }
+    Ok(())
+}
+
+/// The errors of `file_id` and of every module it imports, each with the
+/// file it points into. Warnings are reported on the way.
+fn program_errors(db: &FluxDatabase, vfs: &Vfs, file_id: FileId) -> Vec<(FileId, FluxError)> {
+    let mut errors = Vec::new();
+    for &file in db.program_files(file_id).iter() {
+        for error in db.diagnostics(file).iter().cloned() {
+            if error.is_warning() {
+                report(vfs, file, error);
+            } else {
+                errors.push((file, error));
+            }
         }
     }
+    errors
+}
+
+fn file_content(vfs: &Vfs, file_id: FileId) -> String {
+    vfs.get_file(file_id)
+        .map(|file| file.content.clone())
+        .unwrap_or_default()
+}
+
+/// Print `error` with the source of the file it points into
+fn report(vfs: &Vfs, file_id: FileId, error: FluxError) {
+    let (name, content) = vfs
+        .get_file(file_id)
+        .map(|file| (file.path.display().to_string(), file.content.clone()))
+        .unwrap_or_default();
+    let report = Report::new(error).with_source_code(NamedSource::new(name, content));
+    eprintln!("{:?}", report);
 }
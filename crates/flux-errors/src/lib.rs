@@ -5,7 +5,7 @@ use miette::{Diagnostic, SourceSpan};
 use thiserror::Error;
 
 /// Main error type for Flux compiler errors
-#[derive(Debug, Error, Diagnostic)]
+#[derive(Debug, Clone, PartialEq, Eq, Error, Diagnostic)]
 pub enum FluxError {
     #[error("Syntax error: {message}")]
     #[diagnostic(code(flux::syntax))]
@@ -23,6 +23,18 @@ pub enum FluxError {
         span: SourceSpan,
     },
 
+    #[error("Type error: {message}")]
+    #[diagnostic(code(flux::return_type_mismatch))]
+    ReturnTypeMismatch {
+        message: String,
+        expected: String,
+        found: String,
+        #[label("this is `{found}`")]
+        span: SourceSpan,
+        #[label("expected `{expected}` because of this return type")]
+        annotation: SourceSpan,
+    },
+
     #[error("Semantic error: {message}")]
     #[diagnostic(code(flux::semantic))]
     Semantic {
@@ -31,6 +43,26 @@ pub enum FluxError {
         span: SourceSpan,
     },
 
+    #[error("Missing type annotation: {message}")]
+    #[diagnostic(code(flux::missing_annotation))]
+    MissingAnnotation {
+        message: String,
+        #[label("type annotation required")]
+        span: SourceSpan,
+        #[help]
+        help: Option<String>,
+    },
+
+    #[error("Warning: {message}")]
+    #[diagnostic(code(flux::warning), severity(Warning))]
+    Warning {
+        message: String,
+        #[label("here")]
+        span: SourceSpan,
+        #[help]
+        help: Option<String>,
+    },
+
     #[error("WASM generation error: {message}")]
     #[diagnostic(code(flux::wasm))]
     WasmError { message: String },
@@ -44,6 +76,53 @@ pub enum FluxError {
     },
 }
 
+impl FluxError {
+    /// Warnings are reported but do not fail a check
+    pub fn is_warning(&self) -> bool {
+        matches!(self, FluxError::Warning { .. })
+    }
+
+    /// Where the error points, if anywhere
+    pub fn span(&self) -> Option<SourceSpan> {
+        match self {
+            FluxError::Syntax { span, .. }
+            | FluxError::TypeError { span, .. }
+            | FluxError::ReturnTypeMismatch { span, .. }
+            | FluxError::Semantic { span, .. }
+            | FluxError::MissingAnnotation { span, .. }
+            | FluxError::Warning { span, .. }
+            | FluxError::UnknownIdentifier { span, .. } => Some(*span),
+            FluxError::WasmError { .. } => None,
+        }
+    }
+
+    /// Move every label by `delta` bytes, for source that moved after an edit
+    pub fn shift(&mut self, delta: isize) {
+        match self {
+            FluxError::Syntax { span, .. }
+            | FluxError::TypeError { span, .. }
+            | FluxError::Semantic { span, .. }
+            | FluxError::MissingAnnotation { span, .. }
+            | FluxError::Warning { span, .. }
+            | FluxError::UnknownIdentifier { span, .. } => shift_source_span(span, delta),
+            FluxError::ReturnTypeMismatch {
+                span, annotation, ..
+            } => {
+                shift_source_span(span, delta);
+                shift_source_span(annotation, delta);
+            }
+            FluxError::WasmError { .. } => {}
+        }
+    }
+}
+
+fn shift_source_span(span: &mut SourceSpan, delta: isize) {
+    *span = SourceSpan::new(
+        span.offset().saturating_add_signed(delta).into(),
+        span.len(),
+    );
+}
+
 pub type Result<T> = std::result::Result<T, FluxError>;
 
 /// Represents a position in source code
@@ -58,6 +137,14 @@ impl Span {
         Self { start, end }
     }
 
+    /// The same span moved by `delta` bytes
+    pub fn shifted(self, delta: isize) -> Self {
+        Self::new(
+            self.start.saturating_add_signed(delta),
+            self.end.saturating_add_signed(delta),
+        )
+    }
+
     pub fn to_source_span(&self) -> SourceSpan {
         SourceSpan::new(self.start.into(), self.end - self.start)
     }
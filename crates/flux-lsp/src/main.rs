@@ -1,9 +1,14 @@
 use dashmap::DashMap;
 use flux_errors::FluxError;
-use flux_sema::{check_semantics, FileId, SymbolBridge, Vfs};
+use flux_sema::{
+    FileId, FluxDatabase, Schema, SemaDatabase, SourceDatabase, Strictness, SymbolBridge, TypeInfo,
+    Vfs, STD_SCHEME,
+};
+use flux_syntax::TextEdit;
 use miette::SourceSpan;
+use serde::Deserialize;
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use tower_lsp::jsonrpc::Result;
 use tower_lsp::lsp_types::*;
 use tower_lsp::{Client, LanguageServer, LspService, Server};
@@ -13,47 +18,49 @@ pub struct FluxLanguageServer {
     vfs: Arc<Vfs>,
     symbol_bridge: Arc<SymbolBridge>,
     document_map: DashMap<Url, FileId>,
+    /// Parses and checks of every open document, recomputed only where an
+    /// edit invalidates them
+    db: Mutex<FluxDatabase>,
 }
 
 impl FluxLanguageServer {
     pub fn new(client: Client) -> Self {
+        let vfs = Arc::new(Vfs::new());
+        let mut db = FluxDatabase::new();
+        db.load_std_lib(&vfs);
+
         Self {
             client,
-            vfs: Arc::new(Vfs::new()),
+            vfs,
             symbol_bridge: Arc::new(SymbolBridge::new()),
             document_map: DashMap::new(),
+            db: Mutex::new(db),
         }
     }
 
     fn analyze_document(&self, file_id: FileId) {
         if let Some(file_data) = self.vfs.get_file(file_id) {
-            match flux_syntax::parse(&file_data.content) {
-                Ok(ast) => {
-                    // Analyze symbols first
-                    self.symbol_bridge.analyze_file(file_id, &ast);
-
-                    // Run semantic checks
-                    let symbol_table = self.symbol_bridge.symbol_table();
-                    let errors = check_semantics(&ast, symbol_table, file_id);
-
-                    // Convert errors to diagnostics
-                    let diagnostics: Vec<Diagnostic> = errors
-                        .iter()
-                        .map(|e| flux_error_to_diagnostic(e, &file_data.content))
-                        .collect();
-
-                    // Publish diagnostics
-                    if let Some(uri) = self.file_id_to_uri(file_id) {
-                        let client = self.client.clone();
-                        tokio::spawn(async move {
-                            client.publish_diagnostics(uri, diagnostics, None).await;
-                        });
-                    }
-                }
-                Err(_) => {
-                    // Handle parse errors - for now we just don't publish diagnostics
-                    // In the future, we could publish parse errors as diagnostics too
-                }
+            // Syntax errors leave a partial AST, which still gets symbols
+            // and checks so hover keeps working on half-typed code
+            let (parse, errors) = {
+                let db = self.db.lock().unwrap();
+                (db.parse(file_id), db.diagnostics(file_id))
+            };
+
+            // Symbols for hover
+            self.symbol_bridge.analyze_file(file_id, &parse.ast);
+
+            // Publish diagnostics
+            if let Some(uri) = self.file_id_to_uri(file_id) {
+                let diagnostics: Vec<Diagnostic> = errors
+                    .iter()
+                    .map(|e| flux_error_to_diagnostic(e, &file_data.content, &uri))
+                    .collect();
+
+                let client = self.client.clone();
+                tokio::spawn(async move {
+                    client.publish_diagnostics(uri, diagnostics, None).await;
+                });
             }
         }
     }
@@ -67,11 +74,68 @@ impl FluxLanguageServer {
         }
         None
     }
+
+    /// URI of any file: the document's if it is open, else one built from
+    /// its path
+    fn file_uri(&self, file_id: FileId) -> Option<Url> {
+        if let Some(uri) = self.file_id_to_uri(file_id) {
+            return Some(uri);
+        }
+        let path = self.vfs.get_file(file_id)?.path.clone();
+        if self.vfs.is_std_file(file_id) {
+            Url::parse(path.to_str()?).ok()
+        } else {
+            Url::from_file_path(std::fs::canonicalize(&path).unwrap_or(path)).ok()
+        }
+    }
+
+    /// The std library file a `flux-std:` URI names
+    fn std_file(&self, uri: &Url) -> Option<FileId> {
+        if uri.scheme() != STD_SCHEME {
+            return None;
+        }
+        self.vfs.get_file_by_path(&PathBuf::from(uri.as_str()))
+    }
+
+    /// `flux/virtualDocument`: the text of a read-only `flux-std:` document,
+    /// for clients to show when a definition leads into the std library
+    async fn virtual_document(&self, params: VirtualDocumentParams) -> Result<Option<String>> {
+        Ok(self
+            .std_file(&params.uri)
+            .and_then(|file_id| self.vfs.get_file(file_id))
+            .map(|file_data| file_data.content.clone()))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct VirtualDocumentParams {
+    uri: Url,
 }
 
 #[tower_lsp::async_trait]
 impl LanguageServer for FluxLanguageServer {
-    async fn initialize(&self, _: InitializeParams) -> Result<InitializeResult> {
+    async fn initialize(&self, params: InitializeParams) -> Result<InitializeResult> {
+        if let Some(schema) = params
+            .initialization_options
+            .as_ref()
+            .and_then(schema_from_options)
+        {
+            self.db
+                .lock()
+                .unwrap()
+                .set_schema(Some(Arc::new(schema.clone())));
+            self.symbol_bridge.set_schema(schema);
+        }
+        if let Some(strictness) = params.initialization_options.as_ref().and_then(|options| {
+            options
+                .get("strictness")?
+                .as_str()?
+                .parse::<Strictness>()
+                .ok()
+        }) {
+            self.db.lock().unwrap().set_strictness(strictness);
+        }
+
         Ok(InitializeResult {
             server_info: Some(ServerInfo {
                 name: "flux-lsp".to_string(),
@@ -81,11 +145,12 @@ impl LanguageServer for FluxLanguageServer {
                 text_document_sync: Some(TextDocumentSyncCapability::Options(
                     TextDocumentSyncOptions {
                         open_close: Some(true),
-                        change: Some(TextDocumentSyncKind::FULL),
+                        change: Some(TextDocumentSyncKind::INCREMENTAL),
                         ..Default::default()
                     },
                 )),
                 hover_provider: Some(HoverProviderCapability::Simple(true)),
+                definition_provider: Some(OneOf::Left(true)),
                 completion_provider: Some(CompletionOptions {
                     trigger_characters: Some(vec![".".to_string(), ":".to_string()]),
                     ..Default::default()
@@ -118,9 +183,18 @@ impl LanguageServer for FluxLanguageServer {
     async fn did_open(&self, params: DidOpenTextDocumentParams) {
         let uri = params.text_document.uri;
         let content = params.text_document.text;
+        // Std documents are read-only and always check
+        if self.std_file(&uri).is_some() {
+            return;
+        }
 
         let path = PathBuf::from(uri.path());
-        let file_id = self.vfs.set_file_content(&path, content);
+        let file_id = self.vfs.set_file_content(&path, content.clone());
+        {
+            let mut db = self.db.lock().unwrap();
+            db.set_file_text(file_id, &content);
+            db.load_imports(&self.vfs, file_id);
+        }
 
         self.document_map.insert(uri.clone(), file_id);
         self.analyze_document(file_id);
@@ -132,14 +206,49 @@ impl LanguageServer for FluxLanguageServer {
 
     async fn did_change(&self, params: DidChangeTextDocumentParams) {
         let uri = params.text_document.uri;
+        if self.std_file(&uri).is_some() {
+            return;
+        }
+        let path = PathBuf::from(uri.path());
 
-        if let Some(change) = params.content_changes.first() {
-            let path = PathBuf::from(uri.path());
-            let file_id = self.vfs.set_file_content(&path, change.text.clone());
+        let open_file = self.document_map.get(&uri).map(|id| *id);
+        let mut content = open_file
+            .and_then(|id| self.vfs.get_file(id))
+            .map(|file_data| file_data.content.clone())
+            .unwrap_or_default();
+        let mut db = self.db.lock().unwrap();
+
+        // Ranged changes reparse only the function they touch; a change
+        // without a range replaces the whole document
+        for change in params.content_changes {
+            match change.range {
+                Some(range) => {
+                    let start = position_to_offset(&content, range.start);
+                    let end = position_to_offset(&content, range.end).max(start);
+                    let edit = TextEdit::new(start..end, change.text);
+                    if let Some(file_id) = open_file {
+                        db.apply_edit(file_id, &edit);
+                    }
+                    content = edit.apply(&content);
+                }
+                None => {
+                    if let Some(file_id) = open_file {
+                        db.set_file_text(file_id, &change.text);
+                    }
+                    content = change.text;
+                }
+            }
+        }
 
-            self.document_map.insert(uri.clone(), file_id);
-            self.analyze_document(file_id);
+        let file_id = self.vfs.set_file_content(&path, content.clone());
+        if open_file != Some(file_id) {
+            db.set_file_text(file_id, &content);
         }
+        db.load_imports(&self.vfs, file_id);
+        drop(db);
+
+        self.document_map.insert(uri.clone(), file_id);
+        self.analyze_document(file_id);
     }
 
     async fn did_close(&self, params: DidCloseTextDocumentParams) {
@@ -178,25 +287,112 @@ impl LanguageServer for FluxLanguageServer {
 
         Ok(None)
     }
+
+    async fn goto_definition(
+        &self,
+        params: GotoDefinitionParams,
+    ) -> Result<Option<GotoDefinitionResponse>> {
+        let uri = params.text_document_position_params.text_document.uri;
+        let position = params.text_document_position_params.position;
+
+        let file_id = self.document_map.get(&uri).map(|id| *id);
+        let Some(file_id) = file_id.or_else(|| self.std_file(&uri)) else {
+            return Ok(None);
+        };
+        let Some(file_data) = self.vfs.get_file(file_id) else {
+            return Ok(None);
+        };
+        let offset = position_to_offset(&file_data.content, position);
+        let definition = self.db.lock().unwrap().definition(file_id, offset);
+        let Some((target, span)) = definition else {
+            return Ok(None);
+        };
+
+        let (Some(uri), Some(target_data)) = (self.file_uri(target), self.vfs.get_file(target))
+        else {
+            return Ok(None);
+        };
+        Ok(Some(GotoDefinitionResponse::Scalar(Location {
+            uri,
+            range: span_to_lsp_range(&span.to_source_span(), &target_data.content),
+        })))
+    }
 }
 
+/// Read the input schema from initialization options of the form
+/// `{ "schema": { "column": "type", .. } }`
+fn schema_from_options(options: &serde_json::Value) -> Option<Schema> {
+    let columns = options.get("schema")?.as_object()?;
+    let mut schema = Schema::new();
+    for (name, ty) in columns {
+        if let Some(ty) = ty.as_str() {
+            schema.insert(name.clone(), TypeInfo::from_name(ty));
+        }
+    }
+    Some(schema)
+}
+
+/// Convert an LSP position (line, UTF-16 code units) to a byte offset,
+/// clamped to the end of its line
 fn position_to_offset(content: &str, position: Position) -> usize {
-    let mut offset = 0;
-    for (current_line, line) in content.lines().enumerate() {
-        if current_line == position.line as usize {
-            return offset + position.character as usize;
+    let (target_line, target_character) = (position.line as usize, position.character as usize);
+    let mut line = 0;
+    let mut character = 0;
+
+    for (offset, c) in content.char_indices() {
+        if line == target_line && (character >= target_character || c == '\n') {
+            return offset;
+        }
+        if c == '\n' {
+            line += 1;
+            character = 0;
+        } else {
+            character += c.len_utf16();
         }
-        offset += line.len() + 1; // +1 for newline
     }
 
-    offset
+    content.len()
 }
 
 /// Convert FluxError to LSP Diagnostic
-fn flux_error_to_diagnostic(error: &FluxError, content: &str) -> Diagnostic {
+fn flux_error_to_diagnostic(error: &FluxError, content: &str, uri: &Url) -> Diagnostic {
     let (span, message, code) = match error {
         FluxError::Syntax { message, span } => (span, message.clone(), "flux::syntax"),
         FluxError::TypeError { message, span } => (span, message.clone(), "flux::type_error"),
+        FluxError::ReturnTypeMismatch {
+            message,
+            expected,
+            span,
+            annotation,
+            ..
+        } => {
+            return Diagnostic {
+                range: span_to_lsp_range(span, content),
+                severity: Some(DiagnosticSeverity::ERROR),
+                code: Some(NumberOrString::String(
+                    "flux::return_type_mismatch".to_string(),
+                )),
+                message: message.clone(),
+                related_information: Some(vec![DiagnosticRelatedInformation {
+                    location: Location {
+                        uri: uri.clone(),
+                        range: span_to_lsp_range(annotation, content),
+                    },
+                    message: format!("expected `{}` because of this return type", expected),
+                }]),
+                ..Default::default()
+            };
+        }
+        FluxError::MissingAnnotation {
+            message,
+            span,
+            help,
+        } => (span, with_help(message, help), "flux::missing_annotation"),
+        FluxError::Warning {
+            message,
+            span,
+            help,
+        } => (span, with_help(message, help), "flux::warning"),
         FluxError::Semantic { message, span } => (span, message.clone(), "flux::semantic"),
         FluxError::UnknownIdentifier { name, span } => (
             span,
@@ -225,16 +421,29 @@ fn flux_error_to_diagnostic(error: &FluxError, content: &str) -> Diagnostic {
     };
 
     let range = span_to_lsp_range(span, content);
+    let severity = if error.is_warning() {
+        DiagnosticSeverity::WARNING
+    } else {
+        DiagnosticSeverity::ERROR
+    };
 
     Diagnostic {
         range,
-        severity: Some(DiagnosticSeverity::ERROR),
+        severity: Some(severity),
         code: Some(NumberOrString::String(code.to_string())),
         message,
         ..Default::default()
     }
 }
 
+/// LSP diagnostics have no help field, so suggestions go on their own line
+fn with_help(message: &str, help: &Option<String>) -> String {
+    match help {
+        Some(help) => format!("{}\nhelp: {}", message, help),
+        None => message.to_string(),
+    }
+}
+
 /// Convert a SourceSpan to an LSP Range
 fn span_to_lsp_range(span: &SourceSpan, content: &str) -> Range {
     let start_offset = span.offset();
@@ -286,7 +495,9 @@ async fn main() {
     let stdin = tokio::io::stdin();
     let stdout = tokio::io::stdout();
 
-    let (service, socket) = LspService::new(FluxLanguageServer::new);
+    let (service, socket) = LspService::build(FluxLanguageServer::new)
+        .custom_method("flux/virtualDocument", FluxLanguageServer::virtual_document)
+        .finish();
 
     Server::new(stdin, stdout, socket).serve(service).await;
 }
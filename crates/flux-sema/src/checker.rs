@@ -1,7 +1,37 @@
-use crate::SymbolTable;
-use flux_errors::FluxError;
-use flux_syntax::{Expr, Function, Item, SourceFile};
-use std::collections::HashSet;
+use crate::{SymbolTable, TypeInfo, TypeScheme};
+use flux_errors::{FluxError, Span};
+use flux_syntax::{Expr, Function, SourceFile};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+/// How the explicit-types rules from LANGUAGE_DESIGN.md are enforced: every
+/// parameter and every function return type must be annotated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Strictness {
+    /// Missing annotations are errors
+    #[default]
+    Strict,
+    /// Missing annotations are warnings
+    Lenient,
+    /// Missing annotations are not reported
+    Off,
+}
+
+impl FromStr for Strictness {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "strict" => Ok(Strictness::Strict),
+            "lenient" => Ok(Strictness::Lenient),
+            "off" => Ok(Strictness::Off),
+            _ => Err(format!(
+                "Unknown strictness `{}` (expected strict, lenient or off)",
+                s
+            )),
+        }
+    }
+}
 
 /// Check a source file for semantic errors
 pub fn check_semantics(
@@ -9,43 +39,78 @@ pub fn check_semantics(
     symbol_table: &SymbolTable,
     file_id: crate::FileId,
 ) -> Vec<FluxError> {
-    let mut checker = SemanticChecker::new(symbol_table, file_id);
-    checker.check_source_file(ast);
-    checker.errors
+    check_semantics_with(ast, symbol_table, file_id, Strictness::default())
 }
 
-struct SemanticChecker<'a> {
-    #[allow(dead_code)]
-    symbol_table: &'a SymbolTable,
-    #[allow(dead_code)]
+/// Check a source file for semantic errors, enforcing type annotations at
+/// the given strictness
+pub fn check_semantics_with(
+    ast: &SourceFile,
+    symbol_table: &SymbolTable,
     file_id: crate::FileId,
-    errors: Vec<FluxError>,
+    strictness: Strictness,
+) -> Vec<FluxError> {
+    // Collect all defined symbols from the symbol table
+    let symbols = symbol_table.get_symbols(file_id);
+    // Type names are not values
+    let builtin_variants = crate::types::builtin_types()
+        .enums()
+        .flat_map(|def| &def.variants)
+        .map(|variant| variant.name.clone());
+    let defined_names = symbols
+        .iter()
+        .filter(|s| s.kind != crate::SymbolKind::Type)
+        .map(|s| s.name.clone())
+        .chain(builtin_variants)
+        .collect();
+    let signatures = crate::infer_file(ast).signatures;
+
+    let mut checker = SemanticChecker::new(defined_names, signatures, strictness);
+    for func in ast.functions() {
+        checker.check_function(func);
+    }
+    checker.errors
+}
+
+/// Check one function, given the names defined in its file and the inferred
+/// signatures of its functions
+pub(crate) fn check_function_semantics(
+    func: &Function,
     defined_names: HashSet<String>,
+    signatures: HashMap<String, TypeScheme>,
+    strictness: Strictness,
+) -> Vec<FluxError> {
+    let mut checker = SemanticChecker::new(defined_names, signatures, strictness);
+    checker.check_function(func);
+    checker.errors
 }
 
-impl<'a> SemanticChecker<'a> {
-    fn new(symbol_table: &'a SymbolTable, file_id: crate::FileId) -> Self {
-        // Collect all defined symbols from the symbol table
-        let symbols = symbol_table.get_symbols(file_id);
-        let defined_names = symbols.iter().map(|s| s.name.clone()).collect();
+struct SemanticChecker {
+    strictness: Strictness,
+    errors: Vec<FluxError>,
+    defined_names: HashSet<String>,
+    /// Inferred signatures of the file's functions, used to suggest missing
+    /// annotations
+    signatures: HashMap<String, TypeScheme>,
+}
 
+impl SemanticChecker {
+    fn new(
+        defined_names: HashSet<String>,
+        signatures: HashMap<String, TypeScheme>,
+        strictness: Strictness,
+    ) -> Self {
         Self {
-            symbol_table,
-            file_id,
+            strictness,
             errors: Vec::new(),
             defined_names,
-        }
-    }
-
-    fn check_source_file(&mut self, source_file: &SourceFile) {
-        for item in &source_file.items {
-            match item {
-                Item::Function(func) => self.check_function(func),
-            }
+            signatures,
         }
     }
 
     fn check_function(&mut self, func: &Function) {
+        self.check_annotations(func);
+
         // Add parameters to the scope for this function
         let mut local_scope = self.defined_names.clone();
         for param in &func.params {
@@ -56,6 +121,61 @@ impl<'a> SemanticChecker<'a> {
         self.check_expr_with_scope(&func.body, &local_scope);
     }
 
+    /// Report parameters and return types without a type annotation,
+    /// suggesting a type when one can be inferred
+    fn check_annotations(&mut self, func: &Function) {
+        if self.strictness == Strictness::Off {
+            return;
+        }
+
+        // Only fully inferred types make useful suggestions; a parameter
+        // whose type is still a variable is generic
+        let (params, ret) = match self.signatures.get(&func.name).map(|scheme| &scheme.ty) {
+            Some(TypeInfo::Function { params, ret }) => (params.clone(), Some(*ret.clone())),
+            _ => (Vec::new(), None),
+        };
+        let suggest = |ty: Option<TypeInfo>| ty.filter(TypeInfo::is_concrete);
+
+        for (index, param) in func.params.iter().enumerate() {
+            if param.ty.is_none() {
+                let suggestion = suggest(params.get(index).cloned());
+                self.report_missing_annotation(
+                    format!("parameter `{}` of `{}` has no type", param.name, func.name),
+                    param.span,
+                    suggestion.map(|ty| format!("annotate it as `{}: {}`", param.name, ty)),
+                );
+            }
+        }
+
+        if func.return_type.is_none() {
+            let suggestion = suggest(ret);
+            // Point at the signature: everything before the body
+            let signature = Span::new(func.span.start, func.body.span().start);
+            self.report_missing_annotation(
+                format!("function `{}` has no return type", func.name),
+                signature,
+                suggestion.map(|ty| format!("declare it as `-> {}`", ty)),
+            );
+        }
+    }
+
+    fn report_missing_annotation(&mut self, message: String, span: Span, help: Option<String>) {
+        let error = match self.strictness {
+            Strictness::Strict => FluxError::MissingAnnotation {
+                message,
+                span: span.to_source_span(),
+                help,
+            },
+            Strictness::Lenient => FluxError::Warning {
+                message: format!("Missing type annotation: {}", message),
+                span: span.to_source_span(),
+                help,
+            },
+            Strictness::Off => return,
+        };
+        self.errors.push(error);
+    }
+
     fn check_expr_with_scope(&mut self, expr: &Expr, scope: &HashSet<String>) {
         match expr {
             Expr::Var { name, span } => {
@@ -75,6 +195,30 @@ impl<'a> SemanticChecker<'a> {
                     self.check_expr_with_scope(arg, scope);
                 }
             }
+            Expr::Pipeline { left, right, .. } => {
+                self.check_expr_with_scope(left, scope);
+                self.check_expr_with_scope(right, scope);
+            }
+            Expr::Record { fields, .. } => {
+                for field in fields {
+                    self.check_expr_with_scope(&field.value, scope);
+                }
+            }
+            Expr::Field { base, .. } => {
+                self.check_expr_with_scope(base, scope);
+            }
+            Expr::List { elems, .. } | Expr::Tuple { elems, .. } => {
+                for elem in elems {
+                    self.check_expr_with_scope(elem, scope);
+                }
+            }
+            Expr::Index { base, index, .. } => {
+                self.check_expr_with_scope(base, scope);
+                self.check_expr_with_scope(index, scope);
+            }
+            Expr::Unary { operand, .. } | Expr::Try { operand, .. } => {
+                self.check_expr_with_scope(operand, scope);
+            }
             Expr::Binary { left, right, .. } => {
                 self.check_expr_with_scope(left, scope);
                 self.check_expr_with_scope(right, scope);
@@ -92,6 +236,39 @@ impl<'a> SemanticChecker<'a> {
                 // Check the body with the extended scope
                 self.check_expr_with_scope(body, &new_scope);
             }
+            Expr::If {
+                cond,
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                self.check_expr_with_scope(cond, scope);
+
+                // Both branches start from the enclosing scope; a `let` only
+                // extends its own body, so bindings never leak between branches
+                self.check_expr_with_scope(then_branch, scope);
+                self.check_expr_with_scope(else_branch, scope);
+            }
+            Expr::Match {
+                scrutinee, arms, ..
+            } => {
+                self.check_expr_with_scope(scrutinee, scope);
+
+                // Names in a pattern are variants, already in scope, or
+                // bindings for the arm's body
+                for arm in arms {
+                    let mut arm_scope = scope.clone();
+                    arm_scope.extend(arm.pattern.names());
+                    self.check_expr_with_scope(&arm.body, &arm_scope);
+                }
+            }
+            Expr::Lambda { params, body, .. } => {
+                // The body sees the enclosing scope, which it captures from,
+                // plus the lambda's parameters
+                let mut lambda_scope = scope.clone();
+                lambda_scope.extend(params.iter().map(|param| param.name.clone()));
+                self.check_expr_with_scope(body, &lambda_scope);
+            }
             Expr::Return { value, .. } => {
                 self.check_expr_with_scope(value, scope);
             }
@@ -101,8 +278,15 @@ impl<'a> SemanticChecker<'a> {
                     self.check_expr_with_scope(stmt, scope);
                 }
             }
-            // Literals don't need checking
-            Expr::Int { .. } | Expr::Float { .. } | Expr::Bool { .. } | Expr::String { .. } => {}
+            // Literals, column references (resolved by the type checker
+            // against the input schema) and unparsed code don't need checking
+            Expr::Int { .. }
+            | Expr::Float { .. }
+            | Expr::Bool { .. }
+            | Expr::String { .. }
+            | Expr::Temporal { .. }
+            | Expr::Column { .. }
+            | Expr::Error { .. } => {}
         }
     }
 }
@@ -199,4 +383,160 @@ mod tests {
 
         assert_eq!(errors.len(), 0);
     }
+
+    #[test]
+    fn test_if_branch_bindings_are_scoped() {
+        let source = r#"
+            fn test(flag: bool) -> int {
+                if flag { let x = 1 return x } else { return x }
+            }
+        "#;
+
+        let ast = parse(source).unwrap();
+        let file_id = FileId(1);
+
+        let symbol_bridge = SymbolBridge::new();
+        symbol_bridge.analyze_file(file_id, &ast);
+
+        let symbol_table = symbol_bridge.symbol_table();
+        let errors = check_semantics(&ast, symbol_table, file_id);
+
+        assert_eq!(errors.len(), 1);
+        match &errors[0] {
+            FluxError::UnknownIdentifier { name, .. } => assert_eq!(name, "x"),
+            _ => panic!("Expected UnknownIdentifier error"),
+        }
+    }
+
+    fn check_with(source: &str, strictness: Strictness) -> Vec<FluxError> {
+        let ast = parse(source).unwrap();
+        let file_id = FileId(1);
+
+        let symbol_bridge = SymbolBridge::new();
+        symbol_bridge.analyze_file(file_id, &ast);
+
+        check_semantics_with(&ast, symbol_bridge.symbol_table(), file_id, strictness)
+    }
+
+    #[test]
+    fn test_missing_param_annotation_suggests_type() {
+        let source = r#"
+            fn scale(x: int, factor: int) -> int { return x * factor }
+            fn apply(value, flag) -> int { if flag { scale(value, 2) } else { 0 } }
+        "#;
+        let errors = check_with(source, Strictness::Strict);
+
+        assert_eq!(errors.len(), 2);
+        match &errors[0] {
+            FluxError::MissingAnnotation { message, help, .. } => {
+                assert_eq!(message, "parameter `value` of `apply` has no type");
+                assert_eq!(help.as_deref(), Some("annotate it as `value: int`"));
+            }
+            other => panic!("Expected MissingAnnotation, got {:?}", other),
+        }
+        match &errors[1] {
+            FluxError::MissingAnnotation { help, .. } => {
+                assert_eq!(help.as_deref(), Some("annotate it as `flag: bool`"));
+            }
+            other => panic!("Expected MissingAnnotation, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_missing_annotation_suggests_function_type() {
+        let source = "fn apply(f, x: int) -> int { f(x) + 1 }";
+        let errors = check_with(source, Strictness::Strict);
+
+        assert_eq!(errors.len(), 1);
+        let FluxError::MissingAnnotation { help, .. } = &errors[0] else {
+            panic!("Expected MissingAnnotation, got {:?}", errors[0]);
+        };
+        assert_eq!(help.as_deref(), Some("annotate it as `f: (int) -> int`"));
+
+        // The suggestion is valid syntax
+        let fixed = "fn apply(f: (int) -> int, x: int) -> int { f(x) + 1 }";
+        assert!(check_with(fixed, Strictness::Strict).is_empty());
+    }
+
+    #[test]
+    fn test_missing_return_type_suggests_inferred_type() {
+        let source = "fn double(x: float) { return x * 2.0 }";
+        let errors = check_with(source, Strictness::Strict);
+
+        assert_eq!(errors.len(), 1);
+        match &errors[0] {
+            FluxError::MissingAnnotation {
+                message,
+                span,
+                help,
+            } => {
+                assert_eq!(message, "function `double` has no return type");
+                assert_eq!(help.as_deref(), Some("declare it as `-> float`"));
+                assert_eq!(span.offset(), 0);
+            }
+            other => panic!("Expected MissingAnnotation, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_missing_annotation_suggests_type_from_return() {
+        let errors = check_with("fn plan(ctx) -> Project { return ctx }", Strictness::Strict);
+
+        assert_eq!(errors.len(), 1);
+        match &errors[0] {
+            FluxError::MissingAnnotation { help, .. } => {
+                assert_eq!(help.as_deref(), Some("annotate it as `ctx: Project`"));
+            }
+            other => panic!("Expected MissingAnnotation, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_missing_annotation_without_suggestion() {
+        // `ctx` is never used, so it is generic rather than inferred
+        let errors = check_with("fn plan(ctx) -> int { return 0 }", Strictness::Strict);
+
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            &errors[0],
+            FluxError::MissingAnnotation { help: None, .. }
+        ));
+    }
+
+    #[test]
+    fn test_missing_annotation_strictness_levels() {
+        let source = "fn plan(ctx) { return ctx }";
+
+        let errors = check_with(source, Strictness::Lenient);
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().all(FluxError::is_warning));
+
+        assert!(check_with(source, Strictness::Off).is_empty());
+        assert_eq!("lenient".parse(), Ok(Strictness::Lenient));
+        assert!("loose".parse::<Strictness>().is_err());
+    }
+
+    #[test]
+    fn test_partial_ast_still_checked() {
+        let source = r#"
+            fn broken(x: int) -> int {
+                let y = x + ;
+                y * unknown
+            }
+        "#;
+        let parse = flux_syntax::parse_with_recovery(source);
+        assert_eq!(parse.errors.len(), 1);
+
+        let file_id = FileId(1);
+        let symbol_bridge = SymbolBridge::new();
+        symbol_bridge.analyze_file(file_id, &parse.ast);
+        let errors = check_semantics(&parse.ast, symbol_bridge.symbol_table(), file_id);
+
+        // `y` is still bound by the broken `let`
+        assert_eq!(errors.len(), 1);
+        match &errors[0] {
+            FluxError::UnknownIdentifier { name, .. } => assert_eq!(name, "unknown"),
+            other => panic!("Expected UnknownIdentifier, got {:?}", other),
+        }
+    }
 }
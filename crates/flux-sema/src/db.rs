@@ -0,0 +1,1362 @@
+//! Incremental query database for the compiler pipeline. A file's parse is
+//! the input; everything after it is a memoized query, so after an edit only
+//! the queries that read something the edit changed run again.
+//!
+//! Functions are queried moved to start at offset 0, so an edit in one
+//! function leaves the others' queries valid even though it moves them.
+//! Diagnostics are moved back into place last.
+//!
+//! Imports are resolved by `FluxDatabase::load_imports`, which loads the
+//! imported files through the `Vfs`; names a file imports are typed with the
+//! signatures inferred in the file that exports them. The exports of the
+//! std library are in scope everywhere else, behind the file's own imports.
+
+use crate::checker::{check_function_semantics, Strictness};
+use crate::types::{
+    declare_types, dependency_groups, referenced_names, Schema, TypeChecker, TypeDecls, TypeEnv,
+    TypeScheme,
+};
+use crate::{FileId, Vfs};
+use flux_errors::{FluxError, Span};
+use flux_syntax::cst::SyntaxKind;
+use flux_syntax::{
+    parse_with_recovery, Expr, Function, Item, Parse, Pattern, SourceFile, TextEdit, Type,
+    TypeParam,
+};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+
+#[salsa::query_group(SourceDatabaseStorage)]
+pub trait SourceDatabase: salsa::Database {
+    /// Lossless parse of a file, set through `FluxDatabase::set_file_text`
+    /// or `FluxDatabase::apply_edit`
+    #[salsa::input]
+    fn parse(&self, file: FileId) -> Arc<Parse>;
+
+    /// Text of a file, printed back from its syntax tree
+    fn file_text(&self, file: FileId) -> Arc<String>;
+
+    /// Files the modules imported by a file resolved to, by module path.
+    /// Modules that didn't resolve are left out.
+    #[salsa::input]
+    fn resolved_imports(&self, file: FileId) -> Arc<BTreeMap<String, FileId>>;
+
+    /// Files of the std library modules, by module path
+    #[salsa::input]
+    fn std_modules(&self) -> Arc<BTreeMap<String, FileId>>;
+}
+
+fn file_text(db: &dyn SourceDatabase, file: FileId) -> Arc<String> {
+    Arc::new(db.parse(file).syntax().to_string())
+}
+
+#[salsa::query_group(SemaDatabaseStorage)]
+pub trait SemaDatabase: SourceDatabase {
+    /// How missing type annotations are reported
+    #[salsa::input]
+    fn strictness(&self) -> Strictness;
+
+    /// The input schema `#column` references are checked against. Without
+    /// one every column is accepted.
+    #[salsa::input]
+    fn schema(&self) -> Option<Arc<Schema>>;
+
+    /// Types declared with `type`, `struct` and `enum` in the file
+    fn type_decls(&self, file: FileId) -> Arc<TypeDecls>;
+
+    /// Duplicate and infinite type declarations. Kept apart from
+    /// `type_decls`, whose result doesn't change when a declaration moves.
+    fn type_decl_errors(&self, file: FileId) -> Arc<Vec<FluxError>>;
+
+    /// The file's functions, each moved to start at offset 0
+    fn item_tree(&self, file: FileId) -> Arc<ItemTree>;
+
+    /// Names of the file's functions, in source order
+    fn function_names(&self, file: FileId) -> Arc<Vec<String>>;
+
+    /// Names of the file's exported functions
+    fn exports(&self, file: FileId) -> Arc<Vec<String>>;
+
+    /// Functions the file imports, with the file each is defined in
+    fn imported_names(&self, file: FileId) -> Arc<BTreeMap<String, FileId>>;
+
+    /// Unresolved, cyclic and unknown imports
+    fn import_errors(&self, file: FileId) -> Arc<Vec<FluxError>>;
+
+    /// Exports of the std library, with the module each is defined in
+    fn prelude(&self) -> Arc<BTreeMap<String, FileId>>;
+
+    /// A file followed by every module it imports, directly or not
+    fn program_files(&self, file: FileId) -> Arc<Vec<FileId>>;
+
+    fn function(&self, file: FileId, name: String) -> Option<FunctionItem>;
+
+    /// Names in each group of mutually recursive functions, callees first
+    fn function_groups(&self, file: FileId) -> Arc<Vec<Vec<String>>>;
+
+    /// Signatures and type errors of the functions in one of `function_groups`
+    fn group_types(&self, file: FileId, group: usize) -> Arc<GroupTypes>;
+
+    /// Generalized signature of a function
+    fn function_signature(&self, file: FileId, name: String) -> Option<TypeScheme>;
+
+    /// Semantic errors of a function, relative to its start
+    fn function_semantics(&self, file: FileId, name: String) -> Arc<Vec<FluxError>>;
+
+    /// Syntax, semantic and type errors of a file
+    fn diagnostics(&self, file: FileId) -> Arc<Vec<FluxError>>;
+}
+
+/// The functions of a file, independent of where they are in it. A function
+/// named like an earlier one is left out.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ItemTree {
+    pub functions: Vec<FunctionItem>,
+}
+
+/// A function moved to start at offset 0
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionItem(pub Arc<Function>);
+
+// Float literals are never NaN, so AST equality is reflexive
+impl Eq for FunctionItem {}
+
+impl FunctionItem {
+    fn new(func: &Function) -> Self {
+        let mut func = func.clone();
+        func.shift(-(func.span.start as isize));
+        Self(Arc::new(func))
+    }
+}
+
+impl std::ops::Deref for FunctionItem {
+    type Target = Function;
+
+    fn deref(&self) -> &Function {
+        &self.0
+    }
+}
+
+/// Result of inferring one group of mutually recursive functions
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct GroupTypes {
+    /// Generalized signature of each member, in group order
+    pub signatures: Vec<(String, TypeScheme)>,
+    /// Type errors of each member, relative to its start
+    pub errors: Vec<Vec<FluxError>>,
+}
+
+fn type_decls(db: &dyn SemaDatabase, file: FileId) -> Arc<TypeDecls> {
+    Arc::new(declare_types(&db.parse(file).ast).0)
+}
+
+fn type_decl_errors(db: &dyn SemaDatabase, file: FileId) -> Arc<Vec<FluxError>> {
+    Arc::new(declare_types(&db.parse(file).ast).1)
+}
+
+fn item_tree(db: &dyn SemaDatabase, file: FileId) -> Arc<ItemTree> {
+    let parse = db.parse(file);
+    let mut seen = HashSet::new();
+    let functions = parse
+        .ast
+        .functions()
+        .filter(|func| seen.insert(&func.name))
+        .map(FunctionItem::new)
+        .collect();
+    Arc::new(ItemTree { functions })
+}
+
+fn function_names(db: &dyn SemaDatabase, file: FileId) -> Arc<Vec<String>> {
+    let tree = db.item_tree(file);
+    Arc::new(
+        tree.functions
+            .iter()
+            .map(|func| func.name.clone())
+            .collect(),
+    )
+}
+
+fn exports(db: &dyn SemaDatabase, file: FileId) -> Arc<Vec<String>> {
+    let tree = db.item_tree(file);
+    Arc::new(
+        tree.functions
+            .iter()
+            .filter(|func| func.is_export)
+            .map(|func| func.name.clone())
+            .collect(),
+    )
+}
+
+fn imported_names(db: &dyn SemaDatabase, file: FileId) -> Arc<BTreeMap<String, FileId>> {
+    Arc::new(resolve_imports(db, file).0)
+}
+
+fn import_errors(db: &dyn SemaDatabase, file: FileId) -> Arc<Vec<FluxError>> {
+    Arc::new(resolve_imports(db, file).1)
+}
+
+fn prelude(db: &dyn SemaDatabase) -> Arc<BTreeMap<String, FileId>> {
+    let mut names = BTreeMap::new();
+    for &module in db.std_modules().values() {
+        for name in db.exports(module).iter() {
+            names.entry(name.clone()).or_insert(module);
+        }
+    }
+    Arc::new(names)
+}
+
+/// Bring the exports of each imported module into scope, then the prelude.
+/// A cyclic import brings in nothing, so signatures never depend on
+/// themselves.
+fn resolve_imports(
+    db: &dyn SemaDatabase,
+    file: FileId,
+) -> (BTreeMap<String, FileId>, Vec<FluxError>) {
+    let parse = db.parse(file);
+    let resolved = db.resolved_imports(file);
+    let mut names = BTreeMap::new();
+    let mut errors = Vec::new();
+    let error = |message: String, span: Span| FluxError::Semantic {
+        message,
+        span: span.to_source_span(),
+    };
+
+    for import in parse.ast.imports() {
+        let module = import.module();
+        let Some(&target) = resolved.get(&module) else {
+            errors.push(error(
+                format!("Cannot find module `{}`", module),
+                import.path_span,
+            ));
+            continue;
+        };
+        if imports_back(db, file, target) {
+            errors.push(error(
+                format!("Cyclic import: module `{}` imports this file back", module),
+                import.path_span,
+            ));
+            continue;
+        }
+
+        let exports = db.exports(target);
+        let Some(listed) = &import.names else {
+            for name in exports.iter() {
+                names.entry(name.clone()).or_insert(target);
+            }
+            continue;
+        };
+        for name in listed {
+            if exports.contains(&name.name) {
+                names.entry(name.name.clone()).or_insert(target);
+            } else if db.function_names(target).contains(&name.name) {
+                errors.push(error(
+                    format!("`{}` is not exported by module `{}`", name.name, module),
+                    name.span,
+                ));
+            } else {
+                errors.push(error(
+                    format!("Module `{}` has no function `{}`", module, name.name),
+                    name.span,
+                ));
+            }
+        }
+    }
+
+    // The std library itself only sees what it imports
+    if !db.std_modules().values().any(|&module| module == file) {
+        for (name, &module) in db.prelude().iter() {
+            names.entry(name.clone()).or_insert(module);
+        }
+    }
+    (names, errors)
+}
+
+/// Whether `file` is reachable from `target` through imports
+fn imports_back(db: &dyn SemaDatabase, file: FileId, target: FileId) -> bool {
+    let mut stack = vec![target];
+    let mut seen = HashSet::new();
+    while let Some(next) = stack.pop() {
+        if next == file {
+            return true;
+        }
+        if seen.insert(next) {
+            stack.extend(db.resolved_imports(next).values().copied());
+        }
+    }
+    false
+}
+
+fn program_files(db: &dyn SemaDatabase, file: FileId) -> Arc<Vec<FileId>> {
+    let mut files = vec![file];
+    let mut index = 0;
+    while let Some(&next) = files.get(index) {
+        for &module in db.resolved_imports(next).values() {
+            if !files.contains(&module) {
+                files.push(module);
+            }
+        }
+        index += 1;
+    }
+    Arc::new(files)
+}
+
+fn function(db: &dyn SemaDatabase, file: FileId, name: String) -> Option<FunctionItem> {
+    let tree = db.item_tree(file);
+    tree.functions
+        .iter()
+        .find(|func| func.name == name)
+        .cloned()
+}
+
+fn function_groups(db: &dyn SemaDatabase, file: FileId) -> Arc<Vec<Vec<String>>> {
+    let tree = db.item_tree(file);
+    let functions: Vec<&Function> = tree.functions.iter().map(|func| &*func.0).collect();
+    let groups = dependency_groups(&functions)
+        .into_iter()
+        .map(|group| group.iter().map(|&i| functions[i].name.clone()).collect())
+        .collect();
+    Arc::new(groups)
+}
+
+fn group_types(db: &dyn SemaDatabase, file: FileId, group: usize) -> Arc<GroupTypes> {
+    let groups = db.function_groups(file);
+    let Some(names) = groups.get(group) else {
+        return Arc::default();
+    };
+    let members: Vec<FunctionItem> = names
+        .iter()
+        .filter_map(|name| db.function(file, name.clone()))
+        .collect();
+
+    let mut env = TypeEnv::new();
+    env.set_types((*db.type_decls(file)).clone());
+    if let Some(schema) = db.schema() {
+        env.set_schema((*schema).clone());
+    }
+    let mut called = Vec::new();
+    for member in &members {
+        referenced_names(&member.body, &mut called);
+    }
+    let imported = db.imported_names(file);
+    for name in called {
+        if names.contains(&name) || env.get_scheme(&name).is_some() {
+            continue;
+        }
+        // Functions of the file shadow imported ones
+        let scheme = db.function_signature(file, name.clone()).or_else(|| {
+            let &module = imported.get(&name)?;
+            db.function_signature(module, name.clone())
+        });
+        if let Some(scheme) = scheme {
+            env.insert_scheme(name, scheme);
+        }
+    }
+
+    // Members are laid out one after another from offset 0, so every error
+    // can be traced back to the member it points into
+    let mut bases = Vec::with_capacity(members.len());
+    let mut laid_out = Vec::with_capacity(members.len());
+    let mut base = 0;
+    // Std exports never reach a component interface, so unlike other
+    // exports they may stay polymorphic
+    let is_std = db.std_modules().values().any(|&module| module == file);
+    for member in &members {
+        let mut func = (*member.0).clone();
+        func.shift(base as isize);
+        func.is_export &= !is_std;
+        bases.push(base);
+        base += member.span.end + 1;
+        laid_out.push(func);
+    }
+
+    let mut checker = TypeChecker::new();
+    let group: Vec<&Function> = laid_out.iter().collect();
+    let schemes = checker.infer_group(&group, &mut env);
+
+    let mut errors = vec![Vec::new(); members.len()];
+    for mut error in checker.into_errors() {
+        let offset = error.span().map_or(0, |span| span.offset());
+        let member = bases.iter().rposition(|&base| base <= offset).unwrap_or(0);
+        error.shift(-(bases[member] as isize));
+        errors[member].push(error);
+    }
+
+    Arc::new(GroupTypes {
+        signatures: members
+            .iter()
+            .map(|member| member.name.clone())
+            .zip(schemes)
+            .collect(),
+        errors,
+    })
+}
+
+fn function_signature(db: &dyn SemaDatabase, file: FileId, name: String) -> Option<TypeScheme> {
+    let groups = db.function_groups(file);
+    let group = groups.iter().position(|group| group.contains(&name))?;
+    db.group_types(file, group)
+        .signatures
+        .iter()
+        .find(|(member, _)| *member == name)
+        .map(|(_, scheme)| scheme.clone())
+}
+
+fn function_semantics(db: &dyn SemaDatabase, file: FileId, name: String) -> Arc<Vec<FluxError>> {
+    let Some(func) = db.function(file, name.clone()) else {
+        return Arc::default();
+    };
+    let decls = db.type_decls(file);
+    let variants = decls.enums.values().flatten().map(|variant| &variant.name);
+    let defined_names = db
+        .function_names(file)
+        .iter()
+        .chain(db.imported_names(file).keys())
+        .chain(variants)
+        .cloned()
+        .collect();
+    let signatures = db
+        .function_signature(file, name.clone())
+        .map(|scheme| HashMap::from([(name, scheme)]))
+        .unwrap_or_default();
+    Arc::new(check_function_semantics(
+        &func,
+        defined_names,
+        signatures,
+        db.strictness(),
+    ))
+}
+
+fn diagnostics(db: &dyn SemaDatabase, file: FileId) -> Arc<Vec<FluxError>> {
+    let parse = db.parse(file);
+    let mut starts = HashMap::new();
+    for func in parse.ast.functions() {
+        starts.entry(func.name.as_str()).or_insert(func.span.start);
+    }
+    let in_place = |name: &String, errors: &[FluxError]| {
+        let start = starts.get(name.as_str()).copied().unwrap_or(0);
+        errors
+            .iter()
+            .cloned()
+            .map(move |mut error| {
+                error.shift(start as isize);
+                error
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let mut diagnostics = parse.errors.clone();
+    diagnostics.extend(db.import_errors(file).iter().cloned());
+    diagnostics.extend(db.type_decl_errors(file).iter().cloned());
+    for name in db.function_names(file).iter() {
+        diagnostics.extend(in_place(name, &db.function_semantics(file, name.clone())));
+    }
+    for (index, group) in db.function_groups(file).iter().enumerate() {
+        let types = db.group_types(file, index);
+        for (name, errors) in group.iter().zip(&types.errors) {
+            // Unknown identifiers are reported by the semantic checks
+            diagnostics.extend(
+                in_place(name, errors)
+                    .into_iter()
+                    .filter(|error| !matches!(error, FluxError::UnknownIdentifier { .. })),
+            );
+        }
+    }
+    Arc::new(diagnostics)
+}
+
+/// The compiler's query database
+#[salsa::database(SourceDatabaseStorage, SemaDatabaseStorage)]
+pub struct FluxDatabase {
+    storage: salsa::Storage<Self>,
+    /// Files whose text has been set
+    files: HashSet<FileId>,
+}
+
+impl salsa::Database for FluxDatabase {}
+
+impl FluxDatabase {
+    pub fn new() -> Self {
+        let mut db = Self {
+            storage: salsa::Storage::default(),
+            files: HashSet::new(),
+        };
+        db.set_strictness(Strictness::default());
+        db.set_schema(None);
+        db.set_std_modules(Arc::default());
+        db
+    }
+
+    /// Load the std library mounted in `vfs`, bringing its exports into
+    /// scope in every file
+    pub fn load_std_lib(&mut self, vfs: &Vfs) {
+        let mut modules = BTreeMap::new();
+        for (module, file) in vfs.std_modules() {
+            if let Some(data) = vfs.get_file(file) {
+                self.set_file_text(file, &data.content);
+                modules.insert(module, file);
+            }
+        }
+        for &file in modules.values() {
+            self.load_imports(vfs, file);
+        }
+        self.set_std_modules(Arc::new(modules));
+    }
+
+    /// Set the text of a file, parsing it from scratch
+    pub fn set_file_text(&mut self, file: FileId, text: &str) {
+        self.set_parse(file, Arc::new(parse_with_recovery(text)));
+        if self.files.insert(file) {
+            self.set_resolved_imports(file, Arc::default());
+        }
+    }
+
+    /// Resolve the imports of `file`, and of the modules it imports,
+    /// through `vfs`. Module files seen for the first time are loaded.
+    pub fn load_imports(&mut self, vfs: &Vfs, file: FileId) {
+        let mut stack = vec![file];
+        let mut visited = HashSet::new();
+        while let Some(file) = stack.pop() {
+            if !visited.insert(file) {
+                continue;
+            }
+
+            let mut resolved = BTreeMap::new();
+            for import in self.parse(file).ast.imports() {
+                let module = import.module();
+                let Some(target) = vfs.resolve_import(file, &module) else {
+                    continue;
+                };
+                if !self.files.contains(&target) {
+                    let Some(data) = vfs.get_file(target) else {
+                        continue;
+                    };
+                    self.set_file_text(target, &data.content);
+                }
+                resolved.insert(module, target);
+                stack.push(target);
+            }
+            if *self.resolved_imports(file) != resolved {
+                self.set_resolved_imports(file, Arc::new(resolved));
+            }
+        }
+    }
+
+    /// The functions of `file`, every imported function they reach and the
+    /// types declared in every module of the program, as one file for
+    /// codegen. Spans still point into each item's own file. The functions,
+    /// types and variants of a module other than `file` are named
+    /// `<module>.<name>`, so names private to two modules never meet.
+    pub fn link_program(&self, file: FileId) -> SourceFile {
+        let prefixes = self.module_prefixes(file);
+        let mut names: HashMap<FileId, LinkNames> = HashMap::new();
+
+        let mut linked = HashSet::new();
+        let mut pending = VecDeque::new();
+        for func in self.parse(file).ast.functions() {
+            linked.insert((file, func.name.clone()));
+            pending.push_back((file, func.clone()));
+        }
+
+        let mut modules = self.program_files(file).to_vec();
+        let mut items = Vec::new();
+        while let Some((module, mut func)) = pending.pop_front() {
+            let mut called = Vec::new();
+            referenced_names(&func.body, &mut called);
+            let local = self.function_names(module);
+            let imported = self.imported_names(module);
+            for name in called {
+                let target = if local.contains(&name) {
+                    module
+                } else if let Some(&target) = imported.get(&name) {
+                    target
+                } else {
+                    continue;
+                };
+                if !linked.insert((target, name.clone())) {
+                    continue;
+                }
+                let parse = self.parse(target);
+                let func = parse
+                    .ast
+                    .functions()
+                    .find(|func| func.name == name)
+                    .cloned();
+                if let Some(func) = func {
+                    pending.push_back((target, func));
+                }
+            }
+            if !modules.contains(&module) {
+                modules.push(module);
+            }
+            names
+                .entry(module)
+                .or_insert_with(|| self.link_names(module, &prefixes))
+                .rename_function(&mut func);
+            // The program exports what `file` exports; an export of an
+            // imported module only made it visible to the importer
+            func.is_export &= module == file;
+            items.push(Item::Function(func));
+        }
+
+        // Types go first, in the order of the modules declaring them
+        let mut declared = HashSet::new();
+        let mut types = Vec::new();
+        for module in modules {
+            let names = names
+                .entry(module)
+                .or_insert_with(|| self.link_names(module, &prefixes));
+            for item in &self.parse(module).ast.items {
+                let name = match item {
+                    Item::TypeAlias(alias) => &alias.name,
+                    Item::Struct(def) => &def.name,
+                    Item::Enum(def) => &def.name,
+                    Item::Import(_) | Item::Function(_) => continue,
+                };
+                // Sema reports a type declared twice in one module
+                if declared.insert((module, name.clone())) {
+                    let mut item = item.clone();
+                    names.rename_type_item(&mut item);
+                    types.push(item);
+                }
+            }
+        }
+        types.extend(items);
+
+        let span = self.parse(file).ast.span;
+        SourceFile { items: types, span }
+    }
+
+    /// The prefix of the linked names of each module `file` can reach: the
+    /// path it is imported by
+    fn module_prefixes(&self, file: FileId) -> HashMap<FileId, String> {
+        let mut modules: Vec<_> = self
+            .std_modules()
+            .iter()
+            .map(|(path, &module)| (path.clone(), module))
+            .collect();
+        for &module in self.program_files(file).iter() {
+            for (path, &target) in self.resolved_imports(module).iter() {
+                modules.push((path.clone(), target));
+            }
+        }
+
+        let mut prefixes = HashMap::new();
+        let mut taken = HashSet::new();
+        for (path, module) in modules {
+            if module == file || prefixes.contains_key(&module) {
+                continue;
+            }
+            // Relative imports can give two modules the same path
+            let mut prefix = path.clone();
+            let mut count = 1;
+            while !taken.insert(prefix.clone()) {
+                count += 1;
+                prefix = format!("{}#{}", path, count);
+            }
+            prefixes.insert(module, prefix);
+        }
+        prefixes
+    }
+
+    /// What the names `module` uses stand for in the linked program
+    fn link_names(&self, module: FileId, prefixes: &HashMap<FileId, String>) -> LinkNames {
+        let linked = |target: FileId, name: &str| match prefixes.get(&target) {
+            Some(prefix) => format!("{}.{}", prefix, name),
+            None => name.to_string(),
+        };
+        let mut names = LinkNames::default();
+        for (name, &target) in self.imported_names(module).iter() {
+            names.functions.insert(name.clone(), linked(target, name));
+        }
+        for name in self.function_names(module).iter() {
+            names.functions.insert(name.clone(), linked(module, name));
+        }
+        for item in &self.parse(module).ast.items {
+            match item {
+                Item::TypeAlias(alias) => {
+                    names
+                        .types
+                        .insert(alias.name.clone(), linked(module, &alias.name));
+                }
+                Item::Struct(def) => {
+                    names
+                        .types
+                        .insert(def.name.clone(), linked(module, &def.name));
+                }
+                Item::Enum(def) => {
+                    names
+                        .types
+                        .insert(def.name.clone(), linked(module, &def.name));
+                    for variant in &def.variants {
+                        let name = linked(module, &variant.name);
+                        names.variants.insert(variant.name.clone(), name);
+                    }
+                }
+                Item::Import(_) | Item::Function(_) => {}
+            }
+        }
+        names
+    }
+
+    /// The function named at `offset` of `file`: the file it is defined in
+    /// and the span of its name there
+    pub fn definition(&self, file: FileId, offset: usize) -> Option<(FileId, Span)> {
+        let parse = self.parse(file);
+        let token = parse
+            .syntax()
+            .descendants_with_tokens()
+            .filter_map(|element| element.into_token())
+            .find(|token| {
+                let range = token.text_range();
+                token.kind() == SyntaxKind::Ident
+                    && usize::from(range.start()) <= offset
+                    && offset <= usize::from(range.end())
+            })?;
+        let name = token.text().to_string();
+
+        let target = if self.function_names(file).contains(&name) {
+            file
+        } else {
+            *self.imported_names(file).get(&name)?
+        };
+        let name_token = self
+            .parse(target)
+            .tree()
+            .functions()
+            .find_map(|func| func.name().filter(|token| token.text() == name))?;
+        let range = name_token.text_range();
+        Some((
+            target,
+            Span::new(usize::from(range.start()), usize::from(range.end())),
+        ))
+    }
+
+    /// Edit a file whose text was set before, reparsing only the function
+    /// the edit is in when possible
+    pub fn apply_edit(&mut self, file: FileId, edit: &TextEdit) {
+        let parse = self.parse(file).reparse(edit);
+        self.set_parse(file, Arc::new(parse));
+    }
+}
+
+impl Default for FluxDatabase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The names a module's functions, types and variants have in a linked
+/// program, by the names the module knows them under
+#[derive(Default)]
+struct LinkNames {
+    functions: HashMap<String, String>,
+    types: HashMap<String, String>,
+    variants: HashMap<String, String>,
+}
+
+impl LinkNames {
+    fn rename_function(&self, func: &mut Function) {
+        if let Some(name) = self.functions.get(&func.name) {
+            func.name = name.clone();
+        }
+        let type_params = type_param_names(&func.type_params);
+        for param in &mut func.params {
+            if let Some(ty) = &mut param.ty {
+                self.rename_type(ty, &type_params);
+            }
+        }
+        if let Some(ty) = &mut func.return_type {
+            self.rename_type(ty, &type_params);
+        }
+        let mut bound = func.params.iter().map(|param| param.name.clone()).collect();
+        self.rename_expr(&mut func.body, &mut bound, &type_params);
+    }
+
+    fn rename_type_item(&self, item: &mut Item) {
+        match item {
+            Item::TypeAlias(alias) => {
+                self.rename_declared(&mut alias.name);
+                let type_params = type_param_names(&alias.type_params);
+                self.rename_type(&mut alias.ty, &type_params);
+            }
+            Item::Struct(def) => {
+                self.rename_declared(&mut def.name);
+                let type_params = type_param_names(&def.type_params);
+                for field in &mut def.fields {
+                    self.rename_type(&mut field.ty, &type_params);
+                }
+            }
+            Item::Enum(def) => {
+                self.rename_declared(&mut def.name);
+                let type_params = type_param_names(&def.type_params);
+                for variant in &mut def.variants {
+                    if let Some(name) = self.variants.get(&variant.name) {
+                        variant.name = name.clone();
+                    }
+                    for field in &mut variant.fields {
+                        self.rename_type(field, &type_params);
+                    }
+                }
+            }
+            Item::Import(_) | Item::Function(_) => {}
+        }
+    }
+
+    fn rename_declared(&self, name: &mut String) {
+        if let Some(linked) = self.types.get(name) {
+            *name = linked.clone();
+        }
+    }
+
+    /// Rename the types `ty` names, other than the type parameters in scope
+    fn rename_type(&self, ty: &mut Type, type_params: &[String]) {
+        match ty {
+            Type::Named { name, args, .. } => {
+                if !type_params.contains(name) {
+                    self.rename_declared(name);
+                }
+                for arg in args {
+                    self.rename_type(arg, type_params);
+                }
+            }
+            Type::Record { fields, .. } => {
+                for field in fields {
+                    self.rename_type(&mut field.ty, type_params);
+                }
+            }
+            Type::List { elem, .. } => self.rename_type(elem, type_params),
+            Type::Tuple { elems, .. } => {
+                for elem in elems {
+                    self.rename_type(elem, type_params);
+                }
+            }
+            Type::Function { params, ret, .. } => {
+                for param in params {
+                    self.rename_type(param, type_params);
+                }
+                self.rename_type(ret, type_params);
+            }
+            Type::Int(_) | Type::String(_) | Type::Bool(_) | Type::Float(_) => {}
+        }
+    }
+
+    /// Rename the functions, variants and types `expr` uses, leaving alone
+    /// the names bound by locals that shadow them
+    fn rename_expr(&self, expr: &mut Expr, bound: &mut Vec<String>, type_params: &[String]) {
+        let scope = bound.len();
+        match expr {
+            Expr::Var { name, .. } => {
+                if !bound.contains(name) {
+                    let linked = self.functions.get(name).or_else(|| self.variants.get(name));
+                    if let Some(linked) = linked {
+                        *name = linked.clone();
+                    }
+                }
+            }
+            Expr::Record { name, fields, .. } => {
+                if let Some(name) = name {
+                    self.rename_declared(name);
+                }
+                for field in fields {
+                    self.rename_expr(&mut field.value, bound, type_params);
+                }
+            }
+            Expr::Let {
+                name, value, body, ..
+            } => {
+                self.rename_expr(value, bound, type_params);
+                bound.push(name.clone());
+                self.rename_expr(body, bound, type_params);
+            }
+            Expr::Lambda {
+                params,
+                return_type,
+                body,
+                ..
+            } => {
+                for param in params.iter_mut() {
+                    if let Some(ty) = &mut param.ty {
+                        self.rename_type(ty, type_params);
+                    }
+                    bound.push(param.name.clone());
+                }
+                if let Some(ty) = return_type {
+                    self.rename_type(ty, type_params);
+                }
+                self.rename_expr(body, bound, type_params);
+            }
+            Expr::Match {
+                scrutinee, arms, ..
+            } => {
+                self.rename_expr(scrutinee, bound, type_params);
+                for arm in arms {
+                    self.rename_pattern(&mut arm.pattern, bound);
+                    self.rename_expr(&mut arm.body, bound, type_params);
+                    bound.truncate(scope);
+                }
+            }
+            _ => {
+                for child in expr.children_mut() {
+                    self.rename_expr(child, bound, type_params);
+                }
+            }
+        }
+        bound.truncate(scope);
+    }
+
+    /// Rename the variants `pattern` matches, binding the other names
+    fn rename_pattern(&self, pattern: &mut Pattern, bound: &mut Vec<String>) {
+        match pattern {
+            Pattern::Name { name, .. } => match self.variants.get(name) {
+                Some(linked) => *name = linked.clone(),
+                None => bound.push(name.clone()),
+            },
+            Pattern::Variant { name, args, .. } => {
+                if let Some(linked) = self.variants.get(name) {
+                    *name = linked.clone();
+                }
+                for arg in args {
+                    self.rename_pattern(arg, bound);
+                }
+            }
+            Pattern::Wildcard { .. }
+            | Pattern::Int { .. }
+            | Pattern::Bool { .. }
+            | Pattern::String { .. } => {}
+        }
+    }
+}
+
+fn type_param_names(type_params: &[TypeParam]) -> Vec<String> {
+    type_params.iter().map(|param| param.name.clone()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{check_semantics_with, check_types, SymbolBridge};
+    use std::path::Path;
+    use std::sync::Mutex;
+
+    const SOURCE: &str = r#"fn double(x: int) -> int {
+    x * 2
+}
+
+fn quadruple(x: int) -> int {
+    double(double(x))
+}
+
+fn broken() -> int {
+    missing + true
+}
+
+fn even(n: int) -> bool {
+    if n == 0 { true } else { odd(n - 1) }
+}
+
+fn odd(n: int) -> bool {
+    if n == 0 { false } else { even(n - 1) }
+}
+"#;
+
+    /// A database that records every query it runs
+    #[salsa::database(SourceDatabaseStorage, SemaDatabaseStorage)]
+    #[derive(Default)]
+    struct LoggingDatabase {
+        storage: salsa::Storage<Self>,
+        log: Mutex<Vec<String>>,
+    }
+
+    impl salsa::Database for LoggingDatabase {
+        fn salsa_event(&self, event: salsa::Event) {
+            if let salsa::EventKind::WillExecute { database_key } = event.kind {
+                let query = format!("{:?}", database_key.debug(self));
+                self.log.lock().unwrap().push(query);
+            }
+        }
+    }
+
+    impl LoggingDatabase {
+        fn new(text: &str) -> Self {
+            let mut db = Self::default();
+            db.set_strictness(Strictness::default());
+            db.set_schema(None);
+            db.set_parse(FILE, Arc::new(parse_with_recovery(text)));
+            db.set_resolved_imports(FILE, Arc::default());
+            db.set_std_modules(Arc::default());
+            db
+        }
+
+        fn edit(&mut self, edit: TextEdit) {
+            let parse = self.parse(FILE).reparse(&edit);
+            self.set_parse(FILE, Arc::new(parse));
+        }
+
+        fn executed(&self) -> Vec<String> {
+            std::mem::take(&mut *self.log.lock().unwrap())
+        }
+    }
+
+    const FILE: FileId = FileId(1);
+
+    fn full_check(text: &str) -> Vec<String> {
+        let parse = parse_with_recovery(text);
+        let bridge = SymbolBridge::new();
+        bridge.analyze_file(FILE, &parse.ast);
+        let mut errors = parse.errors.clone();
+        errors.extend(check_semantics_with(
+            &parse.ast,
+            bridge.symbol_table(),
+            FILE,
+            Strictness::default(),
+        ));
+        errors.extend(check_types(&parse.ast));
+        sorted(&errors)
+    }
+
+    fn sorted(errors: &[FluxError]) -> Vec<String> {
+        let mut errors: Vec<_> = errors.iter().map(|error| format!("{error:?}")).collect();
+        errors.sort();
+        errors
+    }
+
+    /// Load `files` into a fresh database with the std library, resolving
+    /// imports from the first one
+    fn load_program(files: &[(&str, &str)]) -> (FluxDatabase, Vec<FileId>) {
+        let vfs = Vfs::new();
+        let ids: Vec<_> = files
+            .iter()
+            .map(|(path, text)| vfs.set_file_content(Path::new(path), text.to_string()))
+            .collect();
+        let mut db = FluxDatabase::new();
+        db.load_std_lib(&vfs);
+        db.set_file_text(ids[0], files[0].1);
+        db.load_imports(&vfs, ids[0]);
+        (db, ids)
+    }
+
+    fn messages(errors: &[FluxError]) -> Vec<String> {
+        errors.iter().map(ToString::to_string).collect()
+    }
+
+    fn group_of(db: &dyn SemaDatabase, name: &str) -> usize {
+        db.function_groups(FILE)
+            .iter()
+            .position(|group| group.iter().any(|member| member == name))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_diagnostics_match_full_check() {
+        let sources = [
+            SOURCE,
+            "fn f(x) { x + 1 }\nfn g() -> string { f(2) }",
+            "fn f() -> int { 1 + }\nfn g() -> int { f() + \"s\" }",
+            "struct P { x: int }\ntype Q = { y: Q }\nfn f(p: P) -> int { p.x + p.y }",
+            "enum E { A(int), B }\nfn f(e: E) -> int { match e { A(n) => n, A(_) => 0 } }\n\
+             fn g() -> int { match B { B => 1, x => y } }",
+            include_str!("../../../examples/let_binding.flux"),
+            include_str!("../../../examples/plan.flux"),
+            include_str!("../../../examples/type_error.flux"),
+        ];
+        let mut db = FluxDatabase::new();
+        for source in sources {
+            db.set_file_text(FILE, source);
+            assert_eq!(
+                sorted(&db.diagnostics(FILE)),
+                full_check(source),
+                "{source}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_edit_reruns_only_the_edited_function() {
+        let mut db = LoggingDatabase::new(SOURCE);
+        let before = sorted(&db.diagnostics(FILE));
+        db.executed();
+
+        // The body changes but the signature doesn't
+        let offset = SOURCE.find("x * 2").unwrap();
+        db.edit(TextEdit::new(offset..offset + 5, "x + x + 0"));
+        let diagnostics = db.diagnostics(FILE);
+
+        let executed = db.executed();
+        let double = group_of(&db, "double");
+        let broken = group_of(&db, "broken");
+        assert!(executed.contains(&format!("group_types((FileId(1), {double}))")));
+        assert!(executed.contains(&"function_semantics((FileId(1), \"double\"))".to_string()));
+        let reran = |query: &String| {
+            query.starts_with("group_types") || query.starts_with("function_semantics")
+        };
+        assert_eq!(executed.iter().filter(|query| reran(query)).count(), 2);
+        assert!(!executed.contains(&format!("group_types((FileId(1), {broken}))")));
+
+        // Later errors still point at their functions after the move
+        let text = db.file_text(FILE);
+        assert_eq!(sorted(&diagnostics), full_check(&text));
+        assert_ne!(sorted(&diagnostics), before);
+    }
+
+    #[test]
+    fn test_signature_change_reruns_callers() {
+        let mut db = LoggingDatabase::new(SOURCE);
+        db.diagnostics(FILE);
+        db.executed();
+
+        let old = "int {\n    x * 2";
+        let offset = SOURCE.find(old).unwrap();
+        db.edit(TextEdit::new(
+            offset..offset + old.len(),
+            "bool {\n    x > 2",
+        ));
+        let diagnostics = db.diagnostics(FILE);
+
+        let executed = db.executed();
+        let quadruple = group_of(&db, "quadruple");
+        assert!(executed.contains(&format!("group_types((FileId(1), {quadruple}))")));
+        assert!(!executed.contains(&"function_semantics((FileId(1), \"odd\"))".to_string()));
+        assert!(!diagnostics.is_empty());
+        assert_eq!(sorted(&diagnostics), full_check(&db.file_text(FILE)));
+    }
+
+    #[test]
+    fn test_imports_bring_exports_into_scope() {
+        let main = r#"import util
+import util.{hidden, nope}
+import missing
+fn main() -> int { double(2) }
+fn wrong() -> bool { double(1) }
+"#;
+        let util = "export fn double(x: int) -> int { x * 2 }\nfn hidden() -> int { 1 }";
+        let (mut db, ids) = load_program(&[("app/main.flux", main), ("app/util.flux", util)]);
+
+        assert_eq!(
+            messages(&db.import_errors(ids[0])),
+            [
+                "Semantic error: `hidden` is not exported by module `util`",
+                "Semantic error: Module `util` has no function `nope`",
+                "Semantic error: Cannot find module `missing`",
+            ]
+        );
+        let diagnostics = db.diagnostics(ids[0]);
+        assert!(!diagnostics
+            .iter()
+            .any(|error| matches!(error, FluxError::UnknownIdentifier { .. })));
+        let mismatches: Vec<_> = diagnostics
+            .iter()
+            .filter(|error| matches!(error, FluxError::ReturnTypeMismatch { .. }))
+            .collect();
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(*db.program_files(ids[0]), ids);
+
+        // Editing the imported module rechecks the importer
+        db.set_file_text(ids[1], "export fn double(x: int) -> bool { x > 2 }");
+        let diagnostics = db.diagnostics(ids[0]);
+        let mismatches = diagnostics
+            .iter()
+            .filter(|error| matches!(error, FluxError::ReturnTypeMismatch { .. }))
+            .count();
+        assert_eq!(mismatches, 1);
+        assert!(messages(&diagnostics)
+            .contains(&"Type error: `main` must return int, found bool".to_string()));
+    }
+
+    #[test]
+    fn test_cyclic_imports_are_reported() {
+        let (db, ids) = load_program(&[
+            ("a.flux", "import b\nexport fn f() -> int { g() }"),
+            ("b.flux", "import a\nexport fn g() -> int { f() }"),
+        ]);
+
+        for file in &ids {
+            let errors = messages(&db.import_errors(*file));
+            assert_eq!(errors.len(), 1);
+            assert!(errors[0].contains("Cyclic import"), "{errors:?}");
+        }
+        // Nothing was imported through the cycle
+        assert!(db.diagnostics(ids[0]).iter().any(
+            |error| matches!(error, FluxError::UnknownIdentifier { name, .. } if name == "g")
+        ));
+    }
+
+    #[test]
+    fn test_link_program_joins_modules() {
+        let (db, ids) = load_program(&[
+            ("main.flux", "import util\nfn main() -> int { double(2) }"),
+            ("util.flux", "export fn double(x: int) -> int { x * 2 }"),
+        ]);
+        let program = db.link_program(ids[0]);
+        let names: Vec<_> = program.functions().map(|func| func.name.as_str()).collect();
+        assert_eq!(names, ["main", "util.double"]);
+
+        // Unused functions of a module are left out
+        let (db, ids) = load_program(&[
+            (
+                "main.flux",
+                "import util\nfn main() -> int { 1 }\nfn helper() -> int { 2 }",
+            ),
+            ("util.flux", "fn helper() -> int { 3 }"),
+        ]);
+        let program = db.link_program(ids[0]);
+        let names: Vec<_> = program.functions().map(|func| func.name.as_str()).collect();
+        assert_eq!(names, ["main", "helper"]);
+
+        // A private function keeps to its module, even where the importer
+        // has one of the same name, and locals shadow it
+        let (db, ids) = load_program(&[
+            (
+                "main.flux",
+                "import util\nfn main() -> int { double(2) + helper() }\nfn helper() -> int { 2 }",
+            ),
+            (
+                "util.flux",
+                "export fn double(x: int) -> int { helper() * x + apply(fn(helper: int) { helper }) }\n\
+                 fn helper() -> int { 2 }\nfn apply(f: (int) -> int) -> int { f(1) }",
+            ),
+        ]);
+        let program = db.link_program(ids[0]);
+        let mut names: Vec<_> = program.functions().map(|func| func.name.as_str()).collect();
+        names.sort();
+        assert_eq!(
+            names,
+            ["helper", "main", "util.apply", "util.double", "util.helper"]
+        );
+        let double = program.functions().find(|func| func.name == "util.double");
+        let mut called = Vec::new();
+        referenced_names(&double.unwrap().body, &mut called);
+        // The lambda's `helper` is its parameter
+        for name in ["util.helper", "util.apply", "helper"] {
+            assert!(called.iter().any(|called| called == name), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_link_program_declares_types_of_every_module() {
+        let (db, ids) = load_program(&[
+            (
+                "main.flux",
+                "import util\ntype Score = int\nenum Level { Low }\nfn main() -> Score { level() }",
+            ),
+            (
+                "util.flux",
+                "enum Level { Low, High }\nstruct Point { x: int }\n\
+                 export fn level() -> int { match High { Low => 1, High => 2 } }",
+            ),
+        ]);
+        let program = db.link_program(ids[0]);
+        let names: Vec<_> = program
+            .items
+            .iter()
+            .map(|item| match item {
+                Item::TypeAlias(alias) => alias.name.as_str(),
+                Item::Struct(def) => def.name.as_str(),
+                Item::Enum(def) => def.name.as_str(),
+                Item::Function(func) => func.name.as_str(),
+                Item::Import(_) => "import",
+            })
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "Score",
+                "Level",
+                "util.Level",
+                "util.Point",
+                "main",
+                "util.level"
+            ]
+        );
+        let Some(Item::Enum(level)) = program.items.get(2) else {
+            panic!("util.Level is not an enum");
+        };
+        let variants: Vec<_> = level.variants.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(variants, ["util.Low", "util.High"]);
+    }
+
+    #[test]
+    fn test_prelude_is_in_scope_behind_the_file() {
+        let main = r#"fn main() -> int {
+    let total = #scores |> filter(#active) |> map(square) |> reduce(fn(a: int, b: int) { a + b }, 0)
+    abs(0 - total) + max(1)
+}
+fn max(a: int) -> int { a }
+"#;
+        let (db, ids) = load_program(&[("main.flux", main)]);
+        assert_eq!(messages(&db.diagnostics(ids[0])), Vec::<String>::new());
+
+        let math = db.std_modules()["std.math"];
+        let offset = main.find("abs").unwrap();
+        let (file, span) = db.definition(ids[0], offset + 1).unwrap();
+        assert_eq!(file, math);
+        assert_eq!(&db.file_text(math)[span.start..span.end], "abs");
+        let offset = main.find("max(1)").unwrap();
+        assert_eq!(db.definition(ids[0], offset).unwrap().0, ids[0]);
+
+        let program = db.link_program(ids[0]);
+        let mut names: Vec<_> = program.functions().map(|func| func.name.as_str()).collect();
+        names.sort();
+        assert_eq!(
+            names,
+            [
+                "main",
+                "max",
+                "std.column.filter",
+                "std.column.map",
+                "std.column.reduce",
+                "std.math.abs",
+                "std.math.square"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lambdas_flow_through_prelude_builtins() {
+        let main = r#"fn main() -> int {
+    let offset = 1
+    #scores |> map(fn(x: int) { x + offset }) |> reduce(fn(a: int, b: int) { a + b }, 0)
+}
+fn bad() -> Column<int> {
+    #scores |> map(fn(x: int) { x > 0 })
+}
+"#;
+        let (db, ids) = load_program(&[("main.flux", main)]);
+        assert_eq!(
+            messages(&db.diagnostics(ids[0])),
+            ["Type error: `bad` must return Column<int>, found Column<bool>"]
+        );
+    }
+
+    #[test]
+    fn test_columns_are_checked_against_the_schema() {
+        let main = "fn main() -> Column<int> {\n    #scores |> filter(#active)\n}\n";
+        let (mut db, ids) = load_program(&[("main.flux", main)]);
+        assert_eq!(messages(&db.diagnostics(ids[0])), Vec::<String>::new());
+
+        db.set_schema(Some(Arc::new("scores:int,active:bool".parse().unwrap())));
+        assert_eq!(messages(&db.diagnostics(ids[0])), Vec::<String>::new());
+
+        db.set_schema(Some(Arc::new("scores:int".parse().unwrap())));
+        let diagnostics = db.diagnostics(ids[0]);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0]
+            .to_string()
+            .contains("Unknown column #active"));
+        let span = diagnostics[0].span().unwrap();
+        assert_eq!(&main[span.offset()..span.offset() + span.len()], "#active");
+    }
+
+    #[test]
+    fn test_std_modules_check_cleanly() {
+        let (mut db, _) = load_program(&[("main.flux", "")]);
+        db.set_strictness(Strictness::Lenient);
+        for (module, &file) in db.std_modules().iter() {
+            let diagnostics = db.diagnostics(file);
+            assert!(
+                diagnostics.iter().all(FluxError::is_warning),
+                "{module}: {:?}",
+                messages(&diagnostics)
+            );
+        }
+    }
+}
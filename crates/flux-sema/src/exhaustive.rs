@@ -0,0 +1,209 @@
+//! Exhaustiveness and reachability of `match` arms, after Maranget's
+//! "Warnings for pattern matching": an arm is unreachable when its pattern
+//! is not useful after the arms above it, and a match is exhaustive when `_`
+//! is not useful after all of its arms. Usefulness also yields a witness,
+//! a value no arm matches, which the error shows.
+
+use crate::types::TypeDecls;
+use flux_errors::FluxError;
+use std::fmt;
+
+/// Head constructor of a value
+#[derive(Debug, Clone, PartialEq)]
+enum Ctor {
+    Variant(String),
+    Bool(bool),
+    Int(i64),
+    String(String),
+}
+
+/// A pattern reduced to what matching depends on: bindings are wildcards
+#[derive(Debug, Clone, PartialEq)]
+enum Pat {
+    Wild,
+    Ctor(Ctor, Vec<Pat>),
+}
+
+impl fmt::Display for Pat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Pat::Wild => write!(f, "_"),
+            Pat::Ctor(Ctor::Bool(value), _) => write!(f, "{}", value),
+            Pat::Ctor(Ctor::Int(value), _) => write!(f, "{}", value),
+            Pat::Ctor(Ctor::String(value), _) => write!(f, "{:?}", value),
+            Pat::Ctor(Ctor::Variant(name), args) if args.is_empty() => write!(f, "{}", name),
+            Pat::Ctor(Ctor::Variant(name), args) => {
+                write!(f, "{}(", name)?;
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", arg)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+/// Check the arms of a well-typed `match`: arms that can never be reached
+/// are warnings, and a value no arm matches is an error at `span`
+pub fn check_match(
+    arms: &[flux_syntax::MatchArm],
+    decls: &TypeDecls,
+    span: flux_errors::Span,
+) -> Vec<FluxError> {
+    let matrix = Matrix { decls };
+    let mut errors = Vec::new();
+    let mut rows: Vec<Vec<Pat>> = Vec::with_capacity(arms.len());
+    for arm in arms {
+        let pat = matrix.lower(&arm.pattern);
+        if matrix.useful(&rows, std::slice::from_ref(&pat)).is_none() {
+            errors.push(FluxError::Warning {
+                message: "Unreachable match arm: the arms above cover everything it matches"
+                    .to_string(),
+                span: arm.pattern.span().to_source_span(),
+                help: Some("Remove this arm or move it above the arms covering it".to_string()),
+            });
+        }
+        rows.push(vec![pat]);
+    }
+    if let Some(witness) = matrix.useful(&rows, &[Pat::Wild]) {
+        errors.push(FluxError::TypeError {
+            message: format!("Non-exhaustive match: `{}` is not covered", witness[0]),
+            span: span.to_source_span(),
+        });
+    }
+    errors
+}
+
+struct Matrix<'a> {
+    decls: &'a TypeDecls,
+}
+
+impl Matrix<'_> {
+    fn lower(&self, pattern: &flux_syntax::Pattern) -> Pat {
+        use flux_syntax::Pattern;
+        match pattern {
+            Pattern::Wildcard { .. } => Pat::Wild,
+            Pattern::Name { name, .. } if self.decls.variant(name).is_some() => {
+                Pat::Ctor(Ctor::Variant(name.clone()), Vec::new())
+            }
+            Pattern::Name { .. } => Pat::Wild,
+            Pattern::Int { value, .. } => Pat::Ctor(Ctor::Int(*value), Vec::new()),
+            Pattern::Bool { value, .. } => Pat::Ctor(Ctor::Bool(*value), Vec::new()),
+            Pattern::String { value, .. } => Pat::Ctor(Ctor::String(value.clone()), Vec::new()),
+            Pattern::Variant { name, args, .. } => Pat::Ctor(
+                Ctor::Variant(name.clone()),
+                args.iter().map(|arg| self.lower(arg)).collect(),
+            ),
+        }
+    }
+
+    fn arity(&self, ctor: &Ctor) -> usize {
+        match ctor {
+            Ctor::Variant(name) => self
+                .decls
+                .variant(name)
+                .map_or(0, |(_, _, variant)| variant.fields.len()),
+            _ => 0,
+        }
+    }
+
+    /// Every constructor of the type `ctor` belongs to, or `None` for types
+    /// with too many values to list
+    fn signature(&self, ctor: &Ctor) -> Option<Vec<Ctor>> {
+        match ctor {
+            Ctor::Variant(name) => {
+                let (enum_name, _, _) = self.decls.variant(name)?;
+                Some(
+                    self.decls.enums[enum_name]
+                        .iter()
+                        .map(|variant| Ctor::Variant(variant.name.clone()))
+                        .collect(),
+                )
+            }
+            Ctor::Bool(_) => Some(vec![Ctor::Bool(false), Ctor::Bool(true)]),
+            Ctor::Int(_) | Ctor::String(_) => None,
+        }
+    }
+
+    /// Whether a value matched by `row` is matched by none of `rows`. If
+    /// so, returns such a value, one pattern per column.
+    fn useful(&self, rows: &[Vec<Pat>], row: &[Pat]) -> Option<Vec<Pat>> {
+        let Some((head, rest)) = row.split_first() else {
+            return rows.is_empty().then(Vec::new);
+        };
+
+        if let Pat::Ctor(ctor, args) = head {
+            let mut row: Vec<Pat> = args.clone();
+            row.extend_from_slice(rest);
+            let witness = self.useful(&self.specialize(rows, ctor), &row)?;
+            return Some(Self::rebuild(ctor, args.len(), witness));
+        }
+
+        let heads: Vec<&Ctor> = rows
+            .iter()
+            .filter_map(|row| match &row[0] {
+                Pat::Ctor(ctor, _) => Some(ctor),
+                Pat::Wild => None,
+            })
+            .collect();
+        let signature = heads.first().and_then(|ctor| self.signature(ctor));
+        let missing = signature
+            .as_ref()
+            .and_then(|signature| signature.iter().find(|ctor| !heads.contains(ctor)));
+
+        match (&signature, missing) {
+            // Every constructor appears, so `_` is useful when one of them is
+            (Some(signature), None) => signature.iter().find_map(|ctor| {
+                let arity = self.arity(ctor);
+                let mut row = vec![Pat::Wild; arity];
+                row.extend_from_slice(rest);
+                let witness = self.useful(&self.specialize(rows, ctor), &row)?;
+                Some(Self::rebuild(ctor, arity, witness))
+            }),
+            // Some value starts with a constructor no row does, so only the
+            // rows starting with a wildcard matter
+            _ => {
+                let defaults: Vec<Vec<Pat>> = rows
+                    .iter()
+                    .filter(|row| row[0] == Pat::Wild)
+                    .map(|row| row[1..].to_vec())
+                    .collect();
+                let mut witness = self.useful(&defaults, rest)?;
+                let head = match missing {
+                    Some(ctor) => Pat::Ctor(ctor.clone(), vec![Pat::Wild; self.arity(ctor)]),
+                    None => Pat::Wild,
+                };
+                witness.insert(0, head);
+                Some(witness)
+            }
+        }
+    }
+
+    /// Rows matching a value built with `ctor`, its fields in place of the
+    /// first column
+    fn specialize(&self, rows: &[Vec<Pat>], ctor: &Ctor) -> Vec<Vec<Pat>> {
+        let arity = self.arity(ctor);
+        rows.iter()
+            .filter_map(|row| {
+                let mut specialized = match &row[0] {
+                    Pat::Ctor(head, args) if head == ctor => args.clone(),
+                    Pat::Ctor(..) => return None,
+                    Pat::Wild => vec![Pat::Wild; arity],
+                };
+                specialized.extend_from_slice(&row[1..]);
+                Some(specialized)
+            })
+            .collect()
+    }
+
+    /// Fold the first `arity` patterns of a witness back under `ctor`
+    fn rebuild(ctor: &Ctor, arity: usize, mut witness: Vec<Pat>) -> Vec<Pat> {
+        let rest = witness.split_off(arity);
+        let mut rebuilt = vec![Pat::Ctor(ctor.clone(), witness)];
+        rebuilt.extend(rest);
+        rebuilt
+    }
+}
@@ -1,9 +1,12 @@
 pub mod checker;
+pub mod db;
+pub mod exhaustive;
 pub mod symbol;
 pub mod types;
 pub mod vfs;
 
-pub use checker::check_semantics;
+pub use checker::{check_semantics, check_semantics_with, Strictness};
+pub use db::{FluxDatabase, SemaDatabase, SourceDatabase};
 pub use symbol::*;
 pub use types::*;
 pub use vfs::*;
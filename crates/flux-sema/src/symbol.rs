@@ -1,7 +1,9 @@
-use crate::types::TypeInfo;
+use crate::types::{Schema, TypeInfo};
 use crate::vfs::FileId;
 use dashmap::DashMap;
 use flux_errors::Span;
+use flux_syntax::Expr;
+use parking_lot::RwLock;
 
 /// Symbol information for variables, functions, etc.
 #[derive(Debug, Clone)]
@@ -19,6 +21,9 @@ pub enum SymbolKind {
     Variable,
     Parameter,
     Type,
+    /// Variant of an enum, typed as its constructor
+    Variant,
+    Column,
 }
 
 /// Symbol table for tracking symbols across files
@@ -44,12 +49,14 @@ impl SymbolTable {
             .unwrap_or_default()
     }
 
-    /// Find symbol at a specific position (for hover/go-to-definition)
+    /// Find symbol at a specific position (for hover/go-to-definition).
+    /// When symbols nest (a column inside a function), the innermost wins.
     pub fn find_symbol_at_position(&self, file_id: FileId, offset: usize) -> Option<Symbol> {
         let symbols = self.get_symbols(file_id);
         symbols
             .into_iter()
-            .find(|sym| sym.span.start <= offset && offset <= sym.span.end)
+            .filter(|sym| sym.span.start <= offset && offset <= sym.span.end)
+            .min_by_key(|sym| sym.span.end - sym.span.start)
     }
 
     pub fn clear(&self, file_id: FileId) {
@@ -66,24 +73,74 @@ impl Default for SymbolTable {
 /// Symbol bridge - connects LSP queries to semantic information
 pub struct SymbolBridge {
     symbol_table: SymbolTable,
+    schema: RwLock<Schema>,
 }
 
 impl SymbolBridge {
     pub fn new() -> Self {
         Self {
             symbol_table: SymbolTable::new(),
+            schema: RwLock::new(Schema::new()),
         }
     }
 
+    /// Set the input schema that column references are typed against
+    pub fn set_schema(&self, schema: Schema) {
+        *self.schema.write() = schema;
+    }
+
     /// Analyze a file and populate symbol table
     pub fn analyze_file(&self, file_id: FileId, ast: &flux_syntax::SourceFile) {
         self.symbol_table.clear(file_id);
+        let (decls, _) = crate::types::declare_types(ast);
 
         for item in &ast.items {
             match item {
+                flux_syntax::Item::Import(_) => {}
+                flux_syntax::Item::TypeAlias(flux_syntax::TypeAlias { name, span, .. })
+                | flux_syntax::Item::Struct(flux_syntax::Struct { name, span, .. }) => {
+                    self.symbol_table.insert(
+                        file_id,
+                        Symbol {
+                            name: name.clone(),
+                            ty: decls.types.get(name).cloned().unwrap_or(TypeInfo::Error),
+                            span: *span,
+                            file_id,
+                            kind: SymbolKind::Type,
+                        },
+                    );
+                }
+                flux_syntax::Item::Enum(def) => {
+                    self.symbol_table.insert(
+                        file_id,
+                        Symbol {
+                            name: def.name.clone(),
+                            ty: decls
+                                .types
+                                .get(&def.name)
+                                .cloned()
+                                .unwrap_or(TypeInfo::Error),
+                            span: def.span,
+                            file_id,
+                            kind: SymbolKind::Type,
+                        },
+                    );
+                    for variant in &def.variants {
+                        self.symbol_table.insert(
+                            file_id,
+                            Symbol {
+                                name: variant.name.clone(),
+                                ty: decls.constructor(&variant.name).unwrap_or(TypeInfo::Error),
+                                span: variant.span,
+                                file_id,
+                                kind: SymbolKind::Variant,
+                            },
+                        );
+                    }
+                }
                 flux_syntax::Item::Function(func) => {
                     let ty = if let Some(ret_ty) = &func.return_type {
-                        self.type_from_ast(ret_ty)
+                        TypeInfo::from_ast(ret_ty)
                     } else {
                         TypeInfo::Unknown
                     };
@@ -98,22 +155,30 @@ impl SymbolBridge {
                             kind: SymbolKind::Function,
                         },
                     );
+
+                    let mut columns = Vec::new();
+                    collect_columns(&func.body, &mut columns);
+                    let schema = self.schema.read();
+                    for (name, span) in columns {
+                        let ty = schema
+                            .column_type(&name)
+                            .unwrap_or_else(|| TypeInfo::Column(Box::new(TypeInfo::Unknown)));
+                        self.symbol_table.insert(
+                            file_id,
+                            Symbol {
+                                name: format!("#{}", name),
+                                ty,
+                                span,
+                                file_id,
+                                kind: SymbolKind::Column,
+                            },
+                        );
+                    }
                 }
             }
         }
     }
 
-    /// Convert AST type to TypeInfo
-    fn type_from_ast(&self, ty: &flux_syntax::Type) -> TypeInfo {
-        match ty {
-            flux_syntax::Type::Int(_) => TypeInfo::Int,
-            flux_syntax::Type::String(_) => TypeInfo::String,
-            flux_syntax::Type::Bool(_) => TypeInfo::Bool,
-            flux_syntax::Type::Float(_) => TypeInfo::Float,
-            flux_syntax::Type::Named { name, .. } => TypeInfo::Named { name: name.clone() },
-        }
-    }
-
     /// Find symbol at position (for LSP hover)
     pub fn symbol_at_position(&self, file_id: FileId, offset: usize) -> Option<Symbol> {
         self.symbol_table.find_symbol_at_position(file_id, offset)
@@ -131,6 +196,78 @@ impl Default for SymbolBridge {
     }
 }
 
+/// Collect every column reference in an expression
+fn collect_columns(expr: &Expr, out: &mut Vec<(String, Span)>) {
+    match expr {
+        Expr::Column { name, span } => out.push((name.clone(), *span)),
+        Expr::Unary { operand, .. }
+        | Expr::Try { operand, .. }
+        | Expr::Field { base: operand, .. }
+        | Expr::Lambda { body: operand, .. } => collect_columns(operand, out),
+        Expr::Record { fields, .. } => {
+            for field in fields {
+                collect_columns(&field.value, out);
+            }
+        }
+        Expr::Binary { left, right, .. }
+        | Expr::Pipeline { left, right, .. }
+        | Expr::Index {
+            base: left,
+            index: right,
+            ..
+        } => {
+            collect_columns(left, out);
+            collect_columns(right, out);
+        }
+        Expr::List { elems, .. } | Expr::Tuple { elems, .. } => {
+            for elem in elems {
+                collect_columns(elem, out);
+            }
+        }
+        Expr::Call { func, args, .. } => {
+            collect_columns(func, out);
+            for arg in args {
+                collect_columns(arg, out);
+            }
+        }
+        Expr::Let { value, body, .. } => {
+            collect_columns(value, out);
+            collect_columns(body, out);
+        }
+        Expr::If {
+            cond,
+            then_branch,
+            else_branch,
+            ..
+        } => {
+            collect_columns(cond, out);
+            collect_columns(then_branch, out);
+            collect_columns(else_branch, out);
+        }
+        Expr::Block { stmts, .. } => {
+            for stmt in stmts {
+                collect_columns(stmt, out);
+            }
+        }
+        Expr::Match {
+            scrutinee, arms, ..
+        } => {
+            collect_columns(scrutinee, out);
+            for arm in arms {
+                collect_columns(&arm.body, out);
+            }
+        }
+        Expr::Return { value, .. } => collect_columns(value, out),
+        Expr::Int { .. }
+        | Expr::Float { .. }
+        | Expr::Bool { .. }
+        | Expr::String { .. }
+        | Expr::Temporal { .. }
+        | Expr::Var { .. }
+        | Expr::Error { .. } => {}
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -154,4 +291,27 @@ mod tests {
         assert!(found.is_some());
         assert_eq!(found.unwrap().name, "test");
     }
+
+    #[test]
+    fn test_column_symbol_typed_from_schema() {
+        let source = "fn f() -> int { #active |> count }";
+        let ast = flux_syntax::parse(source).unwrap();
+        let file_id = FileId(1);
+
+        let bridge = SymbolBridge::new();
+        let mut schema = Schema::new();
+        schema.insert("active".to_string(), TypeInfo::Bool);
+        bridge.set_schema(schema);
+        bridge.analyze_file(file_id, &ast);
+
+        // Offset 18 is inside `#active`, which is nested inside `f`
+        let symbol = bridge.symbol_at_position(file_id, 18).unwrap();
+        assert_eq!(symbol.kind, SymbolKind::Column);
+        assert_eq!(symbol.name, "#active");
+        assert_eq!(symbol.ty.to_string(), "Column<bool>");
+
+        // Offsets outside the column still resolve to the function
+        let symbol = bridge.symbol_at_position(file_id, 3).unwrap();
+        assert_eq!(symbol.kind, SymbolKind::Function);
+    }
 }
@@ -1,5 +1,7 @@
-use std::collections::HashMap;
+use flux_syntax::TemporalKind;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::sync::OnceLock;
 
 /// Type information for Flux types
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -8,14 +10,246 @@ pub enum TypeInfo {
     String,
     Bool,
     Float,
+    Date,
+    Time,
+    /// Date and time in a zone
+    DateTime,
+    /// Absolute UTC time
+    Timestamp,
+    Duration,
     Named {
         name: String,
     },
+    /// Record with fields in declaration order. A `struct` is nominal and
+    /// carries its name and type arguments; a record type without a name is
+    /// structural.
+    Record {
+        name: Option<String>,
+        args: Vec<TypeInfo>,
+        fields: Vec<(String, TypeInfo)>,
+    },
+    /// Enum declared with `enum`, with its type arguments; enums are
+    /// nominal, so the variants are looked up in the file's [`TypeDecls`]
+    Enum {
+        name: String,
+        args: Vec<TypeInfo>,
+    },
     Function {
         params: Vec<TypeInfo>,
         ret: Box<TypeInfo>,
     },
+    /// A column of the input schema holding values of the inner type
+    Column(Box<TypeInfo>),
+    /// `List<T>`: any number of values of one type
+    List(Box<TypeInfo>),
+    /// `(A, B, ..)`: a fixed number of values, each of its own type
+    Tuple(Vec<TypeInfo>),
+    /// Type parameter of the generic function or type being checked. It
+    /// stands for any type, so it is only equal to itself.
+    Param(String),
+    /// Inference variable, solved by unification
+    Var(u32),
     Unknown,
+    /// Type of an expression that failed to type check; compatible with
+    /// everything so the failure is reported only once
+    Error,
+}
+
+impl TypeInfo {
+    /// Convert an AST type annotation
+    pub fn from_ast(ty: &flux_syntax::Type) -> TypeInfo {
+        match ty {
+            flux_syntax::Type::Int(_) => TypeInfo::Int,
+            flux_syntax::Type::String(_) => TypeInfo::String,
+            flux_syntax::Type::Bool(_) => TypeInfo::Bool,
+            flux_syntax::Type::Float(_) => TypeInfo::Float,
+            flux_syntax::Type::Named { name, .. } => TypeInfo::from_name(name),
+            flux_syntax::Type::Record { fields, .. } => TypeInfo::Record {
+                name: None,
+                args: Vec::new(),
+                fields: fields
+                    .iter()
+                    .map(|field| (field.name.clone(), TypeInfo::from_ast(&field.ty)))
+                    .collect(),
+            },
+            flux_syntax::Type::List { elem, .. } => {
+                TypeInfo::List(Box::new(TypeInfo::from_ast(elem)))
+            }
+            flux_syntax::Type::Tuple { elems, .. } => {
+                TypeInfo::Tuple(elems.iter().map(TypeInfo::from_ast).collect())
+            }
+            flux_syntax::Type::Function { params, ret, .. } => TypeInfo::Function {
+                params: params.iter().map(TypeInfo::from_ast).collect(),
+                ret: Box::new(TypeInfo::from_ast(ret)),
+            },
+        }
+    }
+
+    /// Type of the field `name` of a record
+    pub fn field(&self, name: &str) -> Option<&TypeInfo> {
+        match self {
+            TypeInfo::Record { fields, .. } => fields
+                .iter()
+                .find(|(field, _)| field == name)
+                .map(|(_, ty)| ty),
+            _ => None,
+        }
+    }
+
+    /// The type of temporal values of `kind`
+    pub fn temporal(kind: TemporalKind) -> TypeInfo {
+        match kind {
+            TemporalKind::Date => TypeInfo::Date,
+            TemporalKind::Time => TypeInfo::Time,
+            TemporalKind::DateTime => TypeInfo::DateTime,
+            TemporalKind::Timestamp => TypeInfo::Timestamp,
+            TemporalKind::Duration => TypeInfo::Duration,
+        }
+    }
+
+    pub fn temporal_kind(&self) -> Option<TemporalKind> {
+        match self {
+            TypeInfo::Date => Some(TemporalKind::Date),
+            TypeInfo::Time => Some(TemporalKind::Time),
+            TypeInfo::DateTime => Some(TemporalKind::DateTime),
+            TypeInfo::Timestamp => Some(TemporalKind::Timestamp),
+            TypeInfo::Duration => Some(TemporalKind::Duration),
+            _ => None,
+        }
+    }
+
+    /// `Date`, `Time`, `DateTime` or `Timestamp`: a point in time that a
+    /// Duration moves
+    fn is_time_point(&self) -> bool {
+        matches!(
+            self,
+            TypeInfo::Date | TypeInfo::Time | TypeInfo::DateTime | TypeInfo::Timestamp
+        )
+    }
+
+    /// Signature of a function definition; missing annotations become `Unknown`
+    pub fn from_function(func: &flux_syntax::Function) -> TypeInfo {
+        let annotation =
+            |ty: Option<&flux_syntax::Type>| ty.map_or(TypeInfo::Unknown, Self::from_ast);
+        TypeInfo::Function {
+            params: func
+                .params
+                .iter()
+                .map(|param| annotation(param.ty.as_ref()))
+                .collect(),
+            ret: Box::new(annotation(func.return_type.as_ref())),
+        }
+    }
+
+    /// The types directly inside this one
+    fn components(&self) -> Vec<&TypeInfo> {
+        match self {
+            TypeInfo::Function { params, ret } => params.iter().chain([&**ret]).collect(),
+            TypeInfo::Column(elem) | TypeInfo::List(elem) => vec![elem],
+            TypeInfo::Tuple(elems) | TypeInfo::Enum { args: elems, .. } => elems.iter().collect(),
+            TypeInfo::Record { args, fields, .. } => {
+                args.iter().chain(fields.iter().map(|(_, ty)| ty)).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Rebuild the type with every part `replacement` gives a replacement
+    /// for replaced; the parts of a replaced type are not visited
+    fn replace(&self, replacement: &mut impl FnMut(&TypeInfo) -> Option<TypeInfo>) -> TypeInfo {
+        if let Some(replaced) = replacement(self) {
+            return replaced;
+        }
+        fn each(
+            types: &[TypeInfo],
+            replacement: &mut impl FnMut(&TypeInfo) -> Option<TypeInfo>,
+        ) -> Vec<TypeInfo> {
+            types.iter().map(|ty| ty.replace(replacement)).collect()
+        }
+        match self {
+            TypeInfo::Function { params, ret } => TypeInfo::Function {
+                params: each(params, replacement),
+                ret: Box::new(ret.replace(replacement)),
+            },
+            TypeInfo::Column(elem) => TypeInfo::Column(Box::new(elem.replace(replacement))),
+            TypeInfo::List(elem) => TypeInfo::List(Box::new(elem.replace(replacement))),
+            TypeInfo::Tuple(elems) => TypeInfo::Tuple(each(elems, replacement)),
+            TypeInfo::Enum { name, args } => TypeInfo::Enum {
+                name: name.clone(),
+                args: each(args, replacement),
+            },
+            TypeInfo::Record { name, args, fields } => TypeInfo::Record {
+                name: name.clone(),
+                args: each(args, replacement),
+                fields: fields
+                    .iter()
+                    .map(|(field, ty)| (field.clone(), ty.replace(replacement)))
+                    .collect(),
+            },
+            _ => self.clone(),
+        }
+    }
+
+    /// Inference variables occurring in the type, in order of appearance
+    pub fn type_vars(&self) -> Vec<u32> {
+        fn collect(ty: &TypeInfo, out: &mut Vec<u32>) {
+            match ty {
+                TypeInfo::Var(var) if !out.contains(var) => out.push(*var),
+                _ => {
+                    for component in ty.components() {
+                        collect(component, out);
+                    }
+                }
+            }
+        }
+
+        let mut vars = Vec::new();
+        collect(self, &mut vars);
+        vars
+    }
+
+    /// Whether the type is fully known: no inference variables, `Unknown` or
+    /// `Error`
+    pub fn is_concrete(&self) -> bool {
+        match self {
+            TypeInfo::Var(_) | TypeInfo::Unknown | TypeInfo::Error => false,
+            _ => self.components().into_iter().all(TypeInfo::is_concrete),
+        }
+    }
+
+    /// Replace inference variables according to `mapping`
+    pub fn substitute(&self, mapping: &HashMap<u32, TypeInfo>) -> TypeInfo {
+        self.replace(&mut |ty| match ty {
+            TypeInfo::Var(var) => mapping.get(var).cloned(),
+            _ => None,
+        })
+    }
+
+    /// Replace type parameters according to `mapping`
+    pub fn substitute_params(&self, mapping: &HashMap<String, TypeInfo>) -> TypeInfo {
+        self.replace(&mut |ty| match ty {
+            TypeInfo::Param(name) => mapping.get(name).cloned(),
+            _ => None,
+        })
+    }
+
+    /// Resolve a primitive or named type from its source spelling
+    pub fn from_name(name: &str) -> TypeInfo {
+        match name {
+            "int" => TypeInfo::Int,
+            "string" => TypeInfo::String,
+            "bool" => TypeInfo::Bool,
+            "float" => TypeInfo::Float,
+            "Date" => TypeInfo::Date,
+            "Time" => TypeInfo::Time,
+            "DateTime" => TypeInfo::DateTime,
+            "Timestamp" => TypeInfo::Timestamp,
+            "Duration" => TypeInfo::Duration,
+            _ => TypeInfo::Named {
+                name: name.to_string(),
+            },
+        }
+    }
 }
 
 impl fmt::Display for TypeInfo {
@@ -25,7 +259,46 @@ impl fmt::Display for TypeInfo {
             TypeInfo::String => write!(f, "string"),
             TypeInfo::Bool => write!(f, "bool"),
             TypeInfo::Float => write!(f, "float"),
-            TypeInfo::Named { name } => write!(f, "{}", name),
+            TypeInfo::Date => write!(f, "Date"),
+            TypeInfo::Time => write!(f, "Time"),
+            TypeInfo::DateTime => write!(f, "DateTime"),
+            TypeInfo::Timestamp => write!(f, "Timestamp"),
+            TypeInfo::Duration => write!(f, "Duration"),
+            TypeInfo::Named { name } | TypeInfo::Param(name) => write!(f, "{}", name),
+            TypeInfo::Enum { name, args }
+            | TypeInfo::Record {
+                name: Some(name),
+                args,
+                ..
+            } => {
+                write!(f, "{}", name)?;
+                if !args.is_empty() {
+                    write!(f, "<")?;
+                    for (i, arg) in args.iter().enumerate() {
+                        if i > 0 {
+                            write!(f, ", ")?;
+                        }
+                        write!(f, "{}", arg)?;
+                    }
+                    write!(f, ">")?;
+                }
+                Ok(())
+            }
+            TypeInfo::Record {
+                name: None, fields, ..
+            } if fields.is_empty() => write!(f, "{{}}"),
+            TypeInfo::Record {
+                name: None, fields, ..
+            } => {
+                write!(f, "{{ ")?;
+                for (i, (field, ty)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", field, ty)?;
+                }
+                write!(f, " }}")
+            }
             TypeInfo::Function { params, ret } => {
                 write!(f, "(")?;
                 for (i, param) in params.iter().enumerate() {
@@ -36,300 +309,3748 @@ impl fmt::Display for TypeInfo {
                 }
                 write!(f, ") -> {}", ret)
             }
+            TypeInfo::Column(elem) => write!(f, "Column<{}>", elem),
+            TypeInfo::List(elem) => write!(f, "List<{}>", elem),
+            TypeInfo::Tuple(elems) => {
+                write!(f, "(")?;
+                for (i, elem) in elems.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", elem)?;
+                }
+                write!(f, ")")
+            }
+            TypeInfo::Var(var) if *var < 26 => write!(f, "'{}", (b'a' + *var as u8) as char),
+            TypeInfo::Var(var) => write!(f, "'t{}", var),
             TypeInfo::Unknown => write!(f, "?"),
+            TypeInfo::Error => write!(f, "{{error}}"),
+        }
+    }
+}
+
+/// A possibly polymorphic type: `vars` are quantified and replaced with
+/// fresh inference variables at every use, and so are the type parameters
+/// `params` of a generic function
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeScheme {
+    pub vars: Vec<u32>,
+    pub params: Vec<String>,
+    pub ty: TypeInfo,
+}
+
+impl TypeScheme {
+    /// A scheme without quantified variables
+    pub fn mono(ty: TypeInfo) -> Self {
+        Self {
+            vars: Vec::new(),
+            params: Vec::new(),
+            ty,
+        }
+    }
+
+    /// A scheme quantifying every variable of `ty`, numbered from 0 in
+    /// order of appearance so equal signatures compare equal
+    pub fn closed(ty: TypeInfo) -> Self {
+        let vars = ty.type_vars();
+        let numbering = vars
+            .iter()
+            .enumerate()
+            .map(|(i, var)| (*var, TypeInfo::Var(i as u32)))
+            .collect();
+        Self {
+            vars: (0..vars.len() as u32).collect(),
+            params: Vec::new(),
+            ty: ty.substitute(&numbering),
+        }
+    }
+}
+
+impl fmt::Display for TypeScheme {
+    /// Quantified variables are renamed `'a`, `'b`, ... in order
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names = self
+            .vars
+            .iter()
+            .enumerate()
+            .map(|(i, var)| (*var, TypeInfo::Var(i as u32)))
+            .collect();
+        write!(f, "{}", self.ty.substitute(&names))
+    }
+}
+
+/// Input schema: the columns `#column` references resolve against
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Schema {
+    columns: HashMap<String, TypeInfo>,
+}
+
+impl Schema {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, name: String, ty: TypeInfo) {
+        self.columns.insert(name, ty);
+    }
+
+    /// Element type of a column
+    pub fn get(&self, name: &str) -> Option<&TypeInfo> {
+        self.columns.get(name)
+    }
+
+    /// Type of a `#name` reference: `Column<T>` when the schema declares the
+    /// column, `None` when it does not
+    pub fn column_type(&self, name: &str) -> Option<TypeInfo> {
+        self.get(name)
+            .map(|elem| TypeInfo::Column(Box::new(elem.clone())))
+    }
+}
+
+/// `name:type` pairs separated by commas, like `score:int,active:bool`
+impl std::str::FromStr for Schema {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut schema = Schema::new();
+        for column in s
+            .split(',')
+            .map(str::trim)
+            .filter(|column| !column.is_empty())
+        {
+            let Some((name, ty)) = column.split_once(':') else {
+                return Err(format!(
+                    "Column `{}` has no type (expected `name:type`)",
+                    column
+                ));
+            };
+            schema.insert(name.trim().to_string(), TypeInfo::from_name(ty.trim()));
         }
+        Ok(schema)
     }
 }
 
-/// Type environment mapping variable names to their types
+/// Type environment mapping variable names to their type schemes
 #[derive(Debug, Clone)]
 pub struct TypeEnv {
-    bindings: HashMap<String, TypeInfo>,
+    bindings: HashMap<String, TypeScheme>,
+    schema: Option<Schema>,
+    /// Types declared with `type`, `struct` and `enum`
+    decls: TypeDecls,
+    /// Type parameters of the generic function being checked
+    type_params: Vec<String>,
+    /// Names bound by `let` to a Duration known to be a whole number of
+    /// days, which may move a Date
+    whole_days: HashSet<String>,
 }
 
 impl TypeEnv {
     pub fn new() -> Self {
         Self {
             bindings: HashMap::new(),
+            schema: None,
+            decls: TypeDecls::default(),
+            type_params: Vec::new(),
+            whole_days: HashSet::new(),
         }
     }
 
+    /// Environment with every function of a file bound to its signature
+    pub fn from_source_file(ast: &flux_syntax::SourceFile) -> Self {
+        let mut env = Self::new();
+        for func in ast.functions() {
+            env.insert(func.name.clone(), TypeInfo::from_function(func));
+        }
+        env
+    }
+
+    /// Bind `name` to a monomorphic type
     pub fn insert(&mut self, name: String, ty: TypeInfo) {
-        self.bindings.insert(name, ty);
+        self.insert_scheme(name, TypeScheme::mono(ty));
+    }
+
+    pub fn insert_scheme(&mut self, name: String, scheme: TypeScheme) {
+        self.whole_days.remove(&name);
+        self.bindings.insert(name, scheme);
     }
 
+    /// Type of `name`, with any quantified variables left uninstantiated
     pub fn get(&self, name: &str) -> Option<&TypeInfo> {
+        self.bindings.get(name).map(|scheme| &scheme.ty)
+    }
+
+    pub fn get_scheme(&self, name: &str) -> Option<&TypeScheme> {
         self.bindings.get(name)
     }
-}
 
-impl Default for TypeEnv {
-    fn default() -> Self {
-        Self::new()
+    /// Resolve column references against `schema`. Without a schema every
+    /// column is accepted with an unknown element type.
+    pub fn set_schema(&mut self, schema: Schema) {
+        self.schema = Some(schema);
+    }
+
+    pub fn schema(&self) -> Option<&Schema> {
+        self.schema.as_ref()
+    }
+
+    /// Resolve type annotations against the file's declared types; see
+    /// [`declare_types`]
+    pub fn set_types(&mut self, decls: TypeDecls) {
+        self.decls = decls;
+    }
+
+    pub fn decls(&self) -> &TypeDecls {
+        &self.decls
+    }
+
+    /// A type declared with `type`, `struct` or `enum`
+    pub fn get_type(&self, name: &str) -> Option<&TypeInfo> {
+        self.decls.types.get(name)
+    }
+
+    /// The environment inside `func`, where its type parameters name types
+    pub fn with_type_params(&self, func: &flux_syntax::Function) -> Self {
+        let mut env = self.clone();
+        env.type_params = param_names(&func.type_params);
+        env
     }
 }
 
-/// Type checker for Flux
-pub struct TypeChecker {}
+/// Type names of annotations inside a function, resolved against the
+/// declared types and the function's type parameters
+struct EnvScope<'a> {
+    env: &'a TypeEnv,
+    errors: &'a mut Vec<flux_errors::FluxError>,
+}
 
-impl TypeChecker {
-    pub fn new() -> Self {
-        Self {}
+impl TypeScope for EnvScope<'_> {
+    fn declared(&mut self, name: &str) -> Option<(TypeInfo, Vec<String>)> {
+        let decls = self.env.decls();
+        let ty = decls.types.get(name)?.clone();
+        Some((ty, decls.type_params(name).to_vec()))
     }
 
-    /// Infer the type of an expression given an environment
-    pub fn infer_expr(
-        &self,
-        expr: &flux_syntax::Expr,
-        env: &TypeEnv,
-    ) -> flux_errors::Result<TypeInfo> {
-        use flux_syntax::Expr;
-        match expr {
-            Expr::Int { .. } => Ok(TypeInfo::Int),
-            Expr::Float { .. } => Ok(TypeInfo::Float),
-            Expr::Bool { .. } => Ok(TypeInfo::Bool),
-            Expr::String { .. } => Ok(TypeInfo::String),
-            Expr::Var { name, span } => {
-                env.get(name)
-                    .cloned()
-                    .ok_or_else(|| flux_errors::FluxError::UnknownIdentifier {
-                        name: name.clone(),
-                        span: span.to_source_span(),
-                    })
-            }
-            Expr::Binary {
-                op,
-                left,
-                right,
-                span,
-            } => {
-                let left_ty = self.infer_expr(left, env)?;
-                let right_ty = self.infer_expr(right, env)?;
-                self.check_binary_op(*op, left_ty, right_ty, *span)
-            }
-            Expr::Let {
-                name, value, body, ..
-            } => {
-                let value_ty = self.infer_expr(value, env)?;
-                let mut new_env = env.clone();
-                new_env.insert(name.clone(), value_ty);
-                self.infer_expr(body, &new_env)
-            }
-            Expr::Call { func, args, span } => self.check_call(func, args, env, *span),
-            Expr::Block { stmts, .. } => self.infer_block(stmts, env),
-            Expr::Return { value, .. } => self.infer_expr(value, env),
-        }
+    fn is_param(&self, name: &str) -> bool {
+        self.env.type_params.iter().any(|param| param == name)
     }
 
-    /// Check binary operation types
-    fn check_binary_op(
-        &self,
-        op: flux_syntax::BinOp,
-        left: TypeInfo,
-        right: TypeInfo,
-        span: flux_errors::Span,
-    ) -> flux_errors::Result<TypeInfo> {
-        use flux_syntax::BinOp;
-        match (op, &left, &right) {
-            // Arithmetic ops: both operands must be same numeric type
-            (BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div, TypeInfo::Int, TypeInfo::Int) => {
-                Ok(TypeInfo::Int)
-            }
-            (
-                BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div,
-                TypeInfo::Float,
-                TypeInfo::Float,
-            ) => Ok(TypeInfo::Float),
-            (BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div, _, _) => {
-                Err(flux_errors::FluxError::TypeError {
-                    message: format!(
-                        "Cannot apply {:?} to {} and {}. Both operands must be the same numeric type.",
-                        op, left, right
-                    ),
-                    span: span.to_source_span(),
-                })
+    fn report(&mut self, message: String, span: flux_errors::Span) {
+        self.errors.push(flux_errors::FluxError::TypeError {
+            message,
+            span: span.to_source_span(),
+        });
+    }
+}
+
+/// Where the names in a type annotation are looked up
+trait TypeScope {
+    /// The type declared as `name` and its type parameters, which the type
+    /// mentions as `TypeInfo::Param`
+    fn declared(&mut self, name: &str) -> Option<(TypeInfo, Vec<String>)>;
+    /// Whether `name` is a type parameter in scope; these shadow other types
+    fn is_param(&self, name: &str) -> bool;
+    fn report(&mut self, message: String, span: flux_errors::Span);
+}
+
+/// Convert a type annotation. A generic type must be given as many type
+/// arguments as it has parameters, and other types none; the wrong number
+/// is reported and makes the type `TypeInfo::Error`.
+fn convert_type(scope: &mut impl TypeScope, ty: &flux_syntax::Type) -> TypeInfo {
+    match ty {
+        flux_syntax::Type::Named { name, args, span } => {
+            let args: Vec<TypeInfo> = args.iter().map(|arg| convert_type(scope, arg)).collect();
+            let (ty, params) = if scope.is_param(name) {
+                (TypeInfo::Param(name.clone()), Vec::new())
+            } else if let Some(declared) = scope.declared(name) {
+                declared
+            } else if name == "Column" && args.len() == 1 {
+                return TypeInfo::Column(Box::new(args[0].clone()));
+            } else {
+                (TypeInfo::from_name(name), Vec::new())
+            };
+            if args.len() != params.len() {
+                let message = if params.is_empty() {
+                    format!("Type `{}` takes no type arguments", name)
+                } else {
+                    format!(
+                        "`{}` expects {} type argument{}, found {}",
+                        name,
+                        params.len(),
+                        if params.len() == 1 { "" } else { "s" },
+                        args.len()
+                    )
+                };
+                scope.report(message, *span);
+                return TypeInfo::Error;
             }
+            ty.substitute_params(&params.into_iter().zip(args).collect())
+        }
+        flux_syntax::Type::Record { fields, .. } => TypeInfo::Record {
+            name: None,
+            args: Vec::new(),
+            fields: convert_fields(scope, fields),
+        },
+        flux_syntax::Type::List { elem, .. } => TypeInfo::List(Box::new(convert_type(scope, elem))),
+        flux_syntax::Type::Tuple { elems, .. } => {
+            TypeInfo::Tuple(elems.iter().map(|elem| convert_type(scope, elem)).collect())
         }
+        flux_syntax::Type::Function { params, ret, .. } => TypeInfo::Function {
+            params: params
+                .iter()
+                .map(|param| convert_type(scope, param))
+                .collect(),
+            ret: Box::new(convert_type(scope, ret)),
+        },
+        _ => TypeInfo::from_ast(ty),
     }
+}
 
-    /// Type check function calls (placeholder)
-    fn check_call(
-        &self,
-        _func: &flux_syntax::Expr,
-        _args: &[flux_syntax::Expr],
-        _env: &TypeEnv,
-        _span: flux_errors::Span,
-    ) -> flux_errors::Result<TypeInfo> {
-        // For now, assume function calls return Unknown
-        // Full implementation would look up function type and check arguments
-        Ok(TypeInfo::Unknown)
-    }
-
-    /// Type check blocks
-    fn infer_block(
-        &self,
-        stmts: &[flux_syntax::Expr],
-        env: &TypeEnv,
-    ) -> flux_errors::Result<TypeInfo> {
-        if let Some(last) = stmts.last() {
-            self.infer_expr(last, env)
-        } else {
-            Ok(TypeInfo::Int) // Empty block returns default
+fn convert_fields(
+    scope: &mut impl TypeScope,
+    fields: &[flux_syntax::FieldType],
+) -> Vec<(String, TypeInfo)> {
+    let mut converted: Vec<(String, TypeInfo)> = Vec::with_capacity(fields.len());
+    for field in fields {
+        if converted.iter().any(|(name, _)| *name == field.name) {
+            scope.report(
+                format!("Field `{}` is declared more than once", field.name),
+                field.span,
+            );
+            continue;
         }
+        let ty = convert_type(scope, &field.ty);
+        converted.push((field.name.clone(), ty));
     }
+    converted
 }
 
-impl Default for TypeChecker {
+fn param_names(params: &[flux_syntax::TypeParam]) -> Vec<String> {
+    params.iter().map(|param| param.name.clone()).collect()
+}
+
+/// Type parameters named like an earlier one of the same list
+fn duplicate_params(
+    params: &[flux_syntax::TypeParam],
+) -> impl Iterator<Item = &flux_syntax::TypeParam> {
+    params
+        .iter()
+        .enumerate()
+        .filter(|(i, param)| params[..*i].iter().any(|other| other.name == param.name))
+        .map(|(_, param)| param)
+}
+
+fn duplicate_param_error(param: &flux_syntax::TypeParam) -> flux_errors::FluxError {
+    flux_errors::FluxError::TypeError {
+        message: format!("Type parameter `{}` is declared more than once", param.name),
+        span: param.span.to_source_span(),
+    }
+}
+
+impl Default for TypeEnv {
     fn default() -> Self {
         Self::new()
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Types declared in a file with `type`, `struct` and `enum`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TypeDecls {
+    /// Every declared type, by name
+    pub types: HashMap<String, TypeInfo>,
+    /// Variants of each enum, in declaration order
+    pub enums: HashMap<String, Vec<VariantInfo>>,
+    /// Type parameters of each generic type, which its type and the fields
+    /// of its variants mention as `TypeInfo::Param`
+    pub params: HashMap<String, Vec<String>>,
+}
 
-    #[test]
-    fn test_all_type_info_display() {
-        // Test all TypeInfo variants for completeness
-        assert_eq!(TypeInfo::Int.to_string(), "int");
-        assert_eq!(TypeInfo::String.to_string(), "string");
-        assert_eq!(TypeInfo::Bool.to_string(), "bool");
-        assert_eq!(TypeInfo::Float.to_string(), "float");
-        assert_eq!(
-            TypeInfo::Named {
-                name: "MyType".to_string()
-            }
-            .to_string(),
-            "MyType"
-        );
-        assert_eq!(TypeInfo::Unknown.to_string(), "?");
+/// A variant of an enum with the types of its fields
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VariantInfo {
+    pub name: String,
+    pub fields: Vec<TypeInfo>,
+}
+
+impl TypeDecls {
+    /// The variant `name`, with the enum declaring it and its index there
+    pub fn variant(&self, name: &str) -> Option<(&str, usize, &VariantInfo)> {
+        self.enums.iter().find_map(|(enum_name, variants)| {
+            let index = variants.iter().position(|variant| variant.name == name)?;
+            Some((enum_name.as_str(), index, &variants[index]))
+        })
     }
 
-    #[test]
-    fn test_function_with_bool_float() {
-        let func_type = TypeInfo::Function {
-            params: vec![TypeInfo::Bool, TypeInfo::Float],
-            ret: Box::new(TypeInfo::Int),
-        };
-        assert_eq!(func_type.to_string(), "(bool, float) -> int");
+    /// Type parameters of the declared type `name`; none for a type that
+    /// is not generic
+    pub fn type_params(&self, name: &str) -> &[String] {
+        self.params.get(name).map_or(&[], Vec::as_slice)
     }
 
-    #[test]
-    fn test_type_env_insert_and_get() {
-        let mut env = TypeEnv::new();
-        env.insert("x".to_string(), TypeInfo::Int);
-        assert_eq!(env.get("x"), Some(&TypeInfo::Int));
-        assert_eq!(env.get("y"), None);
+    /// Type of the constructor `name`: the enum itself for a variant
+    /// without fields, a function from the fields to the enum otherwise.
+    /// The type parameters of a generic enum are left in it.
+    pub fn constructor(&self, name: &str) -> Option<TypeInfo> {
+        let (enum_name, _, variant) = self.variant(name)?;
+        let ty = TypeInfo::Enum {
+            name: enum_name.to_string(),
+            args: self
+                .type_params(enum_name)
+                .iter()
+                .map(|param| TypeInfo::Param(param.clone()))
+                .collect(),
+        };
+        Some(if variant.fields.is_empty() {
+            ty
+        } else {
+            TypeInfo::Function {
+                params: variant.fields.clone(),
+                ret: Box::new(ty),
+            }
+        })
     }
+}
 
-    #[test]
-    fn test_type_checker_infer_literals() {
-        let checker = TypeChecker::new();
-        let env = TypeEnv::new();
+/// Types every file sees without declaring them
+const BUILTIN_TYPES: &str = "
+    enum Option<T> { some(T), none }
+    enum Result<T, E> { ok(T), err(E) }
+";
 
-        let int_expr = flux_syntax::Expr::Int {
-            value: 42,
-            span: flux_errors::Span::new(0, 2),
-        };
-        assert_eq!(checker.infer_expr(&int_expr, &env).unwrap(), TypeInfo::Int);
+/// The declarations of the built-in types, which [`declare_types`] declares
+/// ahead of a file's own
+pub fn builtin_types() -> &'static flux_syntax::SourceFile {
+    static BUILTINS: OnceLock<flux_syntax::SourceFile> = OnceLock::new();
+    BUILTINS.get_or_init(|| flux_syntax::parse(BUILTIN_TYPES).expect("built-in types parse"))
+}
 
-        let float_expr = flux_syntax::Expr::Float {
-            value: 1.414,
-            span: flux_errors::Span::new(0, 4),
+/// Resolve the `type`, `struct` and `enum` declarations of a file. An alias
+/// expands to the type it names; a struct is a nominal record and an enum a
+/// nominal sum type; any of them can have type parameters. Types, fields,
+/// variants and type parameters declared twice and types containing
+/// themselves are reported, and a type containing itself becomes
+/// `TypeInfo::Error`. The built-in `Option` and `Result` are declared along
+/// with the file's types.
+pub fn declare_types(ast: &flux_syntax::SourceFile) -> (TypeDecls, Vec<flux_errors::FluxError>) {
+    let mut declarations = Declarations::default();
+    for def in builtin_types().enums() {
+        declarations.decls.insert(&def.name, Declaration::Enum(def));
+    }
+    for item in &ast.items {
+        let (name, name_span, decl) = match item {
+            flux_syntax::Item::TypeAlias(alias) => {
+                (&alias.name, alias.name_span, Declaration::Alias(alias))
+            }
+            flux_syntax::Item::Struct(def) => (&def.name, def.name_span, Declaration::Struct(def)),
+            flux_syntax::Item::Enum(def) => (&def.name, def.name_span, Declaration::Enum(def)),
+            _ => continue,
         };
-        assert_eq!(
-            checker.infer_expr(&float_expr, &env).unwrap(),
-            TypeInfo::Float
-        );
-
-        let bool_expr = flux_syntax::Expr::Bool {
-            value: true,
-            span: flux_errors::Span::new(0, 4),
+        declarations
+            .errors
+            .extend(duplicate_params(decl.type_params()).map(duplicate_param_error));
+        let message = if !matches!(TypeInfo::from_name(name), TypeInfo::Named { .. })
+            || builtin_types().enums().any(|def| def.name == *name)
+        {
+            format!("Cannot redeclare the built-in type `{}`", name)
+        } else if declarations.decls.contains_key(name.as_str()) {
+            format!("Type `{}` is declared more than once", name)
+        } else {
+            declarations.decls.insert(name, decl);
+            continue;
         };
-        assert_eq!(
-            checker.infer_expr(&bool_expr, &env).unwrap(),
-            TypeInfo::Bool
-        );
+        declarations.report(message, name_span);
     }
 
-    #[test]
-    fn test_type_error_int_plus_float() {
-        let checker = TypeChecker::new();
-        let env = TypeEnv::new();
+    let mut names: Vec<&str> = declarations.decls.keys().copied().collect();
+    names.sort_unstable();
+    for &name in &names {
+        declarations.resolve(name);
+    }
 
-        let left = flux_syntax::Expr::Int {
-            value: 10,
-            span: flux_errors::Span::new(0, 2),
+    // Enums are referred to by name, so their fields can mention any type,
+    // the enum itself included
+    let mut variant_names = HashSet::new();
+    let mut enums = HashMap::new();
+    for item in builtin_types().items.iter().chain(&ast.items) {
+        let flux_syntax::Item::Enum(def) = item else {
+            continue;
         };
-        let right = flux_syntax::Expr::Float {
-            value: 3.5,
-            span: flux_errors::Span::new(5, 8),
-        };
-        let binary = flux_syntax::Expr::Binary {
-            op: flux_syntax::BinOp::Add,
-            left: Box::new(left),
-            right: Box::new(right),
-            span: flux_errors::Span::new(0, 8),
+        if !matches!(declarations.decls.get(def.name.as_str()), Some(Declaration::Enum(decl)) if std::ptr::eq(*decl, def))
+        {
+            continue;
+        }
+        declarations.in_scope = param_names(&def.type_params);
+        let mut variants = Vec::with_capacity(def.variants.len());
+        for variant in &def.variants {
+            if !variant_names.insert(variant.name.as_str()) {
+                declarations.report(
+                    format!("Variant `{}` is declared more than once", variant.name),
+                    variant.span,
+                );
+                continue;
+            }
+            variants.push(VariantInfo {
+                name: variant.name.clone(),
+                fields: variant
+                    .fields
+                    .iter()
+                    .map(|ty| convert_type(&mut declarations, ty))
+                    .collect(),
+            });
+        }
+        enums.insert(def.name.clone(), variants);
+    }
+
+    let params = declarations
+        .decls
+        .iter()
+        .filter(|(_, decl)| !decl.type_params().is_empty())
+        .map(|(name, decl)| (name.to_string(), param_names(decl.type_params())))
+        .collect();
+    let decls = TypeDecls {
+        types: declarations.resolved,
+        enums,
+        params,
+    };
+    (decls, declarations.errors)
+}
+
+#[derive(Clone, Copy)]
+enum Declaration<'a> {
+    Alias(&'a flux_syntax::TypeAlias),
+    Struct(&'a flux_syntax::Struct),
+    Enum(&'a flux_syntax::Enum),
+}
+
+impl<'a> Declaration<'a> {
+    fn type_params(&self) -> &'a [flux_syntax::TypeParam] {
+        match self {
+            Declaration::Alias(alias) => &alias.type_params,
+            Declaration::Struct(def) => &def.type_params,
+            Declaration::Enum(def) => &def.type_params,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Declarations<'a> {
+    decls: HashMap<&'a str, Declaration<'a>>,
+    resolved: HashMap<String, TypeInfo>,
+    /// Declarations being resolved, innermost last
+    in_progress: Vec<&'a str>,
+    /// Type parameters of the declaration being resolved
+    in_scope: Vec<String>,
+    errors: Vec<flux_errors::FluxError>,
+}
+
+impl TypeScope for Declarations<'_> {
+    fn declared(&mut self, name: &str) -> Option<(TypeInfo, Vec<String>)> {
+        let ty = self.resolve(name)?;
+        let params = param_names(self.decls[name].type_params());
+        Some((ty, params))
+    }
+
+    fn is_param(&self, name: &str) -> bool {
+        self.in_scope.iter().any(|param| param == name)
+    }
+
+    fn report(&mut self, message: String, span: flux_errors::Span) {
+        Declarations::report(self, message, span);
+    }
+}
+
+impl<'a> Declarations<'a> {
+    fn report(&mut self, message: String, span: flux_errors::Span) {
+        self.errors.push(flux_errors::FluxError::TypeError {
+            message,
+            span: span.to_source_span(),
+        });
+    }
+
+    /// The type declared as `name`, or `None` when no declaration has it
+    fn resolve(&mut self, name: &str) -> Option<TypeInfo> {
+        if let Some(ty) = self.resolved.get(name) {
+            return Some(ty.clone());
+        }
+        let (&name, &decl) = self.decls.get_key_value(name)?;
+        let params = param_names(decl.type_params());
+        let name_span = match decl {
+            Declaration::Alias(alias) => alias.name_span,
+            Declaration::Struct(def) => def.name_span,
+            Declaration::Enum(_) => {
+                let ty = TypeInfo::Enum {
+                    name: name.to_string(),
+                    args: params.into_iter().map(TypeInfo::Param).collect(),
+                };
+                self.resolved.insert(name.to_string(), ty.clone());
+                return Some(ty);
+            }
         };
+        // Records are stored inline, so a type reaching itself is infinite
+        if self.in_progress.contains(&name) {
+            self.report(
+                format!("Type `{}` contains itself, so it would be infinite", name),
+                name_span,
+            );
+            self.resolved.insert(name.to_string(), TypeInfo::Error);
+            return Some(TypeInfo::Error);
+        }
 
-        let result = checker.infer_expr(&binary, &env);
-        assert!(result.is_err());
-        match result.unwrap_err() {
-            flux_errors::FluxError::TypeError { message, .. } => {
-                assert!(message.contains("Cannot apply"));
+        self.in_progress.push(name);
+        let outer = std::mem::replace(&mut self.in_scope, params.clone());
+        let ty = match decl {
+            Declaration::Alias(alias) => convert_type(self, &alias.ty),
+            Declaration::Struct(def) => TypeInfo::Record {
+                name: Some(name.to_string()),
+                args: params.into_iter().map(TypeInfo::Param).collect(),
+                fields: convert_fields(self, &def.fields),
+            },
+            Declaration::Enum(_) => unreachable!("resolved above"),
+        };
+        self.in_scope = outer;
+        self.in_progress.pop();
+        // A cycle through this declaration already resolved it to `Error`
+        Some(self.resolved.entry(name.to_string()).or_insert(ty).clone())
+    }
+}
+
+/// Types inferred for every function of a file
+#[derive(Debug, Default)]
+pub struct FileTypes {
+    /// Generalized signature of each function
+    pub signatures: HashMap<String, TypeScheme>,
+    /// Type arguments of every use of a generic function, keyed by the
+    /// function the use is in and the span of the generic function's name
+    pub instantiations: HashMap<(String, flux_errors::Span), Vec<TypeInfo>>,
+    /// Type of every expression and pattern inside a function, keyed by the
+    /// function and the span of the expression or pattern
+    pub expr_types: HashMap<(String, flux_errors::Span), TypeInfo>,
+    pub errors: Vec<flux_errors::FluxError>,
+}
+
+/// Infer the signature of every function of a file. Functions are checked
+/// callees first, mutually recursive ones together, and each group is
+/// generalized before its callers are checked.
+pub fn infer_file(ast: &flux_syntax::SourceFile) -> FileTypes {
+    TypeChecker::new().check_source_file(ast)
+}
+
+/// Type check every function of a file: bodies are inferred with parameters
+/// in scope, and every `return` plus the trailing expression is checked
+/// against the declared return type
+pub fn check_types(ast: &flux_syntax::SourceFile) -> Vec<flux_errors::FluxError> {
+    infer_file(ast)
+        .errors
+        .into_iter()
+        // Unknown identifiers are reported by `check_semantics`
+        .filter(|error| !matches!(error, flux_errors::FluxError::UnknownIdentifier { .. }))
+        .collect()
+}
+
+/// Return type of the function being checked
+#[derive(Clone)]
+struct ExpectedReturn {
+    func_name: String,
+    ty: TypeInfo,
+    /// The declared return type; `None` when it is being inferred
+    annotation: Option<flux_errors::Span>,
+}
+
+/// Operand of a binary operator whose type was still unsolved when the
+/// operator was checked
+struct DeferredOperand {
+    op: flux_syntax::BinOp,
+    ty: TypeInfo,
+    span: flux_errors::Span,
+}
+
+/// Field access on a value whose type was still unsolved when the access
+/// was checked
+struct DeferredField {
+    base: TypeInfo,
+    field: String,
+    /// Type of the access, solved once the base is known
+    ty: TypeInfo,
+    span: flux_errors::Span,
+}
+
+/// Type checker for Flux. Errors are collected rather than returned: an
+/// ill-typed expression gets the type `TypeInfo::Error`, which is accepted
+/// everywhere so one mistake is reported once.
+pub struct TypeChecker {
+    expected_return: Option<ExpectedReturn>,
+    /// Name of the function whose body is being inferred
+    function: Option<String>,
+    /// Solution of each inference variable, indexed by variable
+    substitution: Vec<Option<TypeInfo>>,
+    deferred: Vec<DeferredOperand>,
+    deferred_fields: Vec<DeferredField>,
+    /// Uses of generic functions in the group being inferred, with the
+    /// variables standing for their type arguments
+    pending_instantiations: Vec<(String, flux_errors::Span, Vec<TypeInfo>)>,
+    instantiations: HashMap<(String, flux_errors::Span), Vec<TypeInfo>>,
+    /// Types of the expressions and patterns in the group being inferred,
+    /// which may mention variables solved later
+    pending_expr_types: Vec<(String, flux_errors::Span, TypeInfo)>,
+    expr_types: HashMap<(String, flux_errors::Span), TypeInfo>,
+    /// Durations inferred so far that are known to be a whole number of
+    /// days, by span; see [`TypeChecker::note_whole_days`]
+    whole_days: HashSet<flux_errors::Span>,
+    errors: Vec<flux_errors::FluxError>,
+}
+
+impl TypeChecker {
+    pub fn new() -> Self {
+        Self {
+            expected_return: None,
+            function: None,
+            substitution: Vec::new(),
+            deferred: Vec::new(),
+            deferred_fields: Vec::new(),
+            pending_instantiations: Vec::new(),
+            instantiations: HashMap::new(),
+            pending_expr_types: Vec::new(),
+            expr_types: HashMap::new(),
+            whole_days: HashSet::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Errors found so far
+    pub fn errors(&self) -> &[flux_errors::FluxError] {
+        &self.errors
+    }
+
+    pub fn into_errors(self) -> Vec<flux_errors::FluxError> {
+        self.errors
+    }
+
+    /// Infer every function of a file; see [`infer_file`]
+    pub fn check_source_file(&mut self, ast: &flux_syntax::SourceFile) -> FileTypes {
+        let functions: Vec<&flux_syntax::Function> = ast.functions().collect();
+        let (types, errors) = declare_types(ast);
+        self.errors.extend(errors);
+        let mut env = TypeEnv::new();
+        env.set_types(types);
+        let mut signatures = HashMap::new();
+
+        for group in dependency_groups(&functions) {
+            let members: Vec<_> = group.iter().map(|&i| functions[i]).collect();
+            let schemes = self.infer_group(&members, &mut env);
+            for (func, scheme) in members.iter().zip(schemes) {
+                signatures.insert(func.name.clone(), scheme);
             }
-            _ => panic!("Expected TypeError"),
+        }
+
+        FileTypes {
+            signatures,
+            instantiations: std::mem::take(&mut self.instantiations),
+            expr_types: std::mem::take(&mut self.expr_types),
+            errors: std::mem::take(&mut self.errors),
         }
     }
 
-    #[test]
-    fn test_type_check_valid_addition() {
-        let checker = TypeChecker::new();
-        let env = TypeEnv::new();
+    /// Infer a group of mutually recursive functions. `env` must hold the
+    /// generalized signatures of every function the group calls; the
+    /// members are bound in it to theirs, which are also returned in order.
+    pub fn infer_group(
+        &mut self,
+        group: &[&flux_syntax::Function],
+        env: &mut TypeEnv,
+    ) -> Vec<TypeScheme> {
+        // Signatures in `env` are closed, so no variable of an earlier group
+        // is still live; numbering restarts and only depends on the group
+        self.substitution.clear();
 
-        let left = flux_syntax::Expr::Int {
-            value: 10,
-            span: flux_errors::Span::new(0, 2),
+        // Members stay monomorphic while their bodies are inferred, so
+        // recursive calls constrain the signature being inferred; only their
+        // type parameters are instantiated
+        let signatures: Vec<_> = group
+            .iter()
+            .map(|func| self.fresh_signature(func, env))
+            .collect();
+        for (func, (params, ret)) in group.iter().zip(&signatures) {
+            let scheme = TypeScheme {
+                vars: Vec::new(),
+                params: param_names(&func.type_params),
+                ty: TypeInfo::Function {
+                    params: params.clone(),
+                    ret: Box::new(ret.clone()),
+                },
+            };
+            env.insert_scheme(func.name.clone(), scheme);
+        }
+        for (func, (params, ret)) in group.iter().zip(&signatures) {
+            self.infer_function(func, params, ret, env);
+        }
+        self.solve_deferred();
+        for (function, span, args) in std::mem::take(&mut self.pending_instantiations) {
+            let args = args.iter().map(|arg| self.resolve(arg)).collect();
+            self.instantiations.insert((function, span), args);
+        }
+        for (function, span, ty) in std::mem::take(&mut self.pending_expr_types) {
+            let ty = self.resolve(&ty);
+            self.expr_types.insert((function, span), ty);
+        }
+
+        // Nothing outside the group mentions its variables, so every
+        // variable left in a signature is quantified
+        let types: Vec<_> = group
+            .iter()
+            .map(|func| self.resolve(env.get(&func.name).unwrap_or(&TypeInfo::Unknown)))
+            .collect();
+        let mut schemes = Vec::with_capacity(group.len());
+        for (func, ty) in group.iter().zip(types) {
+            // Native functions are built into every module that uses them
+            if func.is_export && !func.is_native {
+                self.check_export_inferred(func, &ty);
+            }
+            let mut scheme = TypeScheme::closed(ty);
+            scheme.params = param_names(&func.type_params);
+            env.insert_scheme(func.name.clone(), scheme.clone());
+            schemes.push(scheme);
+        }
+        schemes
+    }
+
+    /// Check a function body against its signature, inferring the types of
+    /// missing annotations. `env` supplies the other functions in scope;
+    /// parameters are bound on top of it. Returns the function's type.
+    pub fn check_function(&mut self, func: &flux_syntax::Function, env: &TypeEnv) -> TypeInfo {
+        let (params, ret) = self.fresh_signature(func, env);
+        self.infer_function(func, &params, &ret, env);
+        self.resolve(&TypeInfo::Function {
+            params,
+            ret: Box::new(ret),
+        })
+    }
+
+    /// Parameter and return types of `func`, with a fresh inference variable
+    /// for every missing annotation
+    fn fresh_signature(
+        &mut self,
+        func: &flux_syntax::Function,
+        env: &TypeEnv,
+    ) -> (Vec<TypeInfo>, TypeInfo) {
+        self.errors
+            .extend(duplicate_params(&func.type_params).map(duplicate_param_error));
+        let env = env.with_type_params(func);
+        let mut annotation = |ty: Option<&flux_syntax::Type>| match ty {
+            Some(ty) => self.annotation(ty, &env),
+            None => self.fresh_var(),
         };
-        let right = flux_syntax::Expr::Int {
-            value: 32,
-            span: flux_errors::Span::new(5, 7),
+        let params = func
+            .params
+            .iter()
+            .map(|param| annotation(param.ty.as_ref()))
+            .collect();
+        let ret = annotation(func.return_type.as_ref());
+        (params, ret)
+    }
+
+    /// Convert a type annotation in `env`, reporting type arguments that
+    /// don't fit
+    fn annotation(&mut self, ty: &flux_syntax::Type, env: &TypeEnv) -> TypeInfo {
+        let mut scope = EnvScope {
+            env,
+            errors: &mut self.errors,
         };
-        let binary = flux_syntax::Expr::Binary {
-            op: flux_syntax::BinOp::Add,
-            left: Box::new(left),
-            right: Box::new(right),
-            span: flux_errors::Span::new(0, 7),
+        convert_type(&mut scope, ty)
+    }
+
+    fn infer_function(
+        &mut self,
+        func: &flux_syntax::Function,
+        params: &[TypeInfo],
+        ret: &TypeInfo,
+        env: &TypeEnv,
+    ) {
+        let mut env = env.with_type_params(func);
+        for (param, ty) in func.params.iter().zip(params) {
+            env.insert(param.name.clone(), ty.clone());
+        }
+        if func.is_native {
+            self.check_native_annotated(func);
+            return;
+        }
+        self.function = Some(func.name.clone());
+
+        self.expected_return = Some(ExpectedReturn {
+            func_name: func.name.clone(),
+            ty: ret.clone(),
+            annotation: func.return_type.as_ref().map(|ty| ty.span()),
+        });
+        let body_ty = self.infer(&func.body, &env);
+        self.check_return_value(&body_ty, Self::tail_expr(&func.body));
+        self.expected_return = None;
+        self.function = None;
+    }
+
+    /// A native function has no body to infer its types from, so its
+    /// signature must say them all
+    fn check_native_annotated(&mut self, func: &flux_syntax::Function) {
+        let unannotated = func.params.iter().find(|param| param.ty.is_none());
+        let span = match (unannotated, &func.return_type) {
+            (Some(param), _) => param.span,
+            (None, None) => func.span,
+            (None, Some(_)) => return,
+        };
+        self.report(flux_errors::FluxError::TypeError {
+            message: format!(
+                "Native function `{}` must annotate its parameters and return type",
+                func.name
+            ),
+            span: span.to_source_span(),
+        });
+    }
+
+    /// Exported functions cross the component boundary, so their signature
+    /// must be concrete: neither generic nor left polymorphic
+    fn check_export_inferred(&mut self, func: &flux_syntax::Function, ty: &TypeInfo) {
+        if let Some(param) = func.type_params.first() {
+            self.report(flux_errors::FluxError::TypeError {
+                message: format!(
+                    "Exported function `{}` cannot be generic; give its parameters concrete types",
+                    func.name
+                ),
+                span: param.span.to_source_span(),
+            });
+            return;
+        }
+        let TypeInfo::Function { params, ret } = ty else {
+            return;
         };
 
-        let result = checker.infer_expr(&binary, &env);
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), TypeInfo::Int);
+        for (param, ty) in func.params.iter().zip(params) {
+            if !ty.type_vars().is_empty() {
+                self.report(flux_errors::FluxError::TypeError {
+                    message: format!(
+                        "Cannot infer the type of parameter `{}` of exported function `{}`",
+                        param.name, func.name
+                    ),
+                    span: param.span.to_source_span(),
+                });
+            }
+        }
+        if !ret.type_vars().is_empty() {
+            let signature = flux_errors::Span::new(func.span.start, func.body.span().start);
+            self.report(flux_errors::FluxError::TypeError {
+                message: format!(
+                    "Cannot infer the return type of exported function `{}`",
+                    func.name
+                ),
+                span: signature.to_source_span(),
+            });
+        }
     }
 
-    #[test]
-    fn test_type_check_let_binding() {
-        let checker = TypeChecker::new();
-        let env = TypeEnv::new();
+    /// The expression whose value a body produces: the last statement of a
+    /// block, or the body of a `let`
+    fn tail_expr(expr: &flux_syntax::Expr) -> &flux_syntax::Expr {
+        match expr {
+            flux_syntax::Expr::Block { stmts, .. } => stmts.last().map_or(expr, Self::tail_expr),
+            flux_syntax::Expr::Let { body, .. } => Self::tail_expr(body),
+            _ => expr,
+        }
+    }
 
-        let value = flux_syntax::Expr::Int {
-            value: 42,
-            span: flux_errors::Span::new(8, 10),
+    /// Record an error; the offending expression gets the poison type
+    fn report(&mut self, error: flux_errors::FluxError) -> TypeInfo {
+        self.errors.push(error);
+        TypeInfo::Error
+    }
+
+    /// Unify a returned value with the function's return type, if any
+    fn check_return_value(&mut self, found: &TypeInfo, value: &flux_syntax::Expr) {
+        let Some(expected) = self.expected_return.clone() else {
+            return;
         };
-        let body_var = flux_syntax::Expr::Var {
-            name: "x".to_string(),
-            span: flux_errors::Span::new(11, 12),
+        if self.unify(found, &expected.ty) {
+            return;
+        }
+
+        let expected_ty = self.resolve(&expected.ty);
+        let found = self.resolve(found);
+        self.report(match expected.annotation {
+            Some(annotation) => flux_errors::FluxError::ReturnTypeMismatch {
+                message: format!(
+                    "`{}` must return {}, found {}",
+                    expected.func_name, expected_ty, found
+                ),
+                expected: expected_ty.to_string(),
+                found: found.to_string(),
+                span: value.span().to_source_span(),
+                annotation: annotation.to_source_span(),
+            },
+            None => flux_errors::FluxError::TypeError {
+                message: format!(
+                    "`{}` returns {} here but {} elsewhere",
+                    expected.func_name, found, expected_ty
+                ),
+                span: value.span().to_source_span(),
+            },
+        });
+    }
+
+    /// Infer the type of an expression given an environment. Errors are
+    /// collected in [`TypeChecker::errors`].
+    pub fn infer_expr(&mut self, expr: &flux_syntax::Expr, env: &TypeEnv) -> TypeInfo {
+        let ty = self.infer(expr, env);
+        self.resolve(&ty)
+    }
+
+    /// Infer an expression's type; the result may mention variables solved
+    /// later in the function
+    fn infer(&mut self, expr: &flux_syntax::Expr, env: &TypeEnv) -> TypeInfo {
+        let ty = self.infer_unrecorded(expr, env);
+        self.record_type(expr.span(), &ty);
+        if matches!(self.resolve(&ty), TypeInfo::Duration) {
+            self.note_whole_days(expr, env);
+        }
+        ty
+    }
+
+    /// Remember the Duration `expr` as a whole number of days when that
+    /// follows from how it is built: from literals without a time part, sums
+    /// and differences of such Durations, such a Duration scaled by an int,
+    /// and names `let` binds to one. The difference of two Dates is
+    /// remembered as it is checked. Only those
+    /// may move a Date; see [`TypeChecker::check_whole_days`].
+    fn note_whole_days(&mut self, expr: &flux_syntax::Expr, env: &TypeEnv) {
+        use flux_syntax::{BinOp, Expr};
+        let known = |expr: &Expr| self.whole_days.contains(&expr.span());
+        let whole = match expr {
+            Expr::Temporal {
+                value: flux_syntax::Temporal::Duration(duration),
+                ..
+            } => duration.nanos % flux_syntax::temporal::NANOS_PER_DAY == 0,
+            Expr::Var { name, .. } => env.whole_days.contains(name),
+            Expr::Binary {
+                op: BinOp::Add | BinOp::Sub,
+                left,
+                right,
+                ..
+            } => known(left) && known(right),
+            Expr::Binary {
+                op: BinOp::Mul,
+                left,
+                right,
+                ..
+            } => known(left) || known(right),
+            Expr::Let { body, .. } => known(body),
+            Expr::Block { stmts, .. } => stmts.last().is_some_and(known),
+            Expr::If {
+                then_branch,
+                else_branch,
+                ..
+            } => known(then_branch) && known(else_branch),
+            _ => false,
         };
-        let let_expr = flux_syntax::Expr::Let {
-            name: "x".to_string(),
-            value: Box::new(value),
-            body: Box::new(body_var),
-            span: flux_errors::Span::new(0, 12),
+        if whole {
+            self.whole_days.insert(expr.span());
+        }
+    }
+
+    /// Remember the type of the expression or pattern at `span` in the
+    /// function being inferred, for [`FileTypes::expr_types`]
+    fn record_type(&mut self, span: flux_errors::Span, ty: &TypeInfo) {
+        if let Some(function) = &self.function {
+            self.pending_expr_types
+                .push((function.clone(), span, ty.clone()));
+        }
+    }
+
+    fn infer_unrecorded(&mut self, expr: &flux_syntax::Expr, env: &TypeEnv) -> TypeInfo {
+        use flux_syntax::Expr;
+        match expr {
+            Expr::Int { .. } => TypeInfo::Int,
+            Expr::Float { .. } => TypeInfo::Float,
+            Expr::Bool { .. } => TypeInfo::Bool,
+            Expr::String { .. } => TypeInfo::String,
+            Expr::Temporal { value, span } => {
+                match value {
+                    flux_syntax::Temporal::DateTime { zone, .. }
+                        if !flux_syntax::temporal::is_known_zone(zone) =>
+                    {
+                        self.report(flux_errors::FluxError::TypeError {
+                            message: format!(
+                                "Unknown time zone `{}`. Use `UTC`, an offset like `+02:00` or \
+                                 an IANA zone name like `Europe/Paris`.",
+                                zone
+                            ),
+                            span: span.to_source_span(),
+                        });
+                    }
+                    // A Duration is a number of nanoseconds; a month has none
+                    flux_syntax::Temporal::Duration(duration) if duration.months != 0 => {
+                        self.report(flux_errors::FluxError::TypeError {
+                            message: "Months and years have no fixed length, so a Duration \
+                                      cannot hold them. Use days or weeks."
+                                .to_string(),
+                            span: span.to_source_span(),
+                        });
+                    }
+                    _ => {}
+                }
+                TypeInfo::temporal(value.kind())
+            }
+            // The syntax error was already reported
+            Expr::Error { .. } => TypeInfo::Error,
+            // Variables and functions shadow enum variants
+            Expr::Var { name, span } => match env.get_scheme(name) {
+                Some(scheme) => {
+                    let (ty, type_args) = self.instantiate(scheme);
+                    if !type_args.is_empty() {
+                        if let Some(function) = &self.function {
+                            self.pending_instantiations
+                                .push((function.clone(), *span, type_args));
+                        }
+                    }
+                    ty
+                }
+                None => match env.decls().constructor(name) {
+                    Some(ty) => self.instantiate_params(&ty),
+                    None => self.report(flux_errors::FluxError::UnknownIdentifier {
+                        name: name.clone(),
+                        span: span.to_source_span(),
+                    }),
+                },
+            },
+            Expr::Record { name, fields, span } => {
+                self.infer_record(name.as_deref(), fields, env, *span)
+            }
+            Expr::Field {
+                base,
+                field,
+                field_span,
+                ..
+            } => {
+                let base_ty = self.infer(base, env);
+                self.check_field(base_ty, field, *field_span)
+            }
+            Expr::List { elems, .. } => self.infer_list(elems, env),
+            Expr::Tuple { elems, .. } => {
+                TypeInfo::Tuple(elems.iter().map(|elem| self.infer(elem, env)).collect())
+            }
+            Expr::Index { base, index, .. } => {
+                let base_ty = self.infer(base, env);
+                let index_ty = self.infer(index, env);
+                self.check_index(base_ty, index_ty, index)
+            }
+            Expr::Unary { op, operand, span } => {
+                let operand_ty = self.infer(operand, env);
+                self.check_unary_op(*op, operand_ty, *span)
+            }
+            Expr::Try { operand, span } => {
+                let operand_ty = self.infer(operand, env);
+                self.check_try(operand_ty, *span)
+            }
+            Expr::Column { name, span } => match env.schema() {
+                Some(schema) => match schema.column_type(name) {
+                    Some(ty) => ty,
+                    None => self.report(flux_errors::FluxError::TypeError {
+                        message: format!("Unknown column #{} in the input schema", name),
+                        span: span.to_source_span(),
+                    }),
+                },
+                None => TypeInfo::Column(Box::new(TypeInfo::Unknown)),
+            },
+            Expr::Binary {
+                op,
+                left,
+                right,
+                span,
+            } => {
+                let left_ty = self.infer(left, env);
+                let right_ty = self.infer(right, env);
+                let ty = self.check_binary_op(*op, left_ty.clone(), right_ty.clone(), *span);
+                let operands = [(&**left, left_ty), (&**right, right_ty)];
+                match self.resolve(&ty) {
+                    TypeInfo::Date => self.check_whole_days(operands, *span),
+                    TypeInfo::Duration
+                        if operands
+                            .iter()
+                            .all(|(_, ty)| matches!(self.resolve(ty), TypeInfo::Date)) =>
+                    {
+                        self.whole_days.insert(*span);
+                    }
+                    _ => {}
+                }
+                ty
+            }
+            Expr::Let {
+                name, value, body, ..
+            } => {
+                let value_ty = self.infer(value, env);
+                let scheme = self.generalize(&value_ty, env);
+                let mut new_env = env.clone();
+                new_env.insert_scheme(name.clone(), scheme);
+                if self.whole_days.contains(&value.span()) {
+                    new_env.whole_days.insert(name.clone());
+                }
+                self.infer(body, &new_env)
+            }
+            Expr::Call { func, args, span } => self.check_call(func, args, env, *span),
+            Expr::Pipeline { left, right, span } => {
+                self.infer(&Expr::pipeline_to_call(left, right, *span), env)
+            }
+            Expr::If {
+                cond,
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                let cond_ty = self.infer(cond, env);
+                if !self.unify(&cond_ty, &TypeInfo::Bool) {
+                    let message = format!(
+                        "If condition must be bool, found {}",
+                        self.resolve(&cond_ty)
+                    );
+                    self.report(flux_errors::FluxError::TypeError {
+                        message,
+                        span: cond.span().to_source_span(),
+                    });
+                }
+
+                let then_ty = self.infer(then_branch, env);
+                let else_ty = self.infer(else_branch, env);
+                if !self.unify(&else_ty, &then_ty) {
+                    let message = format!(
+                        "If branches have incompatible types: then branch is {}, else branch is {}",
+                        self.resolve(&then_ty),
+                        self.resolve(&else_ty)
+                    );
+                    return self.report(flux_errors::FluxError::TypeError {
+                        message,
+                        span: else_branch.span().to_source_span(),
+                    });
+                }
+                // `Unknown` and `Error` unify without being constrained, so
+                // prefer the other branch
+                match self.resolve(&then_ty) {
+                    TypeInfo::Unknown | TypeInfo::Error => else_ty,
+                    _ => then_ty,
+                }
+            }
+            Expr::Match {
+                scrutinee, arms, ..
+            } => self.infer_match(scrutinee, arms, env),
+            Expr::Lambda {
+                params,
+                return_type,
+                body,
+                ..
+            } => self.infer_lambda(params, return_type.as_ref(), body, env),
+            Expr::Block { stmts, .. } => self.infer_block(stmts, env),
+            Expr::Return { value, .. } => {
+                let value_ty = self.infer(value, env);
+                self.check_return_value(&value_ty, value);
+                // `return` leaves the function, so a mismatched value is
+                // reported once rather than again by the enclosing expression
+                match &self.expected_return {
+                    Some(expected) => expected.ty.clone(),
+                    None => value_ty,
+                }
+            }
+        }
+    }
+
+    /// Infer a lambda. Its parameters are bound on top of `env`, so the
+    /// variables it captures share their types with the enclosing function
+    /// and are not generalized while it is inferred; a `return` in its body
+    /// leaves the lambda.
+    fn infer_lambda(
+        &mut self,
+        params: &[flux_syntax::Param],
+        return_type: Option<&flux_syntax::Type>,
+        body: &flux_syntax::Expr,
+        env: &TypeEnv,
+    ) -> TypeInfo {
+        let mut annotation = |ty: Option<&flux_syntax::Type>| match ty {
+            Some(ty) => self.annotation(ty, env),
+            None => self.fresh_var(),
+        };
+        let param_tys: Vec<_> = params
+            .iter()
+            .map(|param| annotation(param.ty.as_ref()))
+            .collect();
+        let ret = annotation(return_type);
+
+        let mut lambda_env = env.clone();
+        for (param, ty) in params.iter().zip(&param_tys) {
+            lambda_env.insert(param.name.clone(), ty.clone());
+        }
+        let enclosing = self.expected_return.replace(ExpectedReturn {
+            func_name: "fn(..)".to_string(),
+            ty: ret.clone(),
+            annotation: return_type.map(|ty| ty.span()),
+        });
+        let body_ty = self.infer(body, &lambda_env);
+        self.check_return_value(&body_ty, Self::tail_expr(body));
+        self.expected_return = enclosing;
+
+        TypeInfo::Function {
+            params: param_tys,
+            ret: Box::new(ret),
+        }
+    }
+
+    /// Infer a `match`: every pattern must fit the scrutinee and every arm
+    /// body have the same type. Once the arms type check, arms that can
+    /// never match and values no arm matches are reported.
+    fn infer_match(
+        &mut self,
+        scrutinee: &flux_syntax::Expr,
+        arms: &[flux_syntax::MatchArm],
+        env: &TypeEnv,
+    ) -> TypeInfo {
+        let scrutinee_ty = self.infer(scrutinee, env);
+        let result = self.fresh_var();
+        let mut patterns_fit = true;
+
+        for arm in arms {
+            let mut arm_env = env.clone();
+            let mut bound = Vec::new();
+            patterns_fit &=
+                self.check_pattern(&arm.pattern, &scrutinee_ty, &mut arm_env, &mut bound);
+            let body_ty = self.infer(&arm.body, &arm_env);
+            if !self.unify(&body_ty, &result) {
+                let message = format!(
+                    "Match arms have incompatible types: earlier arms are {}, this arm is {}",
+                    self.resolve(&result),
+                    self.resolve(&body_ty)
+                );
+                self.report(flux_errors::FluxError::TypeError {
+                    message,
+                    span: arm.body.span().to_source_span(),
+                });
+            }
+        }
+
+        // Patterns that don't fit their type make the analysis meaningless
+        if patterns_fit && self.resolve(&scrutinee_ty) != TypeInfo::Error {
+            let errors = crate::exhaustive::check_match(arms, env.decls(), scrutinee.span());
+            self.errors.extend(errors);
+        }
+        result
+    }
+
+    /// Check a pattern against the type of the value it matches, binding
+    /// the names it introduces in `env`. Returns whether the pattern fits.
+    fn check_pattern(
+        &mut self,
+        pattern: &flux_syntax::Pattern,
+        ty: &TypeInfo,
+        env: &mut TypeEnv,
+        bound: &mut Vec<String>,
+    ) -> bool {
+        use flux_syntax::Pattern;
+        let span = pattern.span();
+        self.record_type(span, ty);
+        let pattern_ty = match pattern {
+            Pattern::Wildcard { .. } => return true,
+            Pattern::Name { name, .. } => match env.decls().variant(name) {
+                Some((_, _, variant)) if variant.fields.is_empty() => {
+                    let constructor = env.decls().constructor(name).unwrap_or(TypeInfo::Error);
+                    self.instantiate_params(&constructor)
+                }
+                Some((_, _, variant)) => {
+                    let message = format!(
+                        "Variant `{}` has {} field{}; match it with `{}(..)`",
+                        name,
+                        variant.fields.len(),
+                        if variant.fields.len() == 1 { "" } else { "s" },
+                        name
+                    );
+                    return self.report_pattern(message, span);
+                }
+                None => {
+                    let fits = if bound.contains(name) {
+                        let message = format!("`{}` is bound more than once in this pattern", name);
+                        self.report_pattern(message, span)
+                    } else {
+                        true
+                    };
+                    bound.push(name.clone());
+                    env.insert(name.clone(), ty.clone());
+                    return fits;
+                }
+            },
+            Pattern::Int { .. } => TypeInfo::Int,
+            Pattern::Bool { .. } => TypeInfo::Bool,
+            Pattern::String { .. } => TypeInfo::String,
+            Pattern::Variant { name, args, .. } => {
+                let Some(constructor) = env.decls().constructor(name) else {
+                    for arg in args {
+                        self.check_pattern(arg, &TypeInfo::Error, env, bound);
+                    }
+                    return self.report_pattern(format!("Unknown variant `{}`", name), span);
+                };
+                // The enum's type arguments fix the types of the fields
+                let (fields, enum_ty) = match self.instantiate_params(&constructor) {
+                    TypeInfo::Function { params, ret } => (params, *ret),
+                    enum_ty => (Vec::new(), enum_ty),
+                };
+                let mut fits = true;
+                if fields.len() != args.len() {
+                    let message = format!(
+                        "Variant `{}` has {} field{}, the pattern gives {}",
+                        name,
+                        fields.len(),
+                        if fields.len() == 1 { "" } else { "s" },
+                        args.len()
+                    );
+                    fits = self.report_pattern(message, span);
+                }
+                for (index, arg) in args.iter().enumerate() {
+                    let field_ty = fields.get(index).cloned().unwrap_or(TypeInfo::Error);
+                    fits &= self.check_pattern(arg, &field_ty, env, bound);
+                }
+                if !fits {
+                    return false;
+                }
+                enum_ty
+            }
+        };
+
+        if self.unify(&pattern_ty, ty) {
+            return true;
+        }
+        let message = format!(
+            "Pattern of type {} cannot match a value of type {}",
+            pattern_ty,
+            self.resolve(ty)
+        );
+        self.report_pattern(message, span)
+    }
+
+    /// Report an ill-formed pattern; always `false`
+    fn report_pattern(&mut self, message: String, span: flux_errors::Span) -> bool {
+        self.report(flux_errors::FluxError::TypeError {
+            message,
+            span: span.to_source_span(),
+        });
+        false
+    }
+
+    /// Infer a record literal. Without a type name it has the structural
+    /// type of its fields; `User { .. }` must give every field of `User`,
+    /// with values of the declared types, and has type `User`.
+    fn infer_record(
+        &mut self,
+        name: Option<&str>,
+        fields: &[flux_syntax::FieldInit],
+        env: &TypeEnv,
+        span: flux_errors::Span,
+    ) -> TypeInfo {
+        let mut values: Vec<(&flux_syntax::FieldInit, TypeInfo)> = Vec::new();
+        for field in fields {
+            let ty = self.infer(&field.value, env);
+            if values.iter().any(|(given, _)| given.name == field.name) {
+                self.report(flux_errors::FluxError::TypeError {
+                    message: format!("Field `{}` is given more than once", field.name),
+                    span: field.span.to_source_span(),
+                });
+                continue;
+            }
+            values.push((field, ty));
+        }
+
+        let Some(name) = name else {
+            return TypeInfo::Record {
+                name: None,
+                args: Vec::new(),
+                fields: values
+                    .into_iter()
+                    .map(|(field, ty)| (field.name.clone(), ty))
+                    .collect(),
+            };
+        };
+        let declared = match env.get_type(name) {
+            Some(declared @ TypeInfo::Record { .. }) => self.instantiate_params(declared),
+            Some(TypeInfo::Error) => return TypeInfo::Error,
+            Some(other) => {
+                return self.report(flux_errors::FluxError::TypeError {
+                    message: format!("`{}` is {}, not a record type", name, other),
+                    span: span.to_source_span(),
+                })
+            }
+            None => {
+                return self.report(flux_errors::FluxError::TypeError {
+                    message: format!("Unknown type `{}`", name),
+                    span: span.to_source_span(),
+                })
+            }
+        };
+
+        let TypeInfo::Record {
+            fields: declared_fields,
+            ..
+        } = &declared
+        else {
+            unreachable!("checked above");
         };
+        let missing: Vec<&str> = declared_fields
+            .iter()
+            .filter(|(field, _)| !values.iter().any(|(given, _)| given.name == *field))
+            .map(|(field, _)| field.as_str())
+            .collect();
+        if !missing.is_empty() {
+            self.report(flux_errors::FluxError::TypeError {
+                message: format!(
+                    "`{}` literal is missing field{} `{}`",
+                    name,
+                    if missing.len() == 1 { "" } else { "s" },
+                    missing.join("`, `")
+                ),
+                span: span.to_source_span(),
+            });
+        }
+        for (field, ty) in values {
+            let Some(expected) = declared.field(&field.name) else {
+                self.report(flux_errors::FluxError::TypeError {
+                    message: format!("`{}` has no field `{}`", name, field.name),
+                    span: field.span.to_source_span(),
+                });
+                continue;
+            };
+            if !self.unify(&ty, expected) {
+                let message = format!(
+                    "Field `{}` of `{}` expects {}, found {}",
+                    field.name,
+                    name,
+                    expected,
+                    self.resolve(&ty)
+                );
+                self.report(flux_errors::FluxError::TypeError {
+                    message,
+                    span: field.value.span().to_source_span(),
+                });
+            }
+        }
+        declared
+    }
+
+    /// Infer a list literal: every element must have the first one's type.
+    /// An empty list's elements can be anything.
+    fn infer_list(&mut self, elems: &[flux_syntax::Expr], env: &TypeEnv) -> TypeInfo {
+        let elem_ty = self.fresh_var();
+        for elem in elems {
+            let ty = self.infer(elem, env);
+            if !self.unify(&ty, &elem_ty) {
+                let message = format!(
+                    "List elements have incompatible types: earlier elements are {}, this element is {}",
+                    self.resolve(&elem_ty),
+                    self.resolve(&ty)
+                );
+                self.report(flux_errors::FluxError::TypeError {
+                    message,
+                    span: elem.span().to_source_span(),
+                });
+            }
+        }
+        TypeInfo::List(Box::new(elem_ty))
+    }
+
+    /// Type of `base[index]`. A list takes any int index and is checked at
+    /// run time; a tuple needs a literal index within its length, which
+    /// picks the element's type. A base whose type is still unsolved is
+    /// taken to be a list.
+    fn check_index(
+        &mut self,
+        base: TypeInfo,
+        index: TypeInfo,
+        index_expr: &flux_syntax::Expr,
+    ) -> TypeInfo {
+        let span = index_expr.span();
+        if !self.unify(&index, &TypeInfo::Int) {
+            let message = format!("Index must be int, found {}", self.resolve(&index));
+            self.report(flux_errors::FluxError::TypeError {
+                message,
+                span: span.to_source_span(),
+            });
+        }
 
-        let result = checker.infer_expr(&let_expr, &env);
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), TypeInfo::Int);
+        match self.resolve(&base) {
+            TypeInfo::Var(_) => {
+                let elem = self.fresh_var();
+                self.unify(&base, &TypeInfo::List(Box::new(elem.clone())));
+                elem
+            }
+            TypeInfo::List(elem) => *elem,
+            TypeInfo::Tuple(elems) => match index_expr {
+                flux_syntax::Expr::Int { value, .. } => {
+                    match usize::try_from(*value).ok().and_then(|i| elems.get(i)) {
+                        Some(elem) => elem.clone(),
+                        None => self.report(flux_errors::FluxError::TypeError {
+                            message: format!(
+                                "Index {} is out of bounds for a tuple of {} elements",
+                                value,
+                                elems.len()
+                            ),
+                            span: span.to_source_span(),
+                        }),
+                    }
+                }
+                _ => self.report(flux_errors::FluxError::TypeError {
+                    message: format!(
+                        "Tuple {} can only be indexed with an int literal, e.g. `pair[0]`",
+                        TypeInfo::Tuple(elems)
+                    ),
+                    span: span.to_source_span(),
+                }),
+            },
+            TypeInfo::Unknown => TypeInfo::Unknown,
+            TypeInfo::Error => TypeInfo::Error,
+            other => self.report(flux_errors::FluxError::TypeError {
+                message: format!("Cannot index into {}", other),
+                span: span.to_source_span(),
+            }),
+        }
+    }
+
+    /// Type of `base.field`. Access on a value whose type is still unsolved
+    /// is checked once the function has been inferred.
+    fn check_field(&mut self, base: TypeInfo, field: &str, span: flux_errors::Span) -> TypeInfo {
+        match self.resolve(&base) {
+            TypeInfo::Var(_) => {
+                let ty = self.fresh_var();
+                self.deferred_fields.push(DeferredField {
+                    base,
+                    field: field.to_string(),
+                    ty: ty.clone(),
+                    span,
+                });
+                ty
+            }
+            TypeInfo::Unknown => TypeInfo::Unknown,
+            TypeInfo::Error => TypeInfo::Error,
+            record @ TypeInfo::Record { .. } => match record.field(field) {
+                Some(ty) => ty.clone(),
+                None => self.report(flux_errors::FluxError::TypeError {
+                    message: format!("`{}` has no field `{}`", record, field),
+                    span: span.to_source_span(),
+                }),
+            },
+            other => self.report(flux_errors::FluxError::TypeError {
+                message: format!("Cannot access field `{}` on {}", field, other),
+                span: span.to_source_span(),
+            }),
+        }
+    }
+
+    /// Check binary operation types. Both operands of a non-logical operator
+    /// are unified; an operand type that is still unsolved is checked once
+    /// the function has been inferred.
+    fn check_binary_op(
+        &mut self,
+        op: flux_syntax::BinOp,
+        left: TypeInfo,
+        right: TypeInfo,
+        span: flux_errors::Span,
+    ) -> TypeInfo {
+        // Logical ops: both operands must be bool
+        if op.is_logical() {
+            if !(self.unify(&left, &TypeInfo::Bool) && self.unify(&right, &TypeInfo::Bool)) {
+                self.report_operands(op, &left, &right, span);
+            }
+            return TypeInfo::Bool;
+        }
+
+        if op.is_arithmetic() {
+            let (left, right) = (self.resolve(&left), self.resolve(&right));
+            if left.temporal_kind().is_some() || right.temporal_kind().is_some() {
+                return self.check_temporal_arithmetic(op, left, right, span);
+            }
+        }
+
+        // Arithmetic produces its operand type; comparisons produce bool
+        // even when their operands are ill-typed
+        let result = |operand| {
+            if op.is_arithmetic() {
+                operand
+            } else {
+                TypeInfo::Bool
+            }
+        };
+
+        if !self.unify(&left, &right) {
+            self.report_operands(op, &left, &right, span);
+            return result(TypeInfo::Error);
+        }
+        // `Unknown` and `Error` unify with anything without being constrained
+        let operand = match self.resolve(&left) {
+            TypeInfo::Unknown | TypeInfo::Error => self.resolve(&right),
+            ty => ty,
+        };
+        match operand {
+            TypeInfo::Var(_) => self.deferred.push(DeferredOperand {
+                op,
+                ty: operand.clone(),
+                span,
+            }),
+            ref ty if !Self::accepts_operand(op, ty) => {
+                self.report_operands(op, &left, &right, span);
+                return result(TypeInfo::Error);
+            }
+            _ => {}
+        }
+        result(operand)
+    }
+
+    /// Arithmetic and ordering ops take the same numeric type on both sides,
+    /// equality ops the same primitive type. Temporal values of one type are
+    /// ordered; the only arithmetic on two of them that keeps their type is
+    /// adding or subtracting Durations.
+    fn accepts_operand(op: flux_syntax::BinOp, ty: &TypeInfo) -> bool {
+        use flux_syntax::BinOp;
+        match ty {
+            TypeInfo::Int | TypeInfo::Float | TypeInfo::Unknown | TypeInfo::Error => true,
+            TypeInfo::Bool | TypeInfo::String => matches!(op, BinOp::Eq | BinOp::NotEq),
+            TypeInfo::Duration => !matches!(op, BinOp::Mul | BinOp::Div),
+            ty if ty.is_time_point() => op.is_comparison(),
+            _ => false,
+        }
+    }
+
+    /// Check arithmetic with a temporal operand. An operand that is still
+    /// unsolved takes the only type that combines with the other one: a
+    /// Duration to move a point in time with `+`, an int to scale a Duration,
+    /// and otherwise the same type.
+    fn check_temporal_arithmetic(
+        &mut self,
+        op: flux_syntax::BinOp,
+        left: TypeInfo,
+        right: TypeInfo,
+        span: flux_errors::Span,
+    ) -> TypeInfo {
+        use flux_syntax::BinOp;
+        let counterpart = |known: &TypeInfo| match (op, known) {
+            (BinOp::Mul | BinOp::Div, TypeInfo::Duration) => TypeInfo::Int,
+            (BinOp::Add, point) if point.is_time_point() => TypeInfo::Duration,
+            _ => known.clone(),
+        };
+        if let TypeInfo::Var(_) = left {
+            self.unify(&left, &counterpart(&right));
+        }
+        if let TypeInfo::Var(_) = right {
+            self.unify(&right, &counterpart(&left));
+        }
+        let (left, right) = (self.resolve(&left), self.resolve(&right));
+
+        if matches!(left, TypeInfo::Error) || matches!(right, TypeInfo::Error) {
+            return TypeInfo::Error;
+        }
+        if matches!(left, TypeInfo::Unknown) || matches!(right, TypeInfo::Unknown) {
+            return TypeInfo::Unknown;
+        }
+        match Self::temporal_result(op, &left, &right) {
+            Some(ty) => ty,
+            None => {
+                self.report_operands(op, &left, &right, span);
+                TypeInfo::Error
+            }
+        }
+    }
+
+    /// A Date moves by whole days, so a Duration added to or subtracted
+    /// from one must be known to be a whole number of days: `@2026-10-16 +
+    /// 3h` would otherwise silently drop the hours
+    fn check_whole_days(
+        &mut self,
+        operands: [(&flux_syntax::Expr, TypeInfo); 2],
+        span: flux_errors::Span,
+    ) {
+        use flux_syntax::{temporal::NANOS_PER_DAY, Expr, Temporal};
+        for (operand, ty) in operands {
+            if self.whole_days.contains(&operand.span())
+                || !matches!(self.resolve(&ty), TypeInfo::Duration)
+            {
+                continue;
+            }
+            let message = match operand {
+                Expr::Temporal {
+                    value: Temporal::Duration(duration),
+                    ..
+                } if duration.nanos % NANOS_PER_DAY != 0 => {
+                    "A Date moves by whole days, but this Duration has a time part. Use days \
+                     or weeks, or a DateTime to move by hours."
+                }
+                _ => {
+                    "A Date moves by whole days, but this Duration may have a time part. Build \
+                     it from whole days, like `n * 1d`, or use a DateTime to move by hours."
+                }
+            };
+            self.report(flux_errors::FluxError::TypeError {
+                message: message.to_string(),
+                span: span.to_source_span(),
+            });
+        }
+    }
+
+    /// Type of temporal arithmetic: a point in time moved by a Duration keeps
+    /// its type (`Date + Duration = Date`), the difference of two points of
+    /// the same type is a Duration (`Timestamp - Timestamp = Duration`), and
+    /// Durations add up and scale by ints
+    fn temporal_result(
+        op: flux_syntax::BinOp,
+        left: &TypeInfo,
+        right: &TypeInfo,
+    ) -> Option<TypeInfo> {
+        use flux_syntax::BinOp;
+        match (op, left, right) {
+            (BinOp::Add | BinOp::Sub, TypeInfo::Duration, TypeInfo::Duration) => {
+                Some(TypeInfo::Duration)
+            }
+            (BinOp::Add | BinOp::Sub, point, TypeInfo::Duration) if point.is_time_point() => {
+                Some(point.clone())
+            }
+            (BinOp::Add, TypeInfo::Duration, point) if point.is_time_point() => Some(point.clone()),
+            (BinOp::Sub, a, b) if a.is_time_point() && a == b => Some(TypeInfo::Duration),
+            (BinOp::Mul, TypeInfo::Duration, TypeInfo::Int)
+            | (BinOp::Mul, TypeInfo::Int, TypeInfo::Duration)
+            | (BinOp::Div, TypeInfo::Duration, TypeInfo::Int) => Some(TypeInfo::Duration),
+            _ => None,
+        }
+    }
+
+    fn report_operands(
+        &mut self,
+        op: flux_syntax::BinOp,
+        left: &TypeInfo,
+        right: &TypeInfo,
+        span: flux_errors::Span,
+    ) {
+        use flux_syntax::BinOp;
+        let (left, right) = (self.resolve(left), self.resolve(right));
+        let is_temporal = left.temporal_kind().is_some() || right.temporal_kind().is_some();
+        let message = match op {
+            BinOp::Add
+                if matches!(
+                    (&left, &right),
+                    (TypeInfo::Date, TypeInfo::Time) | (TypeInfo::Time, TypeInfo::Date)
+                ) =>
+            {
+                "Cannot add a Date and a Time: Flux has no naive date-times. Write a DateTime \
+                 with a zone instead, e.g. `@2026-10-16T12:00:00[Europe/Paris]`."
+                    .to_string()
+            }
+            BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div if is_temporal => format!(
+                "Cannot apply {:?} to {} and {}. A Duration moves a Date, Time, DateTime or \
+                 Timestamp, two of the same type subtract to a Duration, and Durations add up \
+                 and scale by int.",
+                op, left, right
+            ),
+            BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div => format!(
+                "Cannot apply {:?} to {} and {}. Both operands must be the same numeric type.",
+                op, left, right
+            ),
+            BinOp::Lt | BinOp::LtEq | BinOp::Gt | BinOp::GtEq => format!(
+                "Cannot compare {} and {} with {:?}. Both operands must be the same numeric or temporal type.",
+                left, right, op
+            ),
+            BinOp::Eq | BinOp::NotEq => format!(
+                "Cannot compare {} and {} with {:?}. Both operands must be the same primitive type.",
+                left, right, op
+            ),
+            BinOp::And | BinOp::Or => format!(
+                "Cannot apply {:?} to {} and {}. Both operands must be bool.",
+                op, left, right
+            ),
+        };
+        self.report(flux_errors::FluxError::TypeError {
+            message,
+            span: span.to_source_span(),
+        });
+    }
+
+    /// Check the operands deferred by `check_binary_op`. Operators whose
+    /// operand type is still unsolved are ambiguous: nothing in the function
+    /// decides between int and float.
+    fn solve_deferred(&mut self) {
+        // Solving one field access can solve the base of another
+        loop {
+            let (pending, ready): (Vec<_>, Vec<_>) = std::mem::take(&mut self.deferred_fields)
+                .into_iter()
+                .partition(|access| matches!(self.resolve(&access.base), TypeInfo::Var(_)));
+            self.deferred_fields = pending;
+            if ready.is_empty() {
+                break;
+            }
+            for access in ready {
+                let ty = self.check_field(access.base, &access.field, access.span);
+                if !self.unify(&ty, &access.ty) {
+                    let message = format!(
+                        "Field `{}` is {}, but is used as {}",
+                        access.field,
+                        self.resolve(&ty),
+                        self.resolve(&access.ty)
+                    );
+                    self.report(flux_errors::FluxError::TypeError {
+                        message,
+                        span: access.span.to_source_span(),
+                    });
+                }
+            }
+        }
+        for access in std::mem::take(&mut self.deferred_fields) {
+            self.report(flux_errors::FluxError::TypeError {
+                message: format!(
+                    "Cannot infer the record type of `.{}`; add a type annotation",
+                    access.field
+                ),
+                span: access.span.to_source_span(),
+            });
+        }
+
+        for operand in std::mem::take(&mut self.deferred) {
+            match self.resolve(&operand.ty) {
+                TypeInfo::Var(_) => {
+                    self.report(flux_errors::FluxError::TypeError {
+                        message: format!(
+                            "Cannot infer the operand type of {:?}; add a type annotation",
+                            operand.op
+                        ),
+                        span: operand.span.to_source_span(),
+                    });
+                }
+                ty if !Self::accepts_operand(operand.op, &ty) => {
+                    self.report_operands(operand.op, &ty, &ty, operand.span);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Check unary operation types
+    /// Check `operand?`, whose operand has type `operand_ty`: it unwraps an
+    /// `Option` in a function returning an `Option`, or a `Result` in one
+    /// returning a `Result` with the same error type. Which of the two is
+    /// taken from the operand, or else from the return type.
+    fn check_try(&mut self, operand_ty: TypeInfo, span: flux_errors::Span) -> TypeInfo {
+        let Some(expected) = self.expected_return.clone() else {
+            return self.report(flux_errors::FluxError::TypeError {
+                message: "`?` can only be used inside a function".to_string(),
+                span: span.to_source_span(),
+            });
+        };
+        let enum_name = |ty: &TypeInfo| match ty {
+            TypeInfo::Enum { name, .. } if name == "Option" || name == "Result" => {
+                Some(name.clone())
+            }
+            _ => None,
+        };
+        let operand = self.resolve(&operand_ty);
+        let ret = self.resolve(&expected.ty);
+        if operand == TypeInfo::Error || ret == TypeInfo::Error {
+            return TypeInfo::Error;
+        }
+        let Some(name) = enum_name(&operand).or_else(|| match operand {
+            TypeInfo::Var(_) => enum_name(&ret),
+            _ => None,
+        }) else {
+            let message = match operand {
+                TypeInfo::Var(_) => {
+                    "Cannot infer whether `?` unwraps an Option or a Result; add a type annotation"
+                        .to_string()
+                }
+                _ => format!("`?` needs an Option or a Result, found {}", operand),
+            };
+            return self.report(flux_errors::FluxError::TypeError {
+                message,
+                span: span.to_source_span(),
+            });
+        };
+
+        // `?` returns the operand's `none` or `err` as it is, so only the
+        // error type of a Result carries over to the return type
+        let value = self.fresh_var();
+        let (operand_args, ret_args) = if name == "Option" {
+            (vec![value.clone()], vec![self.fresh_var()])
+        } else {
+            let error = self.fresh_var();
+            (
+                vec![value.clone(), error.clone()],
+                vec![self.fresh_var(), error],
+            )
+        };
+        let unwrapped = TypeInfo::Enum {
+            name: name.clone(),
+            args: operand_args,
+        };
+        if !self.unify(&operand_ty, &unwrapped) {
+            return self.report(flux_errors::FluxError::TypeError {
+                message: format!("`?` needs an Option or a Result, found {}", operand),
+                span: span.to_source_span(),
+            });
+        }
+        let returned = TypeInfo::Enum {
+            name: name.clone(),
+            args: ret_args,
+        };
+        if !self.unify(&expected.ty, &returned) {
+            let message = match (enum_name(&ret), self.resolve(&operand_ty)) {
+                (Some(ret_name), TypeInfo::Enum { args, .. }) if ret_name == name => format!(
+                    "`?` returns the error {} from `{}`, which returns {}",
+                    args[1], expected.func_name, ret
+                ),
+                _ => {
+                    let kind = if name == "Option" {
+                        "an Option"
+                    } else {
+                        "a Result"
+                    };
+                    format!(
+                        "`?` on {} can only be used in a function returning {}, \
+                         but `{}` returns {}",
+                        kind, kind, expected.func_name, ret
+                    )
+                }
+            };
+            return self.report(flux_errors::FluxError::TypeError {
+                message,
+                span: span.to_source_span(),
+            });
+        }
+        value
+    }
+
+    fn check_unary_op(
+        &mut self,
+        op: flux_syntax::UnaryOp,
+        operand: TypeInfo,
+        span: flux_errors::Span,
+    ) -> TypeInfo {
+        use flux_syntax::UnaryOp;
+        match op {
+            UnaryOp::Not => {
+                if !self.unify(&operand, &TypeInfo::Bool) {
+                    let message = format!(
+                        "Cannot apply Not to {}. Operand must be bool.",
+                        self.resolve(&operand)
+                    );
+                    self.report(flux_errors::FluxError::TypeError {
+                        message,
+                        span: span.to_source_span(),
+                    });
+                }
+                TypeInfo::Bool
+            }
+        }
+    }
+
+    /// Type check a call against the callee's signature and return its
+    /// return type. The return type is known even when the arguments are
+    /// wrong, so callers keep checking against it.
+    fn check_call(
+        &mut self,
+        func: &flux_syntax::Expr,
+        args: &[flux_syntax::Expr],
+        env: &TypeEnv,
+        span: flux_errors::Span,
+    ) -> TypeInfo {
+        let func_ty = self.infer(func, env);
+        let callee = match func {
+            flux_syntax::Expr::Var { name, .. } => format!("`{}`", name),
+            _ => "function".to_string(),
+        };
+
+        let (params, ret) = match self.resolve(&func_ty) {
+            TypeInfo::Function { params, ret } => (params, ret),
+            // Calling an unsolved variable makes it a function of the arguments
+            TypeInfo::Var(var) => {
+                let params: Vec<_> = args.iter().map(|_| self.fresh_var()).collect();
+                let ret = Box::new(self.fresh_var());
+                self.bind(
+                    var,
+                    &TypeInfo::Function {
+                        params: params.clone(),
+                        ret: ret.clone(),
+                    },
+                );
+                (params, ret)
+            }
+            callee_ty => {
+                if !matches!(callee_ty, TypeInfo::Unknown | TypeInfo::Error) {
+                    self.report(flux_errors::FluxError::TypeError {
+                        message: format!("Cannot call a value of type {}", callee_ty),
+                        span: func.span().to_source_span(),
+                    });
+                }
+                for arg in args {
+                    self.infer(arg, env);
+                }
+                return match callee_ty {
+                    TypeInfo::Unknown => TypeInfo::Unknown,
+                    _ => TypeInfo::Error,
+                };
+            }
+        };
+
+        if params.len() != args.len() {
+            self.report(flux_errors::FluxError::TypeError {
+                message: format!(
+                    "{} expects {} argument{}, found {}",
+                    callee,
+                    params.len(),
+                    if params.len() == 1 { "" } else { "s" },
+                    args.len()
+                ),
+                span: span.to_source_span(),
+            });
+        }
+
+        for (index, arg) in args.iter().enumerate() {
+            let arg_ty = self.infer(arg, env);
+            let Some(param_ty) = params.get(index) else {
+                continue;
+            };
+            if !self.unify(&arg_ty, param_ty) {
+                let message = format!(
+                    "Argument {} of {} expects {}, found {}",
+                    index + 1,
+                    callee,
+                    self.resolve(param_ty),
+                    self.resolve(&arg_ty)
+                );
+                self.report(flux_errors::FluxError::TypeError {
+                    message,
+                    span: arg.span().to_source_span(),
+                });
+            }
+        }
+
+        *ret
+    }
+
+    /// Type check blocks: every statement is inferred, the last one gives
+    /// the block its type
+    fn infer_block(&mut self, stmts: &[flux_syntax::Expr], env: &TypeEnv) -> TypeInfo {
+        let mut ty = TypeInfo::Int; // Empty block returns default
+        for stmt in stmts {
+            ty = self.infer(stmt, env);
+        }
+        ty
+    }
+
+    fn fresh_var(&mut self) -> TypeInfo {
+        let var = self.substitution.len() as u32;
+        self.substitution.push(None);
+        TypeInfo::Var(var)
+    }
+
+    /// Apply the current substitution throughout `ty`
+    pub fn resolve(&self, ty: &TypeInfo) -> TypeInfo {
+        ty.replace(&mut |ty| match ty {
+            TypeInfo::Var(var) => match self.substitution.get(*var as usize) {
+                Some(Some(solved)) => Some(self.resolve(solved)),
+                _ => None,
+            },
+            _ => None,
+        })
+    }
+
+    /// Make `found` and `expected` equal by solving inference variables.
+    /// Returns `false` when the types cannot be made equal; variables solved
+    /// before the mismatch was found stay solved.
+    fn unify(&mut self, found: &TypeInfo, expected: &TypeInfo) -> bool {
+        let found = self.resolve(found);
+        let expected = self.resolve(expected);
+        match (&found, &expected) {
+            (TypeInfo::Unknown, _) | (_, TypeInfo::Unknown) => true,
+            (TypeInfo::Var(a), TypeInfo::Var(b)) if a == b => true,
+            // A variable unified with `Error` is poisoned too, so its other
+            // uses don't report the same mistake again
+            (TypeInfo::Var(var), ty) | (ty, TypeInfo::Var(var)) => self.bind(*var, ty),
+            (TypeInfo::Error, _) | (_, TypeInfo::Error) => true,
+            (TypeInfo::Column(found), TypeInfo::Column(expected))
+            | (TypeInfo::List(found), TypeInfo::List(expected)) => self.unify(found, expected),
+            (TypeInfo::Tuple(found), TypeInfo::Tuple(expected)) => self.unify_all(found, expected),
+            (
+                TypeInfo::Enum { name, args },
+                TypeInfo::Enum {
+                    name: expected_name,
+                    args: expected_args,
+                },
+            ) => name == expected_name && self.unify_all(args, expected_args),
+            // A struct is only compatible with itself. A structural record
+            // accepts any record, struct or not, with at least its fields.
+            (
+                TypeInfo::Record { name, args, .. },
+                TypeInfo::Record {
+                    name: expected_name,
+                    args: expected_args,
+                    fields: expected_fields,
+                },
+            ) => {
+                if expected_name.is_some() {
+                    return name == expected_name && self.unify_all(args, expected_args);
+                }
+                expected_fields
+                    .iter()
+                    .all(|(field, expected)| match found.field(field) {
+                        Some(found) => self.unify(&found.clone(), expected),
+                        None => false,
+                    })
+            }
+            (
+                TypeInfo::Function { params, ret },
+                TypeInfo::Function {
+                    params: expected_params,
+                    ret: expected_ret,
+                },
+            ) => self.unify_all(params, expected_params) && self.unify(ret, expected_ret),
+            _ => found == expected,
+        }
+    }
+
+    /// Unify two lists of types pairwise; lists of different lengths never
+    /// unify
+    fn unify_all(&mut self, found: &[TypeInfo], expected: &[TypeInfo]) -> bool {
+        found.len() == expected.len()
+            && found
+                .iter()
+                .zip(expected)
+                .all(|(found, expected)| self.unify(found, expected))
+    }
+
+    /// Solve `var` as the resolved type `ty`
+    fn bind(&mut self, var: u32, ty: &TypeInfo) -> bool {
+        // `'a = ('a) -> int` has no finite solution
+        if ty.type_vars().contains(&var) {
+            return false;
+        }
+        let index = var as usize;
+        if index >= self.substitution.len() {
+            self.substitution.resize(index + 1, None);
+        }
+        self.substitution[index] = Some(ty.clone());
+        true
+    }
+
+    /// Quantify the variables of `ty` that no binding in `env` mentions
+    fn generalize(&self, ty: &TypeInfo, env: &TypeEnv) -> TypeScheme {
+        let ty = self.resolve(ty);
+        // Quantified variables are resolved around: their numbers can be
+        // reused by this checker
+        let in_env: Vec<u32> = env
+            .bindings
+            .values()
+            .flat_map(|scheme| {
+                scheme
+                    .ty
+                    .type_vars()
+                    .into_iter()
+                    .filter(|var| !scheme.vars.contains(var))
+                    .flat_map(|var| self.resolve(&TypeInfo::Var(var)).type_vars())
+            })
+            .collect();
+        let vars = ty
+            .type_vars()
+            .into_iter()
+            .filter(|var| !in_env.contains(var))
+            .collect();
+        TypeScheme {
+            vars,
+            params: Vec::new(),
+            ty,
+        }
+    }
+
+    /// Replace the quantified variables and type parameters of `scheme`
+    /// with fresh variables. Returns the type and the variables standing for
+    /// the type parameters, in order.
+    fn instantiate(&mut self, scheme: &TypeScheme) -> (TypeInfo, Vec<TypeInfo>) {
+        let mapping: HashMap<u32, TypeInfo> = scheme
+            .vars
+            .iter()
+            .map(|var| (*var, self.fresh_var()))
+            .collect();
+        let type_args: Vec<TypeInfo> = scheme.params.iter().map(|_| self.fresh_var()).collect();
+        let params = scheme
+            .params
+            .iter()
+            .cloned()
+            .zip(type_args.clone())
+            .collect();
+        let ty = scheme.ty.substitute(&mapping).substitute_params(&params);
+        (ty, type_args)
+    }
+
+    /// Replace the type parameters of a declared type, which its record
+    /// type and the types of its constructors mention, with fresh variables
+    fn instantiate_params(&mut self, ty: &TypeInfo) -> TypeInfo {
+        let mut fresh: HashMap<String, TypeInfo> = HashMap::new();
+        ty.replace(&mut |ty| match ty {
+            TypeInfo::Param(name) => Some(
+                fresh
+                    .entry(name.clone())
+                    .or_insert_with(|| self.fresh_var())
+                    .clone(),
+            ),
+            _ => None,
+        })
+    }
+}
+
+/// Group the functions of a file into the strongly connected components of
+/// their call graph, each group after every group it calls into
+pub(crate) fn dependency_groups(functions: &[&flux_syntax::Function]) -> Vec<Vec<usize>> {
+    struct Tarjan<'a> {
+        calls: &'a [Vec<usize>],
+        index: Vec<Option<usize>>,
+        lowlink: Vec<usize>,
+        on_stack: Vec<bool>,
+        stack: Vec<usize>,
+        next_index: usize,
+        groups: Vec<Vec<usize>>,
+    }
+
+    impl Tarjan<'_> {
+        fn visit(&mut self, v: usize) {
+            self.index[v] = Some(self.next_index);
+            self.lowlink[v] = self.next_index;
+            self.next_index += 1;
+            self.stack.push(v);
+            self.on_stack[v] = true;
+
+            let calls = self.calls;
+            for &w in &calls[v] {
+                match self.index[w] {
+                    None => {
+                        self.visit(w);
+                        self.lowlink[v] = self.lowlink[v].min(self.lowlink[w]);
+                    }
+                    Some(index) if self.on_stack[w] => {
+                        self.lowlink[v] = self.lowlink[v].min(index);
+                    }
+                    Some(_) => {}
+                }
+            }
+
+            if self.index[v] == Some(self.lowlink[v]) {
+                let mut group = Vec::new();
+                while let Some(w) = self.stack.pop() {
+                    self.on_stack[w] = false;
+                    group.push(w);
+                    if w == v {
+                        break;
+                    }
+                }
+                group.sort_unstable();
+                self.groups.push(group);
+            }
+        }
+    }
+
+    let by_name: HashMap<&str, usize> = functions
+        .iter()
+        .enumerate()
+        .map(|(i, func)| (func.name.as_str(), i))
+        .collect();
+    // Shadowing is ignored: a spurious edge only merges groups
+    let calls: Vec<Vec<usize>> = functions
+        .iter()
+        .map(|func| {
+            let mut names = Vec::new();
+            referenced_names(&func.body, &mut names);
+            names
+                .iter()
+                .filter_map(|name| by_name.get(name.as_str()).copied())
+                .collect()
+        })
+        .collect();
+
+    let mut tarjan = Tarjan {
+        calls: &calls,
+        index: vec![None; functions.len()],
+        lowlink: vec![0; functions.len()],
+        on_stack: vec![false; functions.len()],
+        stack: Vec::new(),
+        next_index: 0,
+        groups: Vec::new(),
+    };
+    for v in 0..functions.len() {
+        if tarjan.index[v].is_none() {
+            tarjan.visit(v);
+        }
+    }
+    tarjan.groups
+}
+
+/// Collect every variable name an expression refers to
+pub(crate) fn referenced_names(expr: &flux_syntax::Expr, out: &mut Vec<String>) {
+    use flux_syntax::Expr;
+    match expr {
+        Expr::Var { name, .. } => out.push(name.clone()),
+        Expr::Unary { operand, .. } | Expr::Try { operand, .. } => referenced_names(operand, out),
+        Expr::Binary { left, right, .. } | Expr::Pipeline { left, right, .. } => {
+            referenced_names(left, out);
+            referenced_names(right, out);
+        }
+        Expr::Call { func, args, .. } => {
+            referenced_names(func, out);
+            for arg in args {
+                referenced_names(arg, out);
+            }
+        }
+        Expr::Let { value, body, .. } => {
+            referenced_names(value, out);
+            referenced_names(body, out);
+        }
+        Expr::If {
+            cond,
+            then_branch,
+            else_branch,
+            ..
+        } => {
+            referenced_names(cond, out);
+            referenced_names(then_branch, out);
+            referenced_names(else_branch, out);
+        }
+        Expr::Block { stmts, .. } => {
+            for stmt in stmts {
+                referenced_names(stmt, out);
+            }
+        }
+        Expr::Return { value, .. } => referenced_names(value, out),
+        Expr::Record { fields, .. } => {
+            for field in fields {
+                referenced_names(&field.value, out);
+            }
+        }
+        Expr::Field { base, .. } => referenced_names(base, out),
+        Expr::List { elems, .. } | Expr::Tuple { elems, .. } => {
+            for elem in elems {
+                referenced_names(elem, out);
+            }
+        }
+        Expr::Index { base, index, .. } => {
+            referenced_names(base, out);
+            referenced_names(index, out);
+        }
+        // Names bound by patterns and lambda parameters are not told apart
+        // from references
+        Expr::Lambda { body, .. } => referenced_names(body, out),
+        Expr::Match {
+            scrutinee, arms, ..
+        } => {
+            referenced_names(scrutinee, out);
+            for arm in arms {
+                referenced_names(&arm.body, out);
+            }
+        }
+        Expr::Int { .. }
+        | Expr::Float { .. }
+        | Expr::Bool { .. }
+        | Expr::String { .. }
+        | Expr::Temporal { .. }
+        | Expr::Column { .. }
+        | Expr::Error { .. } => {}
+    }
+}
+
+impl Default for TypeChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_type_info_display() {
+        // Test all TypeInfo variants for completeness
+        assert_eq!(TypeInfo::Int.to_string(), "int");
+        assert_eq!(TypeInfo::String.to_string(), "string");
+        assert_eq!(TypeInfo::Bool.to_string(), "bool");
+        assert_eq!(TypeInfo::Float.to_string(), "float");
+        assert_eq!(
+            TypeInfo::Named {
+                name: "MyType".to_string()
+            }
+            .to_string(),
+            "MyType"
+        );
+        assert_eq!(
+            TypeInfo::Column(Box::new(TypeInfo::Bool)).to_string(),
+            "Column<bool>"
+        );
+        assert_eq!(TypeInfo::Var(1).to_string(), "'b");
+        assert_eq!(TypeInfo::Var(30).to_string(), "'t30");
+        assert_eq!(TypeInfo::Unknown.to_string(), "?");
+    }
+
+    #[test]
+    fn test_function_with_bool_float() {
+        let func_type = TypeInfo::Function {
+            params: vec![TypeInfo::Bool, TypeInfo::Float],
+            ret: Box::new(TypeInfo::Int),
+        };
+        assert_eq!(func_type.to_string(), "(bool, float) -> int");
+    }
+
+    #[test]
+    fn test_type_env_insert_and_get() {
+        let mut env = TypeEnv::new();
+        env.insert("x".to_string(), TypeInfo::Int);
+        assert_eq!(env.get("x"), Some(&TypeInfo::Int));
+        assert_eq!(env.get("y"), None);
+    }
+
+    #[test]
+    fn test_type_checker_infer_literals() {
+        let mut checker = TypeChecker::new();
+        let env = TypeEnv::new();
+
+        let int_expr = flux_syntax::Expr::Int {
+            value: 42,
+            span: flux_errors::Span::new(0, 2),
+        };
+        assert_eq!(checker.infer_expr(&int_expr, &env), TypeInfo::Int);
+
+        let float_expr = flux_syntax::Expr::Float {
+            value: 1.414,
+            span: flux_errors::Span::new(0, 4),
+        };
+        assert_eq!(checker.infer_expr(&float_expr, &env), TypeInfo::Float);
+
+        let bool_expr = flux_syntax::Expr::Bool {
+            value: true,
+            span: flux_errors::Span::new(0, 4),
+        };
+        assert_eq!(checker.infer_expr(&bool_expr, &env), TypeInfo::Bool);
+    }
+
+    #[test]
+    fn test_type_error_int_plus_float() {
+        let mut checker = TypeChecker::new();
+        let env = TypeEnv::new();
+
+        let left = flux_syntax::Expr::Int {
+            value: 10,
+            span: flux_errors::Span::new(0, 2),
+        };
+        let right = flux_syntax::Expr::Float {
+            value: 3.5,
+            span: flux_errors::Span::new(5, 8),
+        };
+        let binary = flux_syntax::Expr::Binary {
+            op: flux_syntax::BinOp::Add,
+            left: Box::new(left),
+            right: Box::new(right),
+            span: flux_errors::Span::new(0, 8),
+        };
+
+        checker.infer_expr(&binary, &env);
+        match checker.errors() {
+            [flux_errors::FluxError::TypeError { message, .. }] => {
+                assert!(message.contains("Cannot apply"));
+            }
+            _ => panic!("Expected TypeError"),
+        }
+    }
+
+    #[test]
+    fn test_type_check_valid_addition() {
+        let mut checker = TypeChecker::new();
+        let env = TypeEnv::new();
+
+        let left = flux_syntax::Expr::Int {
+            value: 10,
+            span: flux_errors::Span::new(0, 2),
+        };
+        let right = flux_syntax::Expr::Int {
+            value: 32,
+            span: flux_errors::Span::new(5, 7),
+        };
+        let binary = flux_syntax::Expr::Binary {
+            op: flux_syntax::BinOp::Add,
+            left: Box::new(left),
+            right: Box::new(right),
+            span: flux_errors::Span::new(0, 7),
+        };
+
+        assert_eq!(checker.infer_expr(&binary, &env), TypeInfo::Int);
+        assert!(checker.errors().is_empty());
+    }
+
+    #[test]
+    fn test_type_check_let_binding() {
+        let mut checker = TypeChecker::new();
+        let env = TypeEnv::new();
+
+        let value = flux_syntax::Expr::Int {
+            value: 42,
+            span: flux_errors::Span::new(8, 10),
+        };
+        let body_var = flux_syntax::Expr::Var {
+            name: "x".to_string(),
+            span: flux_errors::Span::new(11, 12),
+        };
+        let let_expr = flux_syntax::Expr::Let {
+            name: "x".to_string(),
+            value: Box::new(value),
+            body: Box::new(body_var),
+            span: flux_errors::Span::new(0, 12),
+        };
+
+        assert_eq!(checker.infer_expr(&let_expr, &env), TypeInfo::Int);
+        assert!(checker.errors().is_empty());
+    }
+
+    #[test]
+    fn test_type_check_comparison_yields_bool() {
+        let mut checker = TypeChecker::new();
+        let env = TypeEnv::new();
+
+        let left = flux_syntax::Expr::Float {
+            value: 1.5,
+            span: flux_errors::Span::new(0, 3),
+        };
+        let right = flux_syntax::Expr::Float {
+            value: 2.5,
+            span: flux_errors::Span::new(6, 9),
+        };
+        let binary = flux_syntax::Expr::Binary {
+            op: flux_syntax::BinOp::LtEq,
+            left: Box::new(left),
+            right: Box::new(right),
+            span: flux_errors::Span::new(0, 9),
+        };
+
+        assert_eq!(checker.infer_expr(&binary, &env), TypeInfo::Bool);
+    }
+
+    #[test]
+    fn test_type_error_compare_int_and_bool() {
+        let mut checker = TypeChecker::new();
+        let env = TypeEnv::new();
+
+        let left = flux_syntax::Expr::Int {
+            value: 1,
+            span: flux_errors::Span::new(0, 1),
+        };
+        let right = flux_syntax::Expr::Bool {
+            value: true,
+            span: flux_errors::Span::new(5, 9),
+        };
+        let binary = flux_syntax::Expr::Binary {
+            op: flux_syntax::BinOp::Eq,
+            left: Box::new(left),
+            right: Box::new(right),
+            span: flux_errors::Span::new(0, 9),
+        };
+
+        checker.infer_expr(&binary, &env);
+        match checker.errors() {
+            [flux_errors::FluxError::TypeError { message, .. }] => {
+                assert!(message.contains("Cannot compare int and bool"));
+            }
+            _ => panic!("Expected TypeError"),
+        }
+    }
+
+    #[test]
+    fn test_type_check_logical_operators() {
+        let mut checker = TypeChecker::new();
+        let mut env = TypeEnv::new();
+        env.insert("flag".to_string(), TypeInfo::Bool);
+        env.insert("count".to_string(), TypeInfo::Int);
+
+        let not_flag = flux_syntax::Expr::Unary {
+            op: flux_syntax::UnaryOp::Not,
+            operand: Box::new(flux_syntax::Expr::Var {
+                name: "flag".to_string(),
+                span: flux_errors::Span::new(1, 5),
+            }),
+            span: flux_errors::Span::new(0, 5),
+        };
+        let and_expr = flux_syntax::Expr::Binary {
+            op: flux_syntax::BinOp::And,
+            left: Box::new(not_flag),
+            right: Box::new(flux_syntax::Expr::Bool {
+                value: true,
+                span: flux_errors::Span::new(9, 13),
+            }),
+            span: flux_errors::Span::new(0, 13),
+        };
+        assert_eq!(checker.infer_expr(&and_expr, &env), TypeInfo::Bool);
+
+        let or_int = flux_syntax::Expr::Binary {
+            op: flux_syntax::BinOp::Or,
+            left: Box::new(flux_syntax::Expr::Var {
+                name: "count".to_string(),
+                span: flux_errors::Span::new(0, 5),
+            }),
+            right: Box::new(flux_syntax::Expr::Bool {
+                value: false,
+                span: flux_errors::Span::new(9, 14),
+            }),
+            span: flux_errors::Span::new(0, 14),
+        };
+        assert!(checker.errors().is_empty());
+        checker.infer_expr(&or_int, &env);
+        assert_eq!(checker.errors().len(), 1);
+    }
+
+    fn if_expr(
+        cond: flux_syntax::Expr,
+        then_value: flux_syntax::Expr,
+        else_value: flux_syntax::Expr,
+    ) -> flux_syntax::Expr {
+        flux_syntax::Expr::If {
+            cond: Box::new(cond),
+            then_branch: Box::new(flux_syntax::Expr::Block {
+                stmts: vec![then_value],
+                span: flux_errors::Span::new(5, 10),
+            }),
+            else_branch: Box::new(flux_syntax::Expr::Block {
+                stmts: vec![else_value],
+                span: flux_errors::Span::new(16, 21),
+            }),
+            span: flux_errors::Span::new(0, 21),
+        }
+    }
+
+    #[test]
+    fn test_type_check_if_unifies_branches() {
+        let mut checker = TypeChecker::new();
+        let mut env = TypeEnv::new();
+        env.insert("flag".to_string(), TypeInfo::Bool);
+
+        let expr = if_expr(
+            flux_syntax::Expr::Var {
+                name: "flag".to_string(),
+                span: flux_errors::Span::new(3, 7),
+            },
+            flux_syntax::Expr::Float {
+                value: 1.0,
+                span: flux_errors::Span::new(6, 9),
+            },
+            flux_syntax::Expr::Float {
+                value: 2.0,
+                span: flux_errors::Span::new(17, 20),
+            },
+        );
+        assert_eq!(checker.infer_expr(&expr, &env), TypeInfo::Float);
+    }
+
+    #[test]
+    fn test_type_error_if_condition_not_bool() {
+        let mut checker = TypeChecker::new();
+        let env = TypeEnv::new();
+
+        let expr = if_expr(
+            flux_syntax::Expr::Int {
+                value: 1,
+                span: flux_errors::Span::new(3, 4),
+            },
+            flux_syntax::Expr::Int {
+                value: 1,
+                span: flux_errors::Span::new(6, 7),
+            },
+            flux_syntax::Expr::Int {
+                value: 2,
+                span: flux_errors::Span::new(17, 18),
+            },
+        );
+        checker.infer_expr(&expr, &env);
+        match checker.errors() {
+            [flux_errors::FluxError::TypeError { message, .. }] => {
+                assert!(message.contains("If condition must be bool"));
+            }
+            _ => panic!("Expected TypeError"),
+        }
+    }
+
+    #[test]
+    fn test_type_error_if_branch_mismatch() {
+        let mut checker = TypeChecker::new();
+        let env = TypeEnv::new();
+
+        let expr = if_expr(
+            flux_syntax::Expr::Bool {
+                value: true,
+                span: flux_errors::Span::new(3, 7),
+            },
+            flux_syntax::Expr::Int {
+                value: 1,
+                span: flux_errors::Span::new(6, 7),
+            },
+            flux_syntax::Expr::String {
+                value: "no".to_string(),
+                span: flux_errors::Span::new(17, 21),
+            },
+        );
+        checker.infer_expr(&expr, &env);
+        match checker.errors() {
+            [flux_errors::FluxError::TypeError { message, .. }] => {
+                assert!(message.contains("incompatible types"));
+            }
+            _ => panic!("Expected TypeError"),
+        }
+    }
+
+    #[test]
+    fn test_type_check_column_against_schema() {
+        let mut checker = TypeChecker::new();
+        let active = flux_syntax::Expr::Column {
+            name: "active".to_string(),
+            span: flux_errors::Span::new(0, 7),
+        };
+
+        // Without a schema the element type is unknown
+        let env = TypeEnv::new();
+        assert_eq!(
+            checker.infer_expr(&active, &env),
+            TypeInfo::Column(Box::new(TypeInfo::Unknown))
+        );
+
+        let mut schema = Schema::new();
+        schema.insert("active".to_string(), TypeInfo::Bool);
+        assert_eq!("active: bool".parse(), Ok(schema.clone()));
+        assert!("active".parse::<Schema>().is_err());
+        let mut env = TypeEnv::new();
+        env.set_schema(schema);
+        assert_eq!(
+            checker.infer_expr(&active, &env),
+            TypeInfo::Column(Box::new(TypeInfo::Bool))
+        );
+
+        let missing = flux_syntax::Expr::Column {
+            name: "order total".to_string(),
+            span: flux_errors::Span::new(0, 14),
+        };
+        checker.infer_expr(&missing, &env);
+        match checker.errors() {
+            [flux_errors::FluxError::TypeError { message, .. }] => {
+                assert!(message.contains("Unknown column #order total"));
+            }
+            _ => panic!("Expected TypeError"),
+        }
+    }
+
+    /// Infer the body of `main` with every function of `source` in scope
+    fn infer_main(source: &str) -> Result<TypeInfo, Vec<flux_errors::FluxError>> {
+        let ast = flux_syntax::parse(source).unwrap();
+        let env = TypeEnv::from_source_file(&ast);
+        let main = ast.functions().find(|func| func.name == "main").unwrap();
+        let mut checker = TypeChecker::new();
+        let ty = checker.infer_expr(&main.body, &env);
+        let errors = checker.into_errors();
+        if errors.is_empty() {
+            Ok(ty)
+        } else {
+            Err(errors)
+        }
+    }
+
+    #[test]
+    fn test_call_returns_declared_type() {
+        let source = r#"
+            fn add(x: int, y: int) -> int { return x + y }
+            fn main() -> int { return add(1, 2) + 3 }
+        "#;
+        assert_eq!(infer_main(source).unwrap(), TypeInfo::Int);
+    }
+
+    #[test]
+    fn test_call_result_feeds_chained_expressions() {
+        let source = r#"
+            fn half(x: float) -> float { return x / 2.0 }
+            fn main() -> int { return half(1.0) + 1 }
+        "#;
+        match infer_main(source).unwrap_err().as_slice() {
+            [flux_errors::FluxError::TypeError { message, .. }] => {
+                assert!(message.contains("Cannot apply Add to float and int"));
+            }
+            _ => panic!("Expected TypeError"),
+        }
+    }
+
+    #[test]
+    fn test_call_arity_mismatch() {
+        let source = r#"
+            fn add(x: int, y: int) -> int { return x + y }
+            fn main() -> int { return add(1) }
+        "#;
+        match infer_main(source).unwrap_err().as_slice() {
+            [flux_errors::FluxError::TypeError { message, .. }] => {
+                assert_eq!(message, "`add` expects 2 arguments, found 1");
+            }
+            _ => panic!("Expected TypeError"),
+        }
+    }
+
+    #[test]
+    fn test_call_argument_mismatch_points_at_argument() {
+        let source = "fn add(x: int, y: int) -> int { return x + y } fn main() -> int { return add(1, true) }";
+        match infer_main(source).unwrap_err().as_slice() {
+            [flux_errors::FluxError::TypeError { message, span }] => {
+                assert_eq!(message, "Argument 2 of `add` expects int, found bool");
+                assert_eq!(span.offset(), source.find("true").unwrap());
+                assert_eq!(span.len(), 4);
+            }
+            _ => panic!("Expected TypeError"),
+        }
+    }
+
+    #[test]
+    fn test_call_through_pipeline_and_unannotated_params() {
+        let source = r#"
+            fn scale(x: int, factor: int) -> int { return x * factor }
+            fn apply(f, x) { return x }
+            fn main() -> int { return 2 |> scale(3) |> apply(scale) }
+        "#;
+        assert_eq!(infer_main(source).unwrap(), TypeInfo::Unknown);
+
+        let source = r#"
+            fn scale(x: int, factor: int) -> int { return x * factor }
+            fn main() -> int { return 2.0 |> scale(3) }
+        "#;
+        assert!(infer_main(source).is_err());
+    }
+
+    #[test]
+    fn test_call_non_function() {
+        let mut env = TypeEnv::new();
+        env.insert("x".to_string(), TypeInfo::Int);
+        let call = flux_syntax::Expr::Call {
+            func: Box::new(flux_syntax::Expr::Var {
+                name: "x".to_string(),
+                span: flux_errors::Span::new(0, 1),
+            }),
+            args: vec![],
+            span: flux_errors::Span::new(0, 3),
+        };
+        let mut checker = TypeChecker::new();
+        checker.infer_expr(&call, &env);
+        match checker.errors() {
+            [flux_errors::FluxError::TypeError { message, .. }] => {
+                assert_eq!(message, "Cannot call a value of type int");
+            }
+            _ => panic!("Expected TypeError"),
+        }
+    }
+
+    #[test]
+    fn test_check_types_trailing_expression_mismatch() {
+        let source = r#"fn f() -> int { "x" }"#;
+        let ast = flux_syntax::parse(source).unwrap();
+        let errors = check_types(&ast);
+
+        assert_eq!(errors.len(), 1);
+        match &errors[0] {
+            flux_errors::FluxError::ReturnTypeMismatch {
+                message,
+                expected,
+                found,
+                span,
+                annotation,
+            } => {
+                assert_eq!(message, "`f` must return int, found string");
+                assert_eq!(expected, "int");
+                assert_eq!(found, "string");
+                assert_eq!(span.offset(), source.find('"').unwrap());
+                assert_eq!(annotation.offset(), source.find("int").unwrap());
+            }
+            other => panic!("Expected ReturnTypeMismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_check_types_early_return_mismatch() {
+        let source = r#"
+            fn pick(flag: bool) -> float {
+                if flag { return 1 } else { return 2.0 }
+            }
+        "#;
+        let ast = flux_syntax::parse(source).unwrap();
+        let errors = check_types(&ast);
+
+        assert_eq!(errors.len(), 1);
+        match &errors[0] {
+            flux_errors::FluxError::ReturnTypeMismatch { found, span, .. } => {
+                assert_eq!(found, "int");
+                assert_eq!(span.offset(), source.find("1 }").unwrap());
+            }
+            other => panic!("Expected ReturnTypeMismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_check_types_binds_parameters() {
+        let source = r#"
+            fn add(x: int, y: int) -> int { return x + y }
+            fn bad_add(x: int, y: float) -> float { return x + y }
+            fn plan(ctx) -> Project { return ctx }
+        "#;
+        let ast = flux_syntax::parse(source).unwrap();
+        let errors = check_types(&ast);
+
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            &errors[0],
+            flux_errors::FluxError::TypeError { message, .. } if message.contains("Cannot apply Add to int and float")
+        ));
+    }
+
+    /// Inferred signature of `name`, with quantified variables renamed
+    fn signature(types: &FileTypes, name: &str) -> String {
+        types.signatures[name].to_string()
+    }
+
+    #[test]
+    fn test_infer_unannotated_params_from_usage() {
+        let source = r#"
+            fn scale(x: int, factor: int) -> int { return x * factor }
+            fn apply(value, flag) { if flag { scale(value, 2) } else { 0 } }
+        "#;
+        let types = infer_file(&flux_syntax::parse(source).unwrap());
+
+        assert!(types.errors.is_empty(), "{:?}", types.errors);
+        assert_eq!(signature(&types, "apply"), "(int, bool) -> int");
+    }
+
+    #[test]
+    fn test_infer_higher_order_params() {
+        let source = "fn map(values, f) { f(values) }";
+        let types = infer_file(&flux_syntax::parse(source).unwrap());
+
+        assert!(types.errors.is_empty(), "{:?}", types.errors);
+        assert_eq!(signature(&types, "map"), "('a, ('a) -> 'b) -> 'b");
+    }
+
+    #[test]
+    fn test_generalized_function_used_at_several_types() {
+        let source = r#"
+            fn id(x) { x }
+            fn main() -> int {
+                let pick = id
+                if pick(true) { id(1) } else { pick(2) }
+            }
+        "#;
+        let types = infer_file(&flux_syntax::parse(source).unwrap());
+
+        assert!(types.errors.is_empty(), "{:?}", types.errors);
+        assert_eq!(signature(&types, "id"), "('a) -> 'a");
+    }
+
+    #[test]
+    fn test_infer_mutually_recursive_functions() {
+        let source = r#"
+            fn is_even(n) { if n == 0 { true } else { is_odd(n - 1) } }
+            fn is_odd(n) { if n == 0 { false } else { is_even(n - 1) } }
+        "#;
+        let types = infer_file(&flux_syntax::parse(source).unwrap());
+
+        assert!(types.errors.is_empty(), "{:?}", types.errors);
+        assert_eq!(signature(&types, "is_even"), "(int) -> bool");
+        assert_eq!(signature(&types, "is_odd"), "(int) -> bool");
+    }
+
+    #[test]
+    fn test_cannot_infer_ambiguous_operands() {
+        let source = "fn add(x, y) { x + y }";
+        let errors = check_types(&flux_syntax::parse(source).unwrap());
+
+        assert_eq!(errors.len(), 1);
+        match &errors[0] {
+            flux_errors::FluxError::TypeError { message, span } => {
+                assert_eq!(
+                    message,
+                    "Cannot infer the operand type of Add; add a type annotation"
+                );
+                assert_eq!(span.offset(), source.find("x + y").unwrap());
+            }
+            other => panic!("Expected TypeError, got {:?}", other),
+        }
+
+        // One annotation settles it
+        let source = "fn add(x: float, y) { x + y }";
+        assert!(check_types(&flux_syntax::parse(source).unwrap()).is_empty());
+    }
+
+    #[test]
+    fn test_cannot_infer_exported_signature() {
+        let source = "export fn plan(ctx) -> int { 0 }";
+        let errors = check_types(&flux_syntax::parse(source).unwrap());
+
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            &errors[0],
+            flux_errors::FluxError::TypeError { message, .. }
+                if message == "Cannot infer the type of parameter `ctx` of exported function `plan`"
+        ));
+    }
+
+    #[test]
+    fn test_inferred_return_type_mismatch() {
+        let source = r#"fn pick(flag: bool) { if flag { return 1 } else { return "no" } }"#;
+        let errors = check_types(&flux_syntax::parse(source).unwrap());
+
+        assert_eq!(errors.len(), 1);
+        match &errors[0] {
+            flux_errors::FluxError::TypeError { message, span } => {
+                assert_eq!(message, "`pick` returns string here but int elsewhere");
+                assert_eq!(span.offset(), source.find('"').unwrap());
+            }
+            other => panic!("Expected TypeError, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_occurs_check_rejects_infinite_type() {
+        let source = "fn apply_self(f) { f(f) }";
+        let errors = check_types(&flux_syntax::parse(source).unwrap());
+
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            &errors[0],
+            flux_errors::FluxError::TypeError { message, .. } if message.starts_with("Argument 1 of `f`")
+        ));
+    }
+
+    #[test]
+    fn test_check_types_reports_every_error() {
+        let source = r#"
+            fn first(x: int) -> int {
+                let a = x + 1.5
+                let b = true && 1
+                a
+            }
+            fn second(flag: bool) -> string { if flag { 1 } else { 2 } }
+        "#;
+        let errors = check_types(&flux_syntax::parse(source).unwrap());
+
+        let messages: Vec<_> = errors.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            messages,
+            [
+                "Type error: Cannot apply Add to int and float. Both operands must be the same numeric type.",
+                "Type error: Cannot apply And to bool and int. Both operands must be bool.",
+                "Type error: `second` must return string, found int",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_error_type_does_not_cascade() {
+        let source = r#"
+            fn poisoned(x: int) -> int {
+                let y = x + "s"
+                if y > 2 { y * 2 } else { double(y) }
+            }
+            fn double(n: int) -> int { n * 2 }
+            fn unknown() -> bool { missing + 1 == missing }
+        "#;
+        let errors = check_types(&flux_syntax::parse(source).unwrap());
+
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert!(matches!(
+            &errors[0],
+            flux_errors::FluxError::TypeError { message, .. } if message.contains("int and string")
+        ));
+    }
+
+    #[test]
+    fn test_temporal_arithmetic() {
+        let source = r#"
+            fn elapsed(start: Timestamp, end: Timestamp) -> Duration { end - start }
+            fn due(day: Date) -> Date { day + 2w - 1d }
+            fn later(at: DateTime) -> DateTime { 3 * 1h + at }
+            fn lunch() -> Time { @12:00 + 30m }
+            fn before(a: Timestamp) -> bool { a < @2026-10-16T12:00:00Z }
+            fn shift(at: Timestamp, by) { at + by }
+        "#;
+        let types = infer_file(&flux_syntax::parse(source).unwrap());
+
+        assert!(types.errors.is_empty(), "{:?}", types.errors);
+        assert_eq!(
+            signature(&types, "elapsed"),
+            "(Timestamp, Timestamp) -> Duration"
+        );
+        assert_eq!(
+            signature(&types, "shift"),
+            "(Timestamp, Duration) -> Timestamp"
+        );
+    }
+
+    #[test]
+    fn test_temporal_arithmetic_errors() {
+        let source = r#"
+            fn naive(day: Date, at: Time) -> int { day + at; 0 }
+            fn mixed(a: Timestamp, b: DateTime) -> int { a - b; 0 }
+            fn sum(a: Timestamp, b: Timestamp) -> int { a + b; 0 }
+            fn compare(a: Date, b: Timestamp) -> bool { a < b }
+        "#;
+        let errors = check_types(&flux_syntax::parse(source).unwrap());
+
+        let messages: Vec<_> = errors.iter().map(ToString::to_string).collect();
+        assert_eq!(messages.len(), 4, "{:?}", messages);
+        assert!(messages[0].contains("Flux has no naive date-times"));
+        assert!(messages[1].starts_with("Type error: Cannot apply Sub to Timestamp and DateTime."));
+        assert!(messages[2].starts_with("Type error: Cannot apply Add to Timestamp and Timestamp."));
+        assert!(messages[3].contains("same numeric or temporal type"));
+    }
+
+    #[test]
+    fn test_dates_move_by_whole_days() {
+        let source = r#"
+            fn tomorrow(day: Date) -> Date { day + 24h }
+            fn later(day: Date) -> Date { day + 3h }
+            fn earlier(day: Date) -> Date { day - 1d 30m }
+            fn sooner(day: Date) -> Date { 1ns + day }
+            fn meeting(at: DateTime) -> DateTime { at + 3h }
+        "#;
+        let errors = check_types(&flux_syntax::parse(source).unwrap());
+
+        let messages: Vec<_> = errors.iter().map(ToString::to_string).collect();
+        assert_eq!(messages.len(), 3, "{:?}", messages);
+        assert!(messages
+            .iter()
+            .all(|message| message.starts_with("Type error: A Date moves by whole days")));
+    }
+
+    #[test]
+    fn test_dates_move_by_durations_known_to_be_whole_days() {
+        let source = r#"
+            fn weeks(day: Date, n: int) -> Date { day + n * 1w }
+            fn again(day: Date, from: Date, to: Date) -> Date { day + (to - from) }
+            fn bound(day: Date) -> Date { let step = 2d + 1w  day - step * 3 }
+            fn either(day: Date, soon: bool) -> Date { day + if soon { 1d } else { { 2d } } }
+            fn by(day: Date, step: Duration) -> Date { day + step }
+            fn half(day: Date) -> Date { day + 1d / 2 }
+            fn shadowed(day: Date, step: Duration) -> Date {
+                let step = 1d
+                let move = fn(step: Duration) { day + step }
+                move(step)
+            }
+        "#;
+        let errors = check_types(&flux_syntax::parse(source).unwrap());
+
+        let messages: Vec<_> = errors.iter().map(ToString::to_string).collect();
+        assert_eq!(messages.len(), 3, "{:?}", messages);
+        assert!(messages.iter().all(|message| message
+            .starts_with("Type error: A Date moves by whole days, but this Duration may have")));
+    }
+
+    #[test]
+    fn test_durations_have_no_months() {
+        let source = r#"
+            fn later(day: Date) -> Date { day + 1mo }
+            fn year() -> Duration { 1y 2d }
+        "#;
+        let errors = check_types(&flux_syntax::parse(source).unwrap());
+
+        let messages: Vec<_> = errors.iter().map(ToString::to_string).collect();
+        assert_eq!(messages.len(), 2, "{:?}", messages);
+        assert!(messages
+            .iter()
+            .all(|message| message.starts_with("Type error: Months and years have no fixed")));
+    }
+
+    #[test]
+    fn test_unknown_time_zones() {
+        let source = r#"
+            fn paris() -> DateTime { @2024-01-01T00:00[Europe/Paris] }
+            fn offset() -> DateTime { @2024-01-01T00:00[-05:00] }
+            fn utc() -> DateTime { @2024-01-01T00:00[UTC] }
+            fn unknown() -> DateTime { @2024-01-01T00:00[Not/AZone] }
+            fn too_far() -> DateTime { @2024-01-01T00:00[+15:00] }
+        "#;
+        let errors = check_types(&flux_syntax::parse(source).unwrap());
+
+        let messages: Vec<_> = errors.iter().map(ToString::to_string).collect();
+        assert_eq!(messages.len(), 2, "{:?}", messages);
+        assert!(messages[0].starts_with("Type error: Unknown time zone `Not/AZone`."));
+        assert!(messages[1].starts_with("Type error: Unknown time zone `+15:00`."));
+    }
+
+    #[test]
+    fn test_structural_records_accept_wider_records() {
+        let source = r#"
+            type Point = { x: float, y: float }
+            struct Pixel { x: float, y: float, color: int }
+            fn norm(p: Point) -> float { p.x * p.x + p.y * p.y }
+            fn origin() -> Point { { x: 0.0, y: 0.0 } }
+            fn main() -> float {
+                norm({ x: 3.0, y: 4.0, z: 5.0 }) + norm(Pixel { x: 1.0, y: 1.0, color: 2 })
+            }
+        "#;
+        let types = infer_file(&flux_syntax::parse(source).unwrap());
+
+        assert!(types.errors.is_empty(), "{:?}", types.errors);
+        assert_eq!(
+            signature(&types, "norm"),
+            "({ x: float, y: float }) -> float"
+        );
+        assert_eq!(signature(&types, "origin"), "() -> { x: float, y: float }");
+    }
+
+    #[test]
+    fn test_structs_are_nominal() {
+        let source = r#"
+            struct User { name: string, age: int }
+            struct Admin { name: string, age: int }
+            fn age(user: User) -> int { user.age }
+            fn main() -> int {
+                age(User { name: "a", age: 1 }) + age(Admin { name: "b", age: 2 })
+                    + age({ name: "c", age: 3 })
+            }
+        "#;
+        let errors = check_types(&flux_syntax::parse(source).unwrap());
+
+        let messages: Vec<_> = errors.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            messages,
+            [
+                "Type error: Argument 1 of `age` expects User, found Admin",
+                "Type error: Argument 1 of `age` expects User, found { name: string, age: int }",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_field_access_infers_from_later_annotation() {
+        let source = r#"
+            struct User { name: string, age: int }
+            fn next_age(user, years: int) -> int {
+                let age = user.age
+                let unused = take_user(user)
+                age + years
+            }
+            fn take_user(user: User) -> int { 0 }
+        "#;
+        let types = infer_file(&flux_syntax::parse(source).unwrap());
+
+        assert!(types.errors.is_empty(), "{:?}", types.errors);
+        assert_eq!(signature(&types, "next_age"), "(User, int) -> int");
+    }
+
+    #[test]
+    fn test_record_errors() {
+        let source = r#"
+            struct User { name: string, age: int }
+            type Wrapper = { inner: Wrapper }
+            struct User { id: int }
+            fn missing() -> User { User { name: "a" } }
+            fn unknown_field() -> User { User { name: "a", age: 1, admin: true } }
+            fn wrong_value() -> User { User { name: "a", age: "old" } }
+            fn no_field(user: User) -> int { user.id }
+            fn not_record(x: int) -> int { x.id }
+            fn unknown_type() -> int { Nope { a: 1 }; 0 }
+            fn ambiguous(r) -> int { r.id }
+        "#;
+        let errors = check_types(&flux_syntax::parse(source).unwrap());
+
+        let messages: Vec<_> = errors.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            messages,
+            [
+                "Type error: Type `User` is declared more than once",
+                "Type error: Type `Wrapper` contains itself, so it would be infinite",
+                "Type error: `User` literal is missing field `age`",
+                "Type error: `User` has no field `admin`",
+                "Type error: Field `age` of `User` expects int, found string",
+                "Type error: `User` has no field `id`",
+                "Type error: Cannot access field `id` on int",
+                "Type error: Unknown type `Nope`",
+                "Type error: Cannot infer the record type of `.id`; add a type annotation",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_enum_constructors_and_match() {
+        let source = r#"
+            enum Shape { Circle(float), Rect(float, float), Empty }
+            fn area(shape) {
+                match shape {
+                    Circle(r) => 3.0 * r * r,
+                    Rect(w, h) => w * h,
+                    Empty => 0.0,
+                }
+            }
+            fn main() -> float { area(Rect(2.0, 3.0)) + area(Empty) }
+        "#;
+        let types = infer_file(&flux_syntax::parse(source).unwrap());
+
+        assert!(types.errors.is_empty(), "{:?}", types.errors);
+        assert_eq!(signature(&types, "area"), "(Shape) -> float");
+    }
+
+    #[test]
+    fn test_non_exhaustive_match_names_a_missing_value() {
+        let source = r#"
+            enum Light { Red, Amber, Green }
+            enum Slot { Taken(Light, bool), Free }
+            fn wait(slot: Slot) -> int {
+                match slot {
+                    Free => 0,
+                    Taken(Red, _) => 2,
+                    Taken(Green, true) => 1,
+                    Taken(Green, false) => 1,
+                }
+            }
+            fn flag(b: bool) -> int { match b { true => 1 } }
+            fn count(n: int) -> int { match n { 0 => 0, 1 => 1 } }
+        "#;
+        let errors = check_types(&flux_syntax::parse(source).unwrap());
+
+        let messages: Vec<_> = errors.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            messages,
+            [
+                "Type error: Non-exhaustive match: `Taken(Amber, _)` is not covered",
+                "Type error: Non-exhaustive match: `false` is not covered",
+                "Type error: Non-exhaustive match: `_` is not covered",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unreachable_match_arm_is_a_warning() {
+        let source = r#"
+            enum Answer { Yes, No(string) }
+            fn score(a: Answer) -> int {
+                match a {
+                    No(_) => 0,
+                    other => 1,
+                    Yes => 2,
+                }
+            }
+        "#;
+        let errors = check_types(&flux_syntax::parse(source).unwrap());
+
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert!(errors[0].is_warning());
+        assert_eq!(
+            errors[0].to_string(),
+            "Warning: Unreachable match arm: the arms above cover everything it matches"
+        );
+    }
+
+    #[test]
+    fn test_enum_and_pattern_errors() {
+        let source = r#"
+            enum Shape { Circle(float), Square(float), Circle }
+            enum Other { Square }
+            fn arity(s: Shape) -> float { match s { Circle(r, extra) => r, _ => 0.0 } }
+            fn bare(s: Shape) -> float { match s { Square => 1.0, _ => 0.0 } }
+            fn unknown(s: Shape) -> float { match s { Triangle(a) => a, _ => 0.0 } }
+            fn mismatch(s: Shape) -> float { match s { 1 => 1.0, _ => 0.0 } }
+            fn twice(s: Shape) -> float { match s { Circle(r) => 1.0, Square(w) => true } }
+            fn wrong_field() -> Shape { Circle(1) }
+        "#;
+        let errors = check_types(&flux_syntax::parse(source).unwrap());
+
+        let messages: Vec<_> = errors.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            messages,
+            [
+                "Type error: Variant `Circle` is declared more than once",
+                "Type error: Variant `Square` is declared more than once",
+                "Type error: Variant `Circle` has 1 field, the pattern gives 2",
+                "Type error: Variant `Square` has 1 field; match it with `Square(..)`",
+                "Type error: Unknown variant `Triangle`",
+                "Type error: Pattern of type int cannot match a value of type Shape",
+                "Type error: Match arms have incompatible types: earlier arms are float, \
+                 this arm is bool",
+                "Type error: Argument 1 of `Circle` expects float, found int",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lambdas_capture_and_generalize() {
+        let source = r#"
+            fn apply(f, x) { f(x) }
+            fn scale(factor) {
+                let times = fn(x) { x * factor }
+                apply(times, 1.5)
+            }
+            fn main() -> int {
+                let id = fn(x) { x }
+                let positive = fn(x: int) -> bool { return x > 0 }
+                if id(positive(2)) { id(1) } else { apply(fn(y) { y + 1 }, 0) }
+            }
+        "#;
+        let types = infer_file(&flux_syntax::parse(source).unwrap());
+
+        assert!(types.errors.is_empty(), "{:?}", types.errors);
+        assert_eq!(signature(&types, "apply"), "(('a) -> 'b, 'a) -> 'b");
+        assert_eq!(signature(&types, "scale"), "(float) -> float");
+    }
+
+    #[test]
+    fn test_lambda_errors() {
+        let source = r#"
+            fn f() -> int {
+                let g = fn(x: int) -> int { x > 0 }
+                let h = fn(x) { x + 1 }
+                h(true)
+            }
+        "#;
+        let errors = check_types(&flux_syntax::parse(source).unwrap());
+
+        let messages: Vec<_> = errors.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            messages,
+            [
+                "Type error: `fn(..)` must return int, found bool",
+                "Type error: Argument 1 of `h` expects int, found bool",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_function_type_annotations() {
+        let source = r#"
+            fn apply(f: (int) -> int, x: int) -> int { f(x) }
+            fn adder(k: int) -> (int) -> int { fn(x: int) { x + k } }
+            fn main() -> int { apply(adder(1), 41) }
+            fn wrong() -> int { apply(fn(x: int) { x > 0 }, 1) }
+        "#;
+        let types = infer_file(&flux_syntax::parse(source).unwrap());
+
+        assert_eq!(signature(&types, "apply"), "((int) -> int, int) -> int");
+        assert_eq!(signature(&types, "adder"), "(int) -> (int) -> int");
+        let messages: Vec<_> = types.errors.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            messages,
+            ["Type error: Argument 1 of `apply` expects (int) -> int, found (int) -> bool"]
+        );
+    }
+
+    #[test]
+    fn test_native_functions_are_typed_by_their_signature() {
+        let source = r#"
+            native fn first<T>(values: Column<T>) -> T
+            native fn sum(values: Column<int>, scale)
+            fn main() -> int { first(#scores) + 1 }
+        "#;
+        let types = infer_file(&flux_syntax::parse(source).unwrap());
+
+        assert_eq!(signature(&types, "first"), "(Column<T>) -> T");
+        assert_eq!(signature(&types, "main"), "() -> int");
+        let messages: Vec<_> = types.errors.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            messages,
+            ["Type error: Native function `sum` must annotate its parameters and return type"]
+        );
+    }
+
+    #[test]
+    fn test_lists_tuples_and_indexing() {
+        let source = r#"
+            fn first(xs) { xs[0] }
+            fn swap(pair: (int, string)) -> (string, int) { (pair[1], pair[0]) }
+            fn main() -> float {
+                let grid = [[1.5, 2.0], [], [3.0]]
+                let empty = []
+                first(grid[2]) + first([first(empty), 0.5])
+            }
+        "#;
+        let types = infer_file(&flux_syntax::parse(source).unwrap());
+
+        assert!(types.errors.is_empty(), "{:?}", types.errors);
+        assert_eq!(signature(&types, "first"), "(List<'a>) -> 'a");
+        assert_eq!(
+            signature(&types, "swap"),
+            "((int, string)) -> (string, int)"
+        );
+    }
+
+    #[test]
+    fn test_list_and_index_errors() {
+        let source = r#"
+            type Pair = (int, bool)
+            fn f(pair: Pair, xs: List<int>, i: int) -> int {
+                let mixed = [1, true, 3]
+                let a = xs[true]
+                let b = pair[2]
+                let c = pair[i]
+                let d = 5[0]
+                xs[0]
+            }
+        "#;
+        let errors = check_types(&flux_syntax::parse(source).unwrap());
+
+        let messages: Vec<_> = errors.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            messages,
+            [
+                "Type error: List elements have incompatible types: earlier elements are int, this element is bool",
+                "Type error: Index must be int, found bool",
+                "Type error: Index 2 is out of bounds for a tuple of 2 elements",
+                "Type error: Tuple (int, bool) can only be indexed with an int literal, e.g. `pair[0]`",
+                "Type error: Cannot index into int",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_generic_functions_and_types() {
+        let source = r#"
+            struct Pair<A, B> { first: A, second: B }
+            enum Maybe<T> { Nothing, Just(T) }
+            type Grid<T> = List<List<T>>
+            fn identity<T>(x: T) -> T { x }
+            fn swap<A, B>(pair: Pair<A, B>) -> Pair<B, A> {
+                Pair { first: pair.second, second: pair.first }
+            }
+            fn or_else<T>(value: Maybe<T>, fallback: T) -> T {
+                match value { Just(x) => x, Nothing => fallback }
+            }
+            fn corner<T>(grid: Grid<T>) -> T { grid[0][0] }
+            fn main() -> int {
+                let flipped = swap(Pair { first: true, second: identity(1) })
+                if identity(flipped.second) { or_else(Just(flipped.first), 0) } else { corner([[2]]) }
+            }
+        "#;
+        let types = infer_file(&flux_syntax::parse(source).unwrap());
+
+        assert!(types.errors.is_empty(), "{:?}", types.errors);
+        assert_eq!(signature(&types, "identity"), "(T) -> T");
+        assert_eq!(signature(&types, "swap"), "(Pair<A, B>) -> Pair<B, A>");
+        assert_eq!(signature(&types, "or_else"), "(Maybe<T>, T) -> T");
+        assert_eq!(signature(&types, "corner"), "(List<List<T>>) -> T");
+
+        // Every use of a generic function records what it is instantiated with
+        let mut uses: Vec<String> = types
+            .instantiations
+            .iter()
+            .filter(|((function, _), _)| function == "main")
+            .map(|(_, args)| {
+                let args: Vec<_> = args.iter().map(ToString::to_string).collect();
+                args.join(", ")
+            })
+            .collect();
+        uses.sort();
+        assert_eq!(uses, ["bool", "bool, int", "int", "int", "int"]);
+    }
+
+    #[test]
+    fn test_expression_and_pattern_types_are_recorded() {
+        let source = r#"
+            fn main() {
+                let scale = fn(x) { x * 2.5 }
+                match some(scale(2.0)) { some(y) => y, none => 0.0 }
+            }
+        "#;
+        let types = infer_file(&flux_syntax::parse(source).unwrap());
+        assert!(types.errors.is_empty(), "{:?}", types.errors);
+
+        // Types are resolved once the function is inferred, so `x` is a
+        // float although its first use doesn't say so
+        let type_of = |text: &str| {
+            let start = source.find(text).unwrap();
+            let span = flux_errors::Span::new(start, start + text.len());
+            types.expr_types[&("main".to_string(), span)].to_string()
+        };
+        assert_eq!(type_of("x * 2.5"), "float");
+        assert_eq!(type_of("fn(x) { x * 2.5 }"), "(float) -> float");
+        assert_eq!(type_of("some(scale(2.0))"), "Option<float>");
+        assert_eq!(type_of("some(y)"), "Option<float>");
+        assert_eq!(type_of("y"), "float");
+    }
+
+    #[test]
+    fn test_type_parameters_are_rigid() {
+        let source = r#"
+            enum Maybe<T> { Nothing, Just(T) }
+            fn broken<T>(x: T) -> int { x }
+            fn unequal<A, B>(a: A, b: B) -> A { b }
+            fn misuse(m: Maybe<int>) -> int { match m { Just(x) => x, Nothing => false } }
+        "#;
+        let errors = check_types(&flux_syntax::parse(source).unwrap());
+
+        let messages: Vec<_> = errors.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            messages,
+            [
+                "Type error: `broken` must return int, found T",
+                "Type error: `unequal` must return A, found B",
+                "Type error: Match arms have incompatible types: earlier arms are int, this arm is bool",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_generic_declaration_errors() {
+        let source = r#"
+            struct Box<T> { value: T }
+            enum Twice<T, T> { One(T) }
+            fn unwrap(b: Box) -> int { 0 }
+            fn nested(b: Box<int, bool>) -> int { 0 }
+            fn plain(n: Date<int>) -> int { 0 }
+            fn dup<K, K>(k: K) -> K { k }
+            export fn exported<T>(x: T) -> T { x }
+        "#;
+        let errors = check_types(&flux_syntax::parse(source).unwrap());
+
+        let messages: Vec<_> = errors.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            messages,
+            [
+                "Type error: Type parameter `T` is declared more than once",
+                "Type error: `Box` expects 1 type argument, found 0",
+                "Type error: `Box` expects 1 type argument, found 2",
+                "Type error: Type `Date` takes no type arguments",
+                "Type error: Type parameter `K` is declared more than once",
+                "Type error: Exported function `exported` cannot be generic; give its parameters concrete types",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_option_result_and_try() {
+        let source = r#"
+            fn parse(x: int) -> Result<int, string> { if x > 0 { ok(x) } else { err("negative") } }
+            fn double(x: int) -> Result<int, string> { ok(parse(x)? * 2) }
+            fn first(xs: List<int>) -> Option<int> { if xs[0] > 0 { some(xs[0]) } else { none } }
+            fn halve(xs) { some(first(xs)? / 2) }
+            fn or_zero(o: Option<int>) -> int { match o { some(x) => x, none => 0 } }
+            fn main() -> int {
+                let inner = fn(x: int) -> Option<bool> { some(first([x])? > 0) }
+                or_zero(halve([4]))
+            }
+        "#;
+        let types = infer_file(&flux_syntax::parse(source).unwrap());
+
+        assert!(types.errors.is_empty(), "{:?}", types.errors);
+        assert_eq!(signature(&types, "double"), "(int) -> Result<int, string>");
+        assert_eq!(signature(&types, "halve"), "(List<int>) -> Option<int>");
+    }
+
+    #[test]
+    fn test_try_errors() {
+        let source = r#"
+            enum Option { Present }
+            fn plain(o: Option<int>) -> int { o? }
+            fn mixed(o: Option<int>) -> Result<int, string> { ok(o?) }
+            fn errors(r: Result<int, bool>) -> Result<int, string> { ok(r?) }
+            fn not_wrapped(x: int) -> Option<int> { some(x?) }
+            fn partial(o: Option<int>) -> int { match o { some(x) => x } }
+        "#;
+        let errors = check_types(&flux_syntax::parse(source).unwrap());
+
+        let messages: Vec<_> = errors.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            messages,
+            [
+                "Type error: Cannot redeclare the built-in type `Option`",
+                "Type error: `?` on an Option can only be used in a function returning an Option, \
+                 but `plain` returns int",
+                "Type error: `?` on an Option can only be used in a function returning an Option, \
+                 but `mixed` returns Result<int, string>",
+                "Type error: `?` returns the error bool from `errors`, which returns Result<int, string>",
+                "Type error: `?` needs an Option or a Result, found int",
+                "Type error: Non-exhaustive match: `none` is not covered",
+            ]
+        );
     }
 }
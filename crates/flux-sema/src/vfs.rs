@@ -3,6 +3,17 @@ use parking_lot::RwLock;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+/// URI scheme of the std library's virtual files
+pub const STD_SCHEME: &str = "flux-std";
+
+/// Std library modules embedded in the binary, by module path
+const STD_MODULES: &[(&str, &str)] = &[
+    ("std.column", include_str!("../std/column.flux")),
+    ("std.math", include_str!("../std/math.flux")),
+    ("std.string", include_str!("../std/string.flux")),
+    ("std.time", include_str!("../std/time.flux")),
+];
+
 /// Unique identifier for a file in the VFS
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct FileId(pub u32);
@@ -32,14 +43,54 @@ pub struct FileData {
 
 impl Vfs {
     pub fn new() -> Self {
-        Self {
+        let vfs = Self {
             files: DashMap::new(),
             path_to_id: DashMap::new(),
             next_id: RwLock::new(1),
             std_lib: DashMap::new(),
+        };
+        vfs.register_std_lib();
+        vfs
+    }
+
+    /// Mount the embedded std library at `flux-std:///std/<module>.flux`
+    fn register_std_lib(&self) {
+        for (module, source) in STD_MODULES {
+            let file_id = self.next_id();
+            let path = PathBuf::from(format!(
+                "{}:///{}.flux",
+                STD_SCHEME,
+                module.replace('.', "/")
+            ));
+            let file_data = Arc::new(FileData {
+                id: file_id,
+                path: path.clone(),
+                content: source.to_string(),
+                version: 1,
+            });
+
+            self.files.insert(file_id, file_data.clone());
+            self.path_to_id.insert(path, file_id);
+            self.std_lib.insert(module.to_string(), file_data);
         }
     }
 
+    /// The std library modules, by module path
+    pub fn std_modules(&self) -> Vec<(String, FileId)> {
+        let mut modules: Vec<_> = self
+            .std_lib
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.id))
+            .collect();
+        modules.sort_by(|a, b| a.0.cmp(&b.0));
+        modules
+    }
+
+    /// Whether `file_id` is a std library module, which is read-only
+    pub fn is_std_file(&self, file_id: FileId) -> bool {
+        self.std_lib.iter().any(|entry| entry.id == file_id)
+    }
+
     fn next_id(&self) -> FileId {
         let mut next = self.next_id.write();
         let id = *next;
@@ -116,6 +167,20 @@ impl Vfs {
         None
     }
 
+    /// Resolve a module imported by `from`: a std library module, or else
+    /// `a/b.flux` next to `from` for `a.b`, loaded from disk if needed
+    pub fn resolve_import(&self, from: FileId, module: &str) -> Option<FileId> {
+        if let Some(file_id) = self.resolve_module(module) {
+            return Some(file_id);
+        }
+
+        let mut path = self.get_file(from)?.path.parent()?.to_path_buf();
+        path.extend(module.split('.'));
+        path.set_extension("flux");
+        self.get_file_by_path(&path)
+            .or_else(|| self.load_file(&path).ok())
+    }
+
     /// Get file by path
     pub fn get_file_by_path(&self, path: &Path) -> Option<FileId> {
         self.path_to_id.get(path).map(|id| *id)
@@ -159,4 +224,30 @@ mod tests {
         assert_eq!(file_data.content, "version 2");
         assert_eq!(file_data.version, 2);
     }
+
+    #[test]
+    fn test_vfs_mounts_std_lib() {
+        let vfs = Vfs::new();
+        let math = vfs.resolve_module("std.math").unwrap();
+
+        assert!(vfs.is_std_file(math));
+        assert_eq!(
+            vfs.get_file(math).unwrap().path,
+            PathBuf::from("flux-std:///std/math.flux")
+        );
+        assert!(vfs.get_file(math).unwrap().content.contains("fn abs"));
+        assert_eq!(vfs.std_modules().len(), STD_MODULES.len());
+    }
+
+    #[test]
+    fn test_vfs_resolve_import_next_to_importer() {
+        let vfs = Vfs::new();
+        let main = vfs.set_file_content(Path::new("app/main.flux"), String::new());
+        let util = vfs.set_file_content(Path::new("app/util.flux"), String::new());
+        let nested = vfs.set_file_content(Path::new("app/lib/text.flux"), String::new());
+
+        assert_eq!(vfs.resolve_import(main, "util"), Some(util));
+        assert_eq!(vfs.resolve_import(main, "lib.text"), Some(nested));
+        assert_eq!(vfs.resolve_import(main, "missing"), None);
+    }
 }
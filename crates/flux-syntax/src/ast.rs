@@ -1,3 +1,4 @@
+use crate::temporal::Temporal;
 use flux_errors::Span;
 
 /// Root AST node for a Flux source file
@@ -7,31 +8,212 @@ pub struct SourceFile {
     pub span: Span,
 }
 
+impl SourceFile {
+    pub fn imports(&self) -> impl Iterator<Item = &Import> {
+        self.items.iter().filter_map(|item| match item {
+            Item::Import(import) => Some(import),
+            _ => None,
+        })
+    }
+
+    pub fn functions(&self) -> impl Iterator<Item = &Function> {
+        self.items.iter().filter_map(|item| match item {
+            Item::Function(func) => Some(func),
+            _ => None,
+        })
+    }
+
+    pub fn type_aliases(&self) -> impl Iterator<Item = &TypeAlias> {
+        self.items.iter().filter_map(|item| match item {
+            Item::TypeAlias(alias) => Some(alias),
+            _ => None,
+        })
+    }
+
+    pub fn structs(&self) -> impl Iterator<Item = &Struct> {
+        self.items.iter().filter_map(|item| match item {
+            Item::Struct(def) => Some(def),
+            _ => None,
+        })
+    }
+
+    pub fn enums(&self) -> impl Iterator<Item = &Enum> {
+        self.items.iter().filter_map(|item| match item {
+            Item::Enum(def) => Some(def),
+            _ => None,
+        })
+    }
+}
+
 /// Top-level item in a source file
 #[derive(Debug, Clone, PartialEq)]
 pub enum Item {
+    Import(Import),
     Function(Function),
+    TypeAlias(TypeAlias),
+    Struct(Struct),
+    Enum(Enum),
 }
 
 impl Item {
     pub fn span(&self) -> Span {
         match self {
+            Item::Import(import) => import.span,
             Item::Function(func) => func.span,
+            Item::TypeAlias(alias) => alias.span,
+            Item::Struct(def) => def.span,
+            Item::Enum(def) => def.span,
         }
     }
+
+    /// Move every span by `delta` bytes, for an item after an edit
+    pub fn shift(&mut self, delta: isize) {
+        match self {
+            Item::Import(import) => {
+                import.span = import.span.shifted(delta);
+                import.path_span = import.path_span.shifted(delta);
+                for name in import.names.iter_mut().flatten() {
+                    name.span = name.span.shifted(delta);
+                }
+            }
+            Item::Function(func) => func.shift(delta),
+            Item::TypeAlias(alias) => {
+                alias.span = alias.span.shifted(delta);
+                alias.name_span = alias.name_span.shifted(delta);
+                shift_type_params(&mut alias.type_params, delta);
+                alias.ty.shift(delta);
+            }
+            Item::Struct(def) => {
+                def.span = def.span.shifted(delta);
+                def.name_span = def.name_span.shifted(delta);
+                shift_type_params(&mut def.type_params, delta);
+                for field in &mut def.fields {
+                    field.span = field.span.shifted(delta);
+                    field.ty.shift(delta);
+                }
+            }
+            Item::Enum(def) => {
+                def.span = def.span.shifted(delta);
+                def.name_span = def.name_span.shifted(delta);
+                shift_type_params(&mut def.type_params, delta);
+                for variant in &mut def.variants {
+                    variant.span = variant.span.shifted(delta);
+                    variant.fields.iter_mut().for_each(|ty| ty.shift(delta));
+                }
+            }
+        }
+    }
+}
+
+fn shift_type_params(params: &mut [TypeParam], delta: isize) {
+    for param in params {
+        param.span = param.span.shifted(delta);
+    }
+}
+
+/// `import a.b` brings every function `a/b.flux` exports into scope;
+/// `import a.b.{f, g}` only the ones listed
+#[derive(Debug, Clone, PartialEq)]
+pub struct Import {
+    pub path: Vec<String>,
+    pub path_span: Span,
+    /// `None` when every export is imported
+    pub names: Option<Vec<ImportName>>,
+    pub span: Span,
+}
+
+impl Import {
+    /// The module path as written, `a.b`
+    pub fn module(&self) -> String {
+        self.path.join(".")
+    }
+}
+
+/// A name in an import list
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportName {
+    pub name: String,
+    pub span: Span,
+}
+
+/// `type Point = { x: float, y: float }` names a type. Aliases are
+/// structural: any record of the right shape is a `Point`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeAlias {
+    pub name: String,
+    pub name_span: Span,
+    pub type_params: Vec<TypeParam>,
+    pub ty: Type,
+    pub span: Span,
+}
+
+/// `struct User { name: string, age: int }` declares a nominal record type,
+/// compatible only with itself
+#[derive(Debug, Clone, PartialEq)]
+pub struct Struct {
+    pub name: String,
+    pub name_span: Span,
+    pub type_params: Vec<TypeParam>,
+    pub fields: Vec<FieldType>,
+    pub span: Span,
+}
+
+/// `enum Outcome { Done, Failed(string) }` declares a sum type. Each
+/// variant is a constructor in scope in the whole file: `Done` is a value,
+/// `Failed` a function of its fields.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Enum {
+    pub name: String,
+    pub name_span: Span,
+    pub type_params: Vec<TypeParam>,
+    pub variants: Vec<Variant>,
+    pub span: Span,
+}
+
+/// A variant of an enum with the types of its unnamed fields
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variant {
+    pub name: String,
+    pub fields: Vec<Type>,
+    pub span: Span,
+}
+
+/// A type parameter of a generic function or type, `T` in
+/// `fn identity<T>(x: T) -> T`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeParam {
+    pub name: String,
+    pub span: Span,
 }
 
 /// Function definition
 #[derive(Debug, Clone, PartialEq)]
 pub struct Function {
     pub is_export: bool,
+    /// Provided by the compiler rather than written in Flux: its signature
+    /// is all there is, and its body is an empty block
+    pub is_native: bool,
     pub name: String,
+    pub type_params: Vec<TypeParam>,
     pub params: Vec<Param>,
     pub return_type: Option<Type>,
     pub body: Expr,
     pub span: Span,
 }
 
+impl Function {
+    /// Move every span by `delta` bytes
+    pub fn shift(&mut self, delta: isize) {
+        self.span = self.span.shifted(delta);
+        shift_type_params(&mut self.type_params, delta);
+        self.params.iter_mut().for_each(|param| param.shift(delta));
+        if let Some(ty) = &mut self.return_type {
+            ty.shift(delta);
+        }
+        self.body.shift(delta);
+    }
+}
+
 /// Function parameter
 #[derive(Debug, Clone, PartialEq)]
 pub struct Param {
@@ -40,6 +222,15 @@ pub struct Param {
     pub span: Span,
 }
 
+impl Param {
+    pub fn shift(&mut self, delta: isize) {
+        self.span = self.span.shifted(delta);
+        if let Some(ty) = &mut self.ty {
+            ty.shift(delta);
+        }
+    }
+}
+
 /// Type annotation
 #[derive(Debug, Clone, PartialEq)]
 pub enum Type {
@@ -47,18 +238,97 @@ pub enum Type {
     String(Span),
     Bool(Span),
     Float(Span),
-    Named { name: String, span: Span },
+    /// A built-in, declared or parameter type; `Column<int>` and
+    /// `Pair<int, string>` give type arguments
+    Named {
+        name: String,
+        args: Vec<Type>,
+        span: Span,
+    },
+    /// `{ x: float, y: float }`
+    Record {
+        fields: Vec<FieldType>,
+        span: Span,
+    },
+    /// `List<int>`
+    List {
+        elem: Box<Type>,
+        span: Span,
+    },
+    /// `(int, string)`, at least two elements
+    Tuple {
+        elems: Vec<Type>,
+        span: Span,
+    },
+    /// `(int, int) -> bool`
+    Function {
+        params: Vec<Type>,
+        ret: Box<Type>,
+        span: Span,
+    },
 }
 
 impl Type {
     pub fn span(&self) -> Span {
         match self {
             Type::Int(s) | Type::String(s) | Type::Bool(s) | Type::Float(s) => *s,
-            Type::Named { span, .. } => *span,
+            Type::Named { span, .. }
+            | Type::Record { span, .. }
+            | Type::List { span, .. }
+            | Type::Tuple { span, .. }
+            | Type::Function { span, .. } => *span,
+        }
+    }
+
+    pub fn shift(&mut self, delta: isize) {
+        match self {
+            Type::Int(s) | Type::String(s) | Type::Bool(s) | Type::Float(s) => {
+                *s = s.shifted(delta)
+            }
+            Type::Named { args, span, .. } => {
+                args.iter_mut().for_each(|arg| arg.shift(delta));
+                *span = span.shifted(delta);
+            }
+            Type::Record { fields, span } => {
+                for field in fields {
+                    field.span = field.span.shifted(delta);
+                    field.ty.shift(delta);
+                }
+                *span = span.shifted(delta);
+            }
+            Type::List { elem, span } => {
+                elem.shift(delta);
+                *span = span.shifted(delta);
+            }
+            Type::Tuple { elems, span } => {
+                elems.iter_mut().for_each(|elem| elem.shift(delta));
+                *span = span.shifted(delta);
+            }
+            Type::Function { params, ret, span } => {
+                params.iter_mut().for_each(|param| param.shift(delta));
+                ret.shift(delta);
+                *span = span.shifted(delta);
+            }
         }
     }
 }
 
+/// `name: type` in a record type or struct
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldType {
+    pub name: String,
+    pub ty: Type,
+    pub span: Span,
+}
+
+/// `name: value` in a record literal
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldInit {
+    pub name: String,
+    pub value: Expr,
+    pub span: Span,
+}
+
 /// Expression
 #[derive(Debug, Clone, PartialEq)]
 pub enum Expr {
@@ -79,6 +349,12 @@ pub enum Expr {
         value: String,
         span: Span,
     },
+    // `@2026-10-16`, `@12:30:00`, `@2026-10-16T12:00:00[Europe/Paris]`,
+    // `@2026-10-16T12:00:00Z` or `5d 3h`
+    Temporal {
+        value: Temporal,
+        span: Span,
+    },
 
     // Variables and identifiers
     Var {
@@ -86,6 +362,61 @@ pub enum Expr {
         span: Span,
     },
 
+    // Column reference: `#name` or `#"quoted name"` (name stored without `#`/quotes)
+    Column {
+        name: String,
+        span: Span,
+    },
+
+    // Record literal: `{ x: 1.0, y: 2.0 }`, or `User { name: "a" }` for a
+    // named type
+    Record {
+        name: Option<String>,
+        fields: Vec<FieldInit>,
+        span: Span,
+    },
+
+    // Field access: `base.field`
+    Field {
+        base: Box<Expr>,
+        field: String,
+        field_span: Span,
+        span: Span,
+    },
+
+    // List literal: `[1, 2, 3]`
+    List {
+        elems: Vec<Expr>,
+        span: Span,
+    },
+
+    // Tuple literal: `(a, b)`, at least two elements
+    Tuple {
+        elems: Vec<Expr>,
+        span: Span,
+    },
+
+    // Indexing: `xs[i]`, or `pair[0]` with a literal index on a tuple
+    Index {
+        base: Box<Expr>,
+        index: Box<Expr>,
+        span: Span,
+    },
+
+    // `?` propagation: `value?` is the value inside an Option or Result,
+    // and returns its `none` or `err` from the enclosing function
+    Try {
+        operand: Box<Expr>,
+        span: Span,
+    },
+
+    // Unary operations
+    Unary {
+        op: UnaryOp,
+        operand: Box<Expr>,
+        span: Span,
+    },
+
     // Binary operations
     Binary {
         op: BinOp,
@@ -94,6 +425,14 @@ pub enum Expr {
         span: Span,
     },
 
+    // Pipeline: `left |> right`, equivalent to calling `right` with `left`
+    // prepended to its arguments (see `Expr::pipeline_to_call`)
+    Pipeline {
+        left: Box<Expr>,
+        right: Box<Expr>,
+        span: Span,
+    },
+
     // Function call
     Call {
         func: Box<Expr>,
@@ -109,6 +448,29 @@ pub enum Expr {
         span: Span,
     },
 
+    // Conditional expression: `if cond { .. } else { .. }`
+    If {
+        cond: Box<Expr>,
+        then_branch: Box<Expr>,
+        else_branch: Box<Expr>,
+        span: Span,
+    },
+
+    // `match scrutinee { pattern => body, .. }`
+    Match {
+        scrutinee: Box<Expr>,
+        arms: Vec<MatchArm>,
+        span: Span,
+    },
+
+    // Anonymous function: `fn(x: int) -> int { x * 2 }`
+    Lambda {
+        params: Vec<Param>,
+        return_type: Option<Type>,
+        body: Box<Expr>,
+        span: Span,
+    },
+
     // Block expression
     Block {
         stmts: Vec<Expr>,
@@ -120,6 +482,12 @@ pub enum Expr {
         value: Box<Expr>,
         span: Span,
     },
+
+    // Placeholder for source that failed to parse; the syntax error is
+    // reported separately
+    Error {
+        span: Span,
+    },
 }
 
 impl Expr {
@@ -129,16 +497,404 @@ impl Expr {
             | Expr::Float { span, .. }
             | Expr::Bool { span, .. }
             | Expr::String { span, .. }
+            | Expr::Temporal { span, .. }
             | Expr::Var { span, .. }
+            | Expr::Column { span, .. }
+            | Expr::Record { span, .. }
+            | Expr::Field { span, .. }
+            | Expr::List { span, .. }
+            | Expr::Tuple { span, .. }
+            | Expr::Index { span, .. }
+            | Expr::Try { span, .. }
+            | Expr::Unary { span, .. }
             | Expr::Binary { span, .. }
+            | Expr::Pipeline { span, .. }
             | Expr::Call { span, .. }
             | Expr::Let { span, .. }
+            | Expr::If { span, .. }
+            | Expr::Match { span, .. }
+            | Expr::Lambda { span, .. }
             | Expr::Block { span, .. }
-            | Expr::Return { span, .. } => *span,
+            | Expr::Return { span, .. }
+            | Expr::Error { span } => *span,
+        }
+    }
+
+    /// Move this expression and everything in it by `delta` bytes
+    pub fn shift(&mut self, delta: isize) {
+        match self {
+            Expr::Int { span, .. }
+            | Expr::Float { span, .. }
+            | Expr::Bool { span, .. }
+            | Expr::String { span, .. }
+            | Expr::Temporal { span, .. }
+            | Expr::Var { span, .. }
+            | Expr::Column { span, .. }
+            | Expr::Error { span } => *span = span.shifted(delta),
+            Expr::Unary { operand, span, .. }
+            | Expr::Try { operand, span }
+            | Expr::Return {
+                value: operand,
+                span,
+            } => {
+                operand.shift(delta);
+                *span = span.shifted(delta);
+            }
+            Expr::Binary {
+                left, right, span, ..
+            }
+            | Expr::Pipeline { left, right, span }
+            | Expr::Index {
+                base: left,
+                index: right,
+                span,
+            }
+            | Expr::Let {
+                value: left,
+                body: right,
+                span,
+                ..
+            } => {
+                left.shift(delta);
+                right.shift(delta);
+                *span = span.shifted(delta);
+            }
+            Expr::Call { func, args, span } => {
+                func.shift(delta);
+                args.iter_mut().for_each(|arg| arg.shift(delta));
+                *span = span.shifted(delta);
+            }
+            Expr::List { elems, span } | Expr::Tuple { elems, span } => {
+                elems.iter_mut().for_each(|elem| elem.shift(delta));
+                *span = span.shifted(delta);
+            }
+            Expr::Record { fields, span, .. } => {
+                for field in fields {
+                    field.value.shift(delta);
+                    field.span = field.span.shifted(delta);
+                }
+                *span = span.shifted(delta);
+            }
+            Expr::Field {
+                base,
+                field_span,
+                span,
+                ..
+            } => {
+                base.shift(delta);
+                *field_span = field_span.shifted(delta);
+                *span = span.shifted(delta);
+            }
+            Expr::If {
+                cond,
+                then_branch,
+                else_branch,
+                span,
+            } => {
+                cond.shift(delta);
+                then_branch.shift(delta);
+                else_branch.shift(delta);
+                *span = span.shifted(delta);
+            }
+            Expr::Match {
+                scrutinee,
+                arms,
+                span,
+            } => {
+                scrutinee.shift(delta);
+                for arm in arms {
+                    arm.pattern.shift(delta);
+                    arm.body.shift(delta);
+                    arm.span = arm.span.shifted(delta);
+                }
+                *span = span.shifted(delta);
+            }
+            Expr::Lambda {
+                params,
+                return_type,
+                body,
+                span,
+            } => {
+                params.iter_mut().for_each(|param| param.shift(delta));
+                if let Some(ty) = return_type {
+                    ty.shift(delta);
+                }
+                body.shift(delta);
+                *span = span.shifted(delta);
+            }
+            Expr::Block { stmts, span } => {
+                stmts.iter_mut().for_each(|stmt| stmt.shift(delta));
+                *span = span.shifted(delta);
+            }
+        }
+    }
+
+    /// The expressions directly inside this one, in source order
+    pub fn children_mut(&mut self) -> Vec<&mut Expr> {
+        match self {
+            Expr::Unary { operand, .. }
+            | Expr::Try { operand, .. }
+            | Expr::Field { base: operand, .. }
+            | Expr::Return { value: operand, .. }
+            | Expr::Lambda { body: operand, .. } => vec![operand],
+            Expr::Binary { left, right, .. }
+            | Expr::Pipeline { left, right, .. }
+            | Expr::Index {
+                base: left,
+                index: right,
+                ..
+            }
+            | Expr::Let {
+                value: left,
+                body: right,
+                ..
+            } => vec![left, right],
+            Expr::Call { func, args, .. } => {
+                let mut children = vec![&mut **func];
+                children.extend(args.iter_mut());
+                children
+            }
+            Expr::List { elems, .. }
+            | Expr::Tuple { elems, .. }
+            | Expr::Block { stmts: elems, .. } => elems.iter_mut().collect(),
+            Expr::Record { fields, .. } => {
+                fields.iter_mut().map(|field| &mut field.value).collect()
+            }
+            Expr::If {
+                cond,
+                then_branch,
+                else_branch,
+                ..
+            } => vec![cond, then_branch, else_branch],
+            Expr::Match {
+                scrutinee, arms, ..
+            } => {
+                let mut children = vec![&mut **scrutinee];
+                children.extend(arms.iter_mut().map(|arm| &mut arm.body));
+                children
+            }
+            Expr::Var { .. }
+            | Expr::Int { .. }
+            | Expr::Float { .. }
+            | Expr::Bool { .. }
+            | Expr::String { .. }
+            | Expr::Temporal { .. }
+            | Expr::Column { .. }
+            | Expr::Error { .. } => Vec::new(),
+        }
+    }
+
+    /// Names the expression uses without binding them, in order of first
+    /// use. For a lambda these are the variables it captures, plus the
+    /// functions and enum variants it names.
+    pub fn free_variables(&self) -> Vec<String> {
+        let mut free = Vec::new();
+        self.collect_free_variables(&mut Vec::new(), &mut free);
+        free
+    }
+
+    /// `bound` holds the names in scope, innermost last
+    fn collect_free_variables(&self, bound: &mut Vec<String>, free: &mut Vec<String>) {
+        let scope = bound.len();
+        match self {
+            Expr::Var { name, .. } => {
+                if !bound.contains(name) && !free.contains(name) {
+                    free.push(name.clone());
+                }
+            }
+            Expr::Unary { operand, .. }
+            | Expr::Try { operand, .. }
+            | Expr::Field { base: operand, .. }
+            | Expr::Return { value: operand, .. } => {
+                operand.collect_free_variables(bound, free);
+            }
+            Expr::Binary { left, right, .. }
+            | Expr::Pipeline { left, right, .. }
+            | Expr::Index {
+                base: left,
+                index: right,
+                ..
+            } => {
+                left.collect_free_variables(bound, free);
+                right.collect_free_variables(bound, free);
+            }
+            Expr::List { elems, .. } | Expr::Tuple { elems, .. } => {
+                for elem in elems {
+                    elem.collect_free_variables(bound, free);
+                }
+            }
+            Expr::Call { func, args, .. } => {
+                func.collect_free_variables(bound, free);
+                for arg in args {
+                    arg.collect_free_variables(bound, free);
+                }
+            }
+            Expr::Record { fields, .. } => {
+                for field in fields {
+                    field.value.collect_free_variables(bound, free);
+                }
+            }
+            Expr::Let {
+                name, value, body, ..
+            } => {
+                value.collect_free_variables(bound, free);
+                bound.push(name.clone());
+                body.collect_free_variables(bound, free);
+            }
+            Expr::If {
+                cond,
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                cond.collect_free_variables(bound, free);
+                then_branch.collect_free_variables(bound, free);
+                else_branch.collect_free_variables(bound, free);
+            }
+            Expr::Match {
+                scrutinee, arms, ..
+            } => {
+                scrutinee.collect_free_variables(bound, free);
+                for arm in arms {
+                    arm.pattern.collect_names(bound);
+                    arm.body.collect_free_variables(bound, free);
+                    bound.truncate(scope);
+                }
+            }
+            Expr::Lambda { params, body, .. } => {
+                bound.extend(params.iter().map(|param| param.name.clone()));
+                body.collect_free_variables(bound, free);
+            }
+            Expr::Block { stmts, .. } => {
+                for stmt in stmts {
+                    stmt.collect_free_variables(bound, free);
+                }
+            }
+            Expr::Int { .. }
+            | Expr::Float { .. }
+            | Expr::Bool { .. }
+            | Expr::String { .. }
+            | Expr::Temporal { .. }
+            | Expr::Column { .. }
+            | Expr::Error { .. } => {}
+        }
+        bound.truncate(scope);
+    }
+
+    /// Desugar a pipeline stage into the call it stands for: `a |> f(b)` is
+    /// `f(a, b)` and `a |> f` is `f(a)`. The call keeps the pipeline's span.
+    pub fn pipeline_to_call(left: &Expr, right: &Expr, span: Span) -> Expr {
+        let (func, args) = match right {
+            Expr::Call { func, args, .. } => {
+                let mut piped = Vec::with_capacity(args.len() + 1);
+                piped.push(left.clone());
+                piped.extend(args.iter().cloned());
+                (func.clone(), piped)
+            }
+            _ => (Box::new(right.clone()), vec![left.clone()]),
+        };
+
+        Expr::Call { func, args, span }
+    }
+}
+
+/// `pattern => body` in a `match`
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub body: Expr,
+    pub span: Span,
+}
+
+/// Pattern of a match arm
+#[derive(Debug, Clone, PartialEq)]
+pub enum Pattern {
+    /// `_`
+    Wildcard {
+        span: Span,
+    },
+    /// A unit variant when `name` is one, otherwise a binding that matches
+    /// anything
+    Name {
+        name: String,
+        span: Span,
+    },
+    Int {
+        value: i64,
+        span: Span,
+    },
+    Bool {
+        value: bool,
+        span: Span,
+    },
+    String {
+        value: String,
+        span: Span,
+    },
+    /// `Failed(message)`
+    Variant {
+        name: String,
+        args: Vec<Pattern>,
+        span: Span,
+    },
+}
+
+impl Pattern {
+    /// Names the pattern binds, or that name unit variants: which one a
+    /// name is depends on the enums in scope
+    pub fn names(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        self.collect_names(&mut names);
+        names
+    }
+
+    fn collect_names(&self, out: &mut Vec<String>) {
+        match self {
+            Pattern::Name { name, .. } => out.push(name.clone()),
+            Pattern::Variant { args, .. } => {
+                for arg in args {
+                    arg.collect_names(out);
+                }
+            }
+            Pattern::Wildcard { .. }
+            | Pattern::Int { .. }
+            | Pattern::Bool { .. }
+            | Pattern::String { .. } => {}
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            Pattern::Wildcard { span }
+            | Pattern::Name { span, .. }
+            | Pattern::Int { span, .. }
+            | Pattern::Bool { span, .. }
+            | Pattern::String { span, .. }
+            | Pattern::Variant { span, .. } => *span,
+        }
+    }
+
+    pub fn shift(&mut self, delta: isize) {
+        match self {
+            Pattern::Wildcard { span }
+            | Pattern::Name { span, .. }
+            | Pattern::Int { span, .. }
+            | Pattern::Bool { span, .. }
+            | Pattern::String { span, .. } => *span = span.shifted(delta),
+            Pattern::Variant { args, span, .. } => {
+                args.iter_mut().for_each(|arg| arg.shift(delta));
+                *span = span.shifted(delta);
+            }
         }
     }
 }
 
+/// Unary operator
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnaryOp {
+    Not,
+}
+
 /// Binary operator
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum BinOp {
@@ -146,4 +902,32 @@ pub enum BinOp {
     Sub,
     Mul,
     Div,
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    And,
+    Or,
+}
+
+impl BinOp {
+    /// `+`, `-`, `*`, `/`
+    pub fn is_arithmetic(&self) -> bool {
+        matches!(self, BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div)
+    }
+
+    /// `==`, `!=`, `<`, `<=`, `>`, `>=`
+    pub fn is_comparison(&self) -> bool {
+        matches!(
+            self,
+            BinOp::Eq | BinOp::NotEq | BinOp::Lt | BinOp::LtEq | BinOp::Gt | BinOp::GtEq
+        )
+    }
+
+    /// `&&`, `||`
+    pub fn is_logical(&self) -> bool {
+        matches!(self, BinOp::And | BinOp::Or)
+    }
 }
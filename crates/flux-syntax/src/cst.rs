@@ -0,0 +1,1268 @@
+//! Lossless concrete syntax tree built on rowan. Every byte of the source,
+//! including whitespace, comments and text the parser skipped, is a token in
+//! the tree, so `SyntaxNode::to_string` gives back the exact input. The typed
+//! nodes below are views over the tree; semantic analysis and codegen keep
+//! using the owned `ast` types.
+
+use crate::ast::BinOp;
+use crate::lexer::TokenKind;
+
+macro_rules! syntax_kinds {
+    (tokens { $($token:ident),* $(,)? } nodes { $($node:ident),* $(,)? }) => {
+        /// Kind of a token or node in the syntax tree. Token kinds mirror
+        /// `TokenKind`; `Error` is both an unlexable token and a node wrapping
+        /// source the parser skipped.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        #[repr(u16)]
+        pub enum SyntaxKind {
+            $($token,)*
+            $($node,)*
+        }
+
+        impl SyntaxKind {
+            const ALL: &'static [SyntaxKind] = &[
+                $(SyntaxKind::$token,)*
+                $(SyntaxKind::$node,)*
+            ];
+        }
+
+        impl From<TokenKind> for SyntaxKind {
+            fn from(kind: TokenKind) -> Self {
+                match kind {
+                    $(TokenKind::$token => SyntaxKind::$token,)*
+                }
+            }
+        }
+    };
+}
+
+syntax_kinds! {
+    tokens {
+        Whitespace, Comment,
+        KwFn, KwLet, KwReturn, KwExport, KwIf, KwElse, KwImport, KwType, KwStruct, KwEnum,
+        KwMatch, KwNative,
+        TyInt, TyString, TyBool, TyFloat, TyProject,
+        OpArrow, OpFatArrow, OpEq, OpEqEq, OpNotEq, OpLt, OpLtEq, OpGt, OpGtEq,
+        OpAnd, OpOr, OpPipe, OpBang, OpPlus, OpMinus, OpStar, OpSlash, OpQuestion,
+        LParen, RParen, LBrace, RBrace, LBracket, RBracket, Comma, Colon, Dot, Semi,
+        LitInt, LitFloat, LitTrue, LitFalse, LitString, LitColumn, LitTemporal, LitDuration,
+        Ident,
+        Error, Eof,
+    }
+    nodes {
+        SourceFile,
+        ImportDef, ImportList,
+        TypeAliasDef, StructDef, RecordType, FieldDef, EnumDef, VariantDef, ListType, TupleType,
+        FunctionType,
+        FnDef, ParamList, Param, RetType, TypeRef, TypeParamList, TypeParam, TypeArgList,
+        Literal, ColumnRef, NameRef, ParenExpr, BlockExpr, IfExpr, LetExpr,
+        ReturnExpr, BinExpr, UnaryExpr, PipelineExpr, CallExpr, ArgList,
+        RecordExpr, RecordField, FieldExpr, MatchExpr, MatchArm, LambdaExpr, ListExpr, TupleExpr,
+        IndexExpr, TryExpr,
+        WildcardPat, NamePat, LiteralPat, VariantPat,
+    }
+}
+
+impl SyntaxKind {
+    pub fn is_trivia(self) -> bool {
+        matches!(self, SyntaxKind::Whitespace | SyntaxKind::Comment)
+    }
+}
+
+impl From<SyntaxKind> for rowan::SyntaxKind {
+    fn from(kind: SyntaxKind) -> Self {
+        rowan::SyntaxKind(kind as u16)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FluxLanguage {}
+
+impl rowan::Language for FluxLanguage {
+    type Kind = SyntaxKind;
+
+    fn kind_from_raw(raw: rowan::SyntaxKind) -> SyntaxKind {
+        SyntaxKind::ALL[raw.0 as usize]
+    }
+
+    fn kind_to_raw(kind: SyntaxKind) -> rowan::SyntaxKind {
+        kind.into()
+    }
+}
+
+pub type SyntaxNode = rowan::SyntaxNode<FluxLanguage>;
+pub type SyntaxToken = rowan::SyntaxToken<FluxLanguage>;
+pub type SyntaxElement = rowan::SyntaxElement<FluxLanguage>;
+
+/// A typed view of a syntax node
+pub trait AstNode: Sized {
+    fn cast(node: SyntaxNode) -> Option<Self>;
+    fn syntax(&self) -> &SyntaxNode;
+}
+
+macro_rules! ast_node {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub struct $name(SyntaxNode);
+
+        impl AstNode for $name {
+            fn cast(node: SyntaxNode) -> Option<Self> {
+                (node.kind() == SyntaxKind::$name).then(|| Self(node))
+            }
+
+            fn syntax(&self) -> &SyntaxNode {
+                &self.0
+            }
+        }
+    };
+}
+
+fn child<N: AstNode>(parent: &SyntaxNode) -> Option<N> {
+    parent.children().find_map(N::cast)
+}
+
+fn children<N: AstNode>(parent: &SyntaxNode) -> impl Iterator<Item = N> {
+    parent.children().filter_map(N::cast)
+}
+
+fn token(parent: &SyntaxNode, kind: SyntaxKind) -> Option<SyntaxToken> {
+    parent
+        .children_with_tokens()
+        .filter_map(SyntaxElement::into_token)
+        .find(|token| token.kind() == kind)
+}
+
+/// First non-trivia token directly under `parent`
+fn first_token(parent: &SyntaxNode) -> Option<SyntaxToken> {
+    parent
+        .children_with_tokens()
+        .filter_map(SyntaxElement::into_token)
+        .find(|token| !token.kind().is_trivia())
+}
+
+ast_node!(
+    /// Root of a file
+    SourceFile
+);
+
+impl SourceFile {
+    pub fn imports(&self) -> impl Iterator<Item = ImportDef> {
+        children(&self.0)
+    }
+
+    pub fn functions(&self) -> impl Iterator<Item = FnDef> {
+        children(&self.0)
+    }
+
+    pub fn type_aliases(&self) -> impl Iterator<Item = TypeAliasDef> {
+        children(&self.0)
+    }
+
+    pub fn structs(&self) -> impl Iterator<Item = StructDef> {
+        children(&self.0)
+    }
+
+    pub fn enums(&self) -> impl Iterator<Item = EnumDef> {
+        children(&self.0)
+    }
+}
+
+ast_node!(
+    /// `import a.b` or `import a.b.{f, g}`
+    ImportDef
+);
+
+impl ImportDef {
+    /// Segments of the module path
+    pub fn path(&self) -> impl Iterator<Item = SyntaxToken> {
+        self.0
+            .children_with_tokens()
+            .filter_map(SyntaxElement::into_token)
+            .filter(|token| token.kind() == SyntaxKind::Ident)
+    }
+
+    pub fn import_list(&self) -> Option<ImportList> {
+        child(&self.0)
+    }
+}
+
+ast_node!(
+    /// `{f, g}`
+    ImportList
+);
+
+impl ImportList {
+    pub fn names(&self) -> impl Iterator<Item = SyntaxToken> {
+        self.0
+            .children_with_tokens()
+            .filter_map(SyntaxElement::into_token)
+            .filter(|token| token.kind() == SyntaxKind::Ident)
+    }
+}
+
+ast_node!(
+    /// `type Name = type`
+    TypeAliasDef
+);
+
+impl TypeAliasDef {
+    pub fn name(&self) -> Option<SyntaxToken> {
+        token(&self.0, SyntaxKind::Ident)
+    }
+
+    pub fn type_param_list(&self) -> Option<TypeParamList> {
+        child(&self.0)
+    }
+
+    pub fn ty(&self) -> Option<TypeRef> {
+        child(&self.0)
+    }
+}
+
+ast_node!(
+    /// `struct Name { fields }`
+    StructDef
+);
+
+impl StructDef {
+    pub fn name(&self) -> Option<SyntaxToken> {
+        token(&self.0, SyntaxKind::Ident)
+    }
+
+    pub fn type_param_list(&self) -> Option<TypeParamList> {
+        child(&self.0)
+    }
+
+    pub fn fields(&self) -> impl Iterator<Item = FieldDef> {
+        children(&self.0)
+    }
+}
+
+ast_node!(
+    /// `{ fields }` in a type annotation
+    RecordType
+);
+
+impl RecordType {
+    pub fn fields(&self) -> impl Iterator<Item = FieldDef> {
+        children(&self.0)
+    }
+}
+
+ast_node!(
+    /// `List<type>` in a type annotation
+    ListType
+);
+
+impl ListType {
+    pub fn elem(&self) -> Option<TypeRef> {
+        child(&self.0)
+    }
+}
+
+ast_node!(
+    /// `(type, type, ..)` in a type annotation
+    TupleType
+);
+
+impl TupleType {
+    pub fn elems(&self) -> impl Iterator<Item = TypeRef> {
+        children(&self.0)
+    }
+}
+
+ast_node!(
+    /// `(type, ..) -> type` in a type annotation
+    FunctionType
+);
+
+impl FunctionType {
+    /// The parameter types, in the parentheses before `->`
+    pub fn params(&self) -> impl Iterator<Item = TypeRef> {
+        let arrow = token(&self.0, SyntaxKind::OpArrow).map(|arrow| arrow.text_range().start());
+        children(&self.0).take_while(move |ty: &TypeRef| {
+            arrow.is_none_or(|arrow| ty.syntax().text_range().end() <= arrow)
+        })
+    }
+
+    pub fn ret(&self) -> Option<TypeRef> {
+        let arrow = token(&self.0, SyntaxKind::OpArrow)?.text_range().end();
+        children(&self.0).find(|ty: &TypeRef| ty.syntax().text_range().start() >= arrow)
+    }
+}
+
+ast_node!(
+    /// `name: type` of a record type or struct
+    FieldDef
+);
+
+impl FieldDef {
+    pub fn name(&self) -> Option<SyntaxToken> {
+        token(&self.0, SyntaxKind::Ident)
+    }
+
+    pub fn ty(&self) -> Option<TypeRef> {
+        child(&self.0)
+    }
+}
+
+ast_node!(
+    /// `enum Name { variants }`
+    EnumDef
+);
+
+impl EnumDef {
+    pub fn name(&self) -> Option<SyntaxToken> {
+        token(&self.0, SyntaxKind::Ident)
+    }
+
+    pub fn type_param_list(&self) -> Option<TypeParamList> {
+        child(&self.0)
+    }
+
+    pub fn variants(&self) -> impl Iterator<Item = VariantDef> {
+        children(&self.0)
+    }
+}
+
+ast_node!(
+    /// `Name` or `Name(type, ..)` of an enum
+    VariantDef
+);
+
+impl VariantDef {
+    pub fn name(&self) -> Option<SyntaxToken> {
+        token(&self.0, SyntaxKind::Ident)
+    }
+
+    pub fn fields(&self) -> impl Iterator<Item = TypeRef> {
+        children(&self.0)
+    }
+}
+
+ast_node!(
+    /// `export fn name(params) -> type body`, or `native fn name(params) ->
+    /// type` without a body
+    FnDef
+);
+
+impl FnDef {
+    pub fn is_export(&self) -> bool {
+        token(&self.0, SyntaxKind::KwExport).is_some()
+    }
+
+    pub fn is_native(&self) -> bool {
+        token(&self.0, SyntaxKind::KwNative).is_some()
+    }
+
+    pub fn name(&self) -> Option<SyntaxToken> {
+        token(&self.0, SyntaxKind::Ident)
+    }
+
+    pub fn type_param_list(&self) -> Option<TypeParamList> {
+        child(&self.0)
+    }
+
+    pub fn param_list(&self) -> Option<ParamList> {
+        child(&self.0)
+    }
+
+    pub fn ret_type(&self) -> Option<RetType> {
+        child(&self.0)
+    }
+
+    pub fn body(&self) -> Option<Expr> {
+        child(&self.0)
+    }
+}
+
+ast_node!(
+    /// `<T, U>` of a generic function or type
+    TypeParamList
+);
+
+impl TypeParamList {
+    pub fn params(&self) -> impl Iterator<Item = TypeParam> {
+        children(&self.0)
+    }
+}
+
+ast_node!(
+    /// A type parameter, `T`
+    TypeParam
+);
+
+impl TypeParam {
+    pub fn name(&self) -> Option<SyntaxToken> {
+        token(&self.0, SyntaxKind::Ident)
+    }
+}
+
+ast_node!(
+    /// `(a: int, b)`
+    ParamList
+);
+
+impl ParamList {
+    pub fn params(&self) -> impl Iterator<Item = Param> {
+        children(&self.0)
+    }
+}
+
+ast_node!(
+    /// `name` or `name: type`
+    Param
+);
+
+impl Param {
+    pub fn name(&self) -> Option<SyntaxToken> {
+        token(&self.0, SyntaxKind::Ident)
+    }
+
+    pub fn ty(&self) -> Option<TypeRef> {
+        child(&self.0)
+    }
+}
+
+ast_node!(
+    /// `-> type`
+    RetType
+);
+
+impl RetType {
+    pub fn ty(&self) -> Option<TypeRef> {
+        child(&self.0)
+    }
+}
+
+ast_node!(
+    /// A type annotation
+    TypeRef
+);
+
+impl TypeRef {
+    /// The type's name; `None` for a record, list, tuple or function type
+    pub fn token(&self) -> Option<SyntaxToken> {
+        first_token(&self.0)
+    }
+
+    pub fn record_type(&self) -> Option<RecordType> {
+        child(&self.0)
+    }
+
+    pub fn list_type(&self) -> Option<ListType> {
+        child(&self.0)
+    }
+
+    pub fn tuple_type(&self) -> Option<TupleType> {
+        child(&self.0)
+    }
+
+    pub fn function_type(&self) -> Option<FunctionType> {
+        child(&self.0)
+    }
+
+    /// `<int, string>` after the type's name
+    pub fn type_arg_list(&self) -> Option<TypeArgList> {
+        child(&self.0)
+    }
+}
+
+ast_node!(
+    /// `<type, ..>` giving the type arguments of a generic type
+    TypeArgList
+);
+
+impl TypeArgList {
+    pub fn args(&self) -> impl Iterator<Item = TypeRef> {
+        children(&self.0)
+    }
+}
+
+/// Any expression node
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Expr {
+    Literal(Literal),
+    ColumnRef(ColumnRef),
+    NameRef(NameRef),
+    Paren(ParenExpr),
+    Block(BlockExpr),
+    If(IfExpr),
+    Let(LetExpr),
+    Return(ReturnExpr),
+    Bin(BinExpr),
+    Unary(UnaryExpr),
+    Pipeline(PipelineExpr),
+    Call(CallExpr),
+    Record(RecordExpr),
+    Field(FieldExpr),
+    Match(MatchExpr),
+    Lambda(LambdaExpr),
+    List(ListExpr),
+    Tuple(TupleExpr),
+    Index(IndexExpr),
+    Try(TryExpr),
+}
+
+impl AstNode for Expr {
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        let expr = match node.kind() {
+            SyntaxKind::Literal => Expr::Literal(Literal(node)),
+            SyntaxKind::ColumnRef => Expr::ColumnRef(ColumnRef(node)),
+            SyntaxKind::NameRef => Expr::NameRef(NameRef(node)),
+            SyntaxKind::ParenExpr => Expr::Paren(ParenExpr(node)),
+            SyntaxKind::BlockExpr => Expr::Block(BlockExpr(node)),
+            SyntaxKind::IfExpr => Expr::If(IfExpr(node)),
+            SyntaxKind::LetExpr => Expr::Let(LetExpr(node)),
+            SyntaxKind::ReturnExpr => Expr::Return(ReturnExpr(node)),
+            SyntaxKind::BinExpr => Expr::Bin(BinExpr(node)),
+            SyntaxKind::UnaryExpr => Expr::Unary(UnaryExpr(node)),
+            SyntaxKind::PipelineExpr => Expr::Pipeline(PipelineExpr(node)),
+            SyntaxKind::CallExpr => Expr::Call(CallExpr(node)),
+            SyntaxKind::RecordExpr => Expr::Record(RecordExpr(node)),
+            SyntaxKind::FieldExpr => Expr::Field(FieldExpr(node)),
+            SyntaxKind::MatchExpr => Expr::Match(MatchExpr(node)),
+            SyntaxKind::LambdaExpr => Expr::Lambda(LambdaExpr(node)),
+            SyntaxKind::ListExpr => Expr::List(ListExpr(node)),
+            SyntaxKind::TupleExpr => Expr::Tuple(TupleExpr(node)),
+            SyntaxKind::IndexExpr => Expr::Index(IndexExpr(node)),
+            SyntaxKind::TryExpr => Expr::Try(TryExpr(node)),
+            _ => return None,
+        };
+        Some(expr)
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            Expr::Literal(it) => it.syntax(),
+            Expr::ColumnRef(it) => it.syntax(),
+            Expr::NameRef(it) => it.syntax(),
+            Expr::Paren(it) => it.syntax(),
+            Expr::Block(it) => it.syntax(),
+            Expr::If(it) => it.syntax(),
+            Expr::Let(it) => it.syntax(),
+            Expr::Return(it) => it.syntax(),
+            Expr::Bin(it) => it.syntax(),
+            Expr::Unary(it) => it.syntax(),
+            Expr::Pipeline(it) => it.syntax(),
+            Expr::Call(it) => it.syntax(),
+            Expr::Record(it) => it.syntax(),
+            Expr::Field(it) => it.syntax(),
+            Expr::Match(it) => it.syntax(),
+            Expr::Lambda(it) => it.syntax(),
+            Expr::List(it) => it.syntax(),
+            Expr::Tuple(it) => it.syntax(),
+            Expr::Index(it) => it.syntax(),
+            Expr::Try(it) => it.syntax(),
+        }
+    }
+}
+
+ast_node!(
+    /// Int, float, bool, string or temporal literal; a duration literal
+    /// holds one token per part
+    Literal
+);
+
+impl Literal {
+    pub fn token(&self) -> Option<SyntaxToken> {
+        first_token(&self.0)
+    }
+}
+
+ast_node!(
+    /// `#name` or `#"quoted name"`
+    ColumnRef
+);
+
+impl ColumnRef {
+    pub fn token(&self) -> Option<SyntaxToken> {
+        token(&self.0, SyntaxKind::LitColumn)
+    }
+}
+
+ast_node!(
+    /// A variable or function name
+    NameRef
+);
+
+impl NameRef {
+    pub fn ident(&self) -> Option<SyntaxToken> {
+        token(&self.0, SyntaxKind::Ident)
+    }
+}
+
+ast_node!(
+    /// `(expr)`
+    ParenExpr
+);
+
+impl ParenExpr {
+    pub fn expr(&self) -> Option<Expr> {
+        child(&self.0)
+    }
+}
+
+ast_node!(
+    /// `{ stmts }`
+    BlockExpr
+);
+
+impl BlockExpr {
+    /// Statements that parsed; skipped source sits in `Error` nodes between them
+    pub fn stmts(&self) -> impl Iterator<Item = Expr> {
+        children(&self.0)
+    }
+}
+
+ast_node!(
+    /// `if cond { .. } else { .. }`
+    IfExpr
+);
+
+impl IfExpr {
+    pub fn condition(&self) -> Option<Expr> {
+        children(&self.0).next()
+    }
+
+    pub fn then_branch(&self) -> Option<Expr> {
+        children(&self.0).nth(1)
+    }
+
+    /// A block, or the nested `if` of an `else if`
+    pub fn else_branch(&self) -> Option<Expr> {
+        children(&self.0).nth(2)
+    }
+}
+
+ast_node!(
+    /// `let name = value body`
+    LetExpr
+);
+
+impl LetExpr {
+    pub fn name(&self) -> Option<SyntaxToken> {
+        token(&self.0, SyntaxKind::Ident)
+    }
+
+    pub fn value(&self) -> Option<Expr> {
+        children(&self.0).next()
+    }
+
+    pub fn body(&self) -> Option<Expr> {
+        children(&self.0).nth(1)
+    }
+}
+
+ast_node!(
+    /// `return value`
+    ReturnExpr
+);
+
+impl ReturnExpr {
+    pub fn value(&self) -> Option<Expr> {
+        child(&self.0)
+    }
+}
+
+ast_node!(
+    /// `lhs op rhs`
+    BinExpr
+);
+
+impl BinExpr {
+    pub fn lhs(&self) -> Option<Expr> {
+        children(&self.0).next()
+    }
+
+    pub fn rhs(&self) -> Option<Expr> {
+        children(&self.0).nth(1)
+    }
+
+    pub fn op_token(&self) -> Option<SyntaxToken> {
+        self.0
+            .children_with_tokens()
+            .filter_map(SyntaxElement::into_token)
+            .find(|token| Self::op_for(token.kind()).is_some())
+    }
+
+    pub fn op(&self) -> Option<BinOp> {
+        self.op_token().and_then(|token| Self::op_for(token.kind()))
+    }
+
+    fn op_for(kind: SyntaxKind) -> Option<BinOp> {
+        let op = match kind {
+            SyntaxKind::OpPlus => BinOp::Add,
+            SyntaxKind::OpMinus => BinOp::Sub,
+            SyntaxKind::OpStar => BinOp::Mul,
+            SyntaxKind::OpSlash => BinOp::Div,
+            SyntaxKind::OpEqEq => BinOp::Eq,
+            SyntaxKind::OpNotEq => BinOp::NotEq,
+            SyntaxKind::OpLt => BinOp::Lt,
+            SyntaxKind::OpLtEq => BinOp::LtEq,
+            SyntaxKind::OpGt => BinOp::Gt,
+            SyntaxKind::OpGtEq => BinOp::GtEq,
+            SyntaxKind::OpAnd => BinOp::And,
+            SyntaxKind::OpOr => BinOp::Or,
+            _ => return None,
+        };
+        Some(op)
+    }
+}
+
+ast_node!(
+    /// `!operand`
+    UnaryExpr
+);
+
+impl UnaryExpr {
+    pub fn op_token(&self) -> Option<SyntaxToken> {
+        token(&self.0, SyntaxKind::OpBang)
+    }
+
+    pub fn operand(&self) -> Option<Expr> {
+        child(&self.0)
+    }
+}
+
+ast_node!(
+    /// `lhs |> rhs`
+    PipelineExpr
+);
+
+impl PipelineExpr {
+    pub fn lhs(&self) -> Option<Expr> {
+        children(&self.0).next()
+    }
+
+    pub fn rhs(&self) -> Option<Expr> {
+        children(&self.0).nth(1)
+    }
+}
+
+ast_node!(
+    /// `callee(args)`
+    CallExpr
+);
+
+impl CallExpr {
+    pub fn callee(&self) -> Option<Expr> {
+        child(&self.0)
+    }
+
+    pub fn arg_list(&self) -> Option<ArgList> {
+        child(&self.0)
+    }
+}
+
+ast_node!(
+    /// `(a, b)` of a call
+    ArgList
+);
+
+impl ArgList {
+    pub fn args(&self) -> impl Iterator<Item = Expr> {
+        children(&self.0)
+    }
+}
+
+ast_node!(
+    /// `{ x: 1.0 }` or `Name { x: 1.0 }`
+    RecordExpr
+);
+
+impl RecordExpr {
+    /// The type named before the `{`
+    pub fn name(&self) -> Option<SyntaxToken> {
+        token(&self.0, SyntaxKind::Ident)
+    }
+
+    pub fn fields(&self) -> impl Iterator<Item = RecordField> {
+        children(&self.0)
+    }
+}
+
+ast_node!(
+    /// `name: value` of a record literal
+    RecordField
+);
+
+impl RecordField {
+    pub fn name(&self) -> Option<SyntaxToken> {
+        token(&self.0, SyntaxKind::Ident)
+    }
+
+    pub fn value(&self) -> Option<Expr> {
+        child(&self.0)
+    }
+}
+
+ast_node!(
+    /// `base.field`
+    FieldExpr
+);
+
+impl FieldExpr {
+    pub fn base(&self) -> Option<Expr> {
+        child(&self.0)
+    }
+
+    pub fn field(&self) -> Option<SyntaxToken> {
+        token(&self.0, SyntaxKind::Ident)
+    }
+}
+
+ast_node!(
+    /// `[elems]`
+    ListExpr
+);
+
+impl ListExpr {
+    pub fn elems(&self) -> impl Iterator<Item = Expr> {
+        children(&self.0)
+    }
+}
+
+ast_node!(
+    /// `(elem, elem, ..)`
+    TupleExpr
+);
+
+impl TupleExpr {
+    pub fn elems(&self) -> impl Iterator<Item = Expr> {
+        children(&self.0)
+    }
+}
+
+ast_node!(
+    /// `base[index]`
+    IndexExpr
+);
+
+impl IndexExpr {
+    pub fn base(&self) -> Option<Expr> {
+        children(&self.0).next()
+    }
+
+    pub fn index(&self) -> Option<Expr> {
+        children(&self.0).nth(1)
+    }
+}
+
+ast_node!(
+    /// `operand?`
+    TryExpr
+);
+
+impl TryExpr {
+    pub fn operand(&self) -> Option<Expr> {
+        child(&self.0)
+    }
+}
+
+ast_node!(
+    /// `match scrutinee { arms }`
+    MatchExpr
+);
+
+impl MatchExpr {
+    pub fn scrutinee(&self) -> Option<Expr> {
+        child(&self.0)
+    }
+
+    pub fn arms(&self) -> impl Iterator<Item = MatchArm> {
+        children(&self.0)
+    }
+}
+
+ast_node!(
+    /// `fn(params) -> type { body }`
+    LambdaExpr
+);
+
+impl LambdaExpr {
+    pub fn param_list(&self) -> Option<ParamList> {
+        child(&self.0)
+    }
+
+    pub fn ret_type(&self) -> Option<RetType> {
+        child(&self.0)
+    }
+
+    pub fn body(&self) -> Option<Expr> {
+        child(&self.0)
+    }
+}
+
+ast_node!(
+    /// `pattern => body`
+    MatchArm
+);
+
+impl MatchArm {
+    pub fn pattern(&self) -> Option<Pattern> {
+        child(&self.0)
+    }
+
+    pub fn body(&self) -> Option<Expr> {
+        child(&self.0)
+    }
+}
+
+/// Any pattern node
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Pattern {
+    Wildcard(WildcardPat),
+    Name(NamePat),
+    Literal(LiteralPat),
+    Variant(VariantPat),
+}
+
+impl AstNode for Pattern {
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        let pattern = match node.kind() {
+            SyntaxKind::WildcardPat => Pattern::Wildcard(WildcardPat(node)),
+            SyntaxKind::NamePat => Pattern::Name(NamePat(node)),
+            SyntaxKind::LiteralPat => Pattern::Literal(LiteralPat(node)),
+            SyntaxKind::VariantPat => Pattern::Variant(VariantPat(node)),
+            _ => return None,
+        };
+        Some(pattern)
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            Pattern::Wildcard(it) => it.syntax(),
+            Pattern::Name(it) => it.syntax(),
+            Pattern::Literal(it) => it.syntax(),
+            Pattern::Variant(it) => it.syntax(),
+        }
+    }
+}
+
+ast_node!(
+    /// `_`
+    WildcardPat
+);
+
+ast_node!(
+    /// A unit variant or a binding
+    NamePat
+);
+
+impl NamePat {
+    pub fn ident(&self) -> Option<SyntaxToken> {
+        token(&self.0, SyntaxKind::Ident)
+    }
+}
+
+ast_node!(
+    /// Int, bool or string literal pattern
+    LiteralPat
+);
+
+impl LiteralPat {
+    pub fn token(&self) -> Option<SyntaxToken> {
+        first_token(&self.0)
+    }
+}
+
+ast_node!(
+    /// `Name(patterns)`
+    VariantPat
+);
+
+impl VariantPat {
+    pub fn name(&self) -> Option<SyntaxToken> {
+        token(&self.0, SyntaxKind::Ident)
+    }
+
+    pub fn args(&self) -> impl Iterator<Item = Pattern> {
+        children(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_with_recovery;
+
+    fn text(token: Option<SyntaxToken>) -> String {
+        token
+            .map(|token| token.text().to_string())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_typed_function_type_accessors() {
+        let parse = parse_with_recovery("fn f(g: (int, bool) -> (int) -> int) { g }");
+        let tree = parse.tree();
+        let func = tree.functions().next().unwrap();
+        let param = func.param_list().unwrap().params().next().unwrap();
+        let function_type = param.ty().and_then(|ty| ty.function_type()).unwrap();
+        let params: Vec<_> = function_type.params().map(|ty| text(ty.token())).collect();
+        assert_eq!(params, ["int", "bool"]);
+        let ret = function_type
+            .ret()
+            .and_then(|ret| ret.function_type())
+            .unwrap();
+        assert_eq!(ret.params().count(), 1);
+        assert_eq!(text(ret.ret().unwrap().token()), "int");
+    }
+
+    #[test]
+    fn test_typed_function_accessors() {
+        let parse = parse_with_recovery("export fn add(x: int, y) -> int { x + y }");
+        let func = parse.tree().functions().next().unwrap();
+
+        assert!(func.is_export());
+        assert_eq!(text(func.name()), "add");
+        let params: Vec<_> = func.param_list().unwrap().params().collect();
+        assert_eq!(text(params[0].name()), "x");
+        assert_eq!(text(params[0].ty().and_then(|ty| ty.token())), "int");
+        assert_eq!(text(params[1].name()), "y");
+        assert!(params[1].ty().is_none());
+        assert_eq!(
+            text(
+                func.ret_type()
+                    .and_then(|ret| ret.ty())
+                    .and_then(|ty| ty.token())
+            ),
+            "int"
+        );
+
+        let Some(Expr::Block(body)) = func.body() else {
+            panic!("Expected block body");
+        };
+        let Some(Expr::Bin(sum)) = body.stmts().next() else {
+            panic!("Expected binary expression");
+        };
+        assert_eq!(sum.op(), Some(BinOp::Add));
+        assert_eq!(sum.lhs().unwrap().syntax().to_string(), "x");
+        assert_eq!(sum.rhs().unwrap().syntax().to_string(), "y");
+    }
+
+    #[test]
+    fn test_native_function_has_no_body() {
+        let parse = parse_with_recovery("native fn tick() -> int\nfn main() -> int { tick() }");
+        let funcs: Vec<_> = parse.tree().functions().collect();
+
+        assert!(funcs[0].is_native() && !funcs[0].is_export());
+        assert!(funcs[0].ret_type().is_some());
+        assert!(funcs[0].body().is_none());
+        assert!(!funcs[1].is_native());
+        assert!(parse.errors.is_empty());
+    }
+
+    #[test]
+    fn test_typed_expression_accessors() {
+        let parse = parse_with_recovery(
+            "fn f(v: int) -> int { let a = v |> g(#col, 1) if !a { a } else { (a) } }",
+        );
+        let func = parse.tree().functions().next().unwrap();
+        let Some(Expr::Block(body)) = func.body() else {
+            panic!("Expected block body");
+        };
+        let Some(Expr::Let(binding)) = body.stmts().next() else {
+            panic!("Expected let");
+        };
+        assert_eq!(text(binding.name()), "a");
+
+        let Some(Expr::Pipeline(pipeline)) = binding.value() else {
+            panic!("Expected pipeline");
+        };
+        let Some(Expr::Call(call)) = pipeline.rhs() else {
+            panic!("Expected call");
+        };
+        assert_eq!(call.callee().unwrap().syntax().to_string(), "g");
+        let args: Vec<_> = call.arg_list().unwrap().args().collect();
+        assert!(matches!(&args[..], [Expr::ColumnRef(_), Expr::Literal(_)]));
+
+        let Some(Expr::If(if_expr)) = binding.body() else {
+            panic!("Expected if");
+        };
+        assert!(matches!(if_expr.condition(), Some(Expr::Unary(_))));
+        assert!(matches!(if_expr.then_branch(), Some(Expr::Block(_))));
+        assert!(matches!(if_expr.else_branch(), Some(Expr::Block(_))));
+    }
+
+    #[test]
+    fn test_typed_record_accessors() {
+        let parse = parse_with_recovery(
+            "type P = { x: float }\nstruct U { p: P, n: int }\nfn f() { U { p: { x: 1.0 }, n: 2 }.p.x }",
+        );
+        let tree = parse.tree();
+        let alias = tree.type_aliases().next().unwrap();
+        assert_eq!(text(alias.name()), "P");
+        let record_type = alias.ty().and_then(|ty| ty.record_type()).unwrap();
+        assert_eq!(text(record_type.fields().next().unwrap().name()), "x");
+        let def = tree.structs().next().unwrap();
+        assert_eq!(text(def.name()), "U");
+        let fields: Vec<_> = def.fields().map(|field| text(field.name())).collect();
+        assert_eq!(fields, ["p", "n"]);
+
+        let func = tree.functions().next().unwrap();
+        let Some(Expr::Block(body)) = func.body() else {
+            panic!("Expected block body");
+        };
+        let Some(Expr::Field(access)) = body.stmts().next() else {
+            panic!("Expected field access");
+        };
+        assert_eq!(text(access.field()), "x");
+        let Some(Expr::Field(inner)) = access.base() else {
+            panic!("Expected field access");
+        };
+        let Some(Expr::Record(record)) = inner.base() else {
+            panic!("Expected record literal");
+        };
+        assert_eq!(text(record.name()), "U");
+        let first = record.fields().next().unwrap();
+        assert_eq!(text(first.name()), "p");
+        assert!(matches!(first.value(), Some(Expr::Record(_))));
+    }
+
+    #[test]
+    fn test_typed_list_and_tuple_accessors() {
+        let parse =
+            parse_with_recovery("fn f(xs: List<int>) -> (int, bool) { (xs[0], [1, 2][1] > 0) }");
+        let tree = parse.tree();
+        let func = tree.functions().next().unwrap();
+        let param = func.param_list().unwrap().params().next().unwrap();
+        let list_type = param.ty().and_then(|ty| ty.list_type()).unwrap();
+        assert_eq!(text(list_type.elem().unwrap().token()), "int");
+        let ret = func.ret_type().and_then(|ret| ret.ty()).unwrap();
+        let elems: Vec<_> = ret
+            .tuple_type()
+            .unwrap()
+            .elems()
+            .map(|ty| text(ty.token()))
+            .collect();
+        assert_eq!(elems, ["int", "bool"]);
+
+        let Some(Expr::Block(body)) = func.body() else {
+            panic!("Expected block body");
+        };
+        let Some(Expr::Tuple(tuple)) = body.stmts().next() else {
+            panic!("Expected tuple");
+        };
+        let elems: Vec<_> = tuple.elems().collect();
+        assert_eq!(elems.len(), 2);
+        let Expr::Index(index) = &elems[0] else {
+            panic!("Expected index");
+        };
+        assert!(matches!(index.base(), Some(Expr::NameRef(_))));
+        assert!(matches!(index.index(), Some(Expr::Literal(_))));
+        let Expr::Bin(comparison) = &elems[1] else {
+            panic!("Expected comparison");
+        };
+        let Some(Expr::Index(index)) = comparison.lhs() else {
+            panic!("Expected index");
+        };
+        let Some(Expr::List(list)) = index.base() else {
+            panic!("Expected list");
+        };
+        assert_eq!(list.elems().count(), 2);
+    }
+
+    #[test]
+    fn test_typed_try_accessors() {
+        let parse = parse_with_recovery("fn f(o: Option<int>) -> Option<int> { some(o?) }");
+        let func = parse.tree().functions().next().unwrap();
+        let Some(Expr::Block(body)) = func.body() else {
+            panic!("Expected block body");
+        };
+        let Some(Expr::Call(call)) = body.stmts().next() else {
+            panic!("Expected call");
+        };
+        let Some(Expr::Try(try_expr)) = call.arg_list().unwrap().args().next() else {
+            panic!("Expected `?`");
+        };
+        assert_eq!(try_expr.syntax().to_string(), "o?");
+        assert!(matches!(try_expr.operand(), Some(Expr::NameRef(_))));
+    }
+
+    #[test]
+    fn test_typed_generic_accessors() {
+        let source = "enum Maybe<T> { Nothing, Just(T) }\nfn pick<A, B>(pair: Pair<A, B>) -> Maybe<A> { Nothing }";
+        let parse = parse_with_recovery(source);
+        assert!(parse.errors.is_empty(), "{:?}", parse.errors);
+        let tree = parse.tree();
+        assert_eq!(tree.syntax().to_string(), source);
+
+        let def = tree.enums().next().unwrap();
+        let params: Vec<_> = def
+            .type_param_list()
+            .unwrap()
+            .params()
+            .map(|param| text(param.name()))
+            .collect();
+        assert_eq!(params, ["T"]);
+        assert_eq!(text(def.name()), "Maybe");
+
+        let func = tree.functions().next().unwrap();
+        let params: Vec<_> = func
+            .type_param_list()
+            .unwrap()
+            .params()
+            .map(|param| text(param.name()))
+            .collect();
+        assert_eq!(params, ["A", "B"]);
+        let param = func.param_list().unwrap().params().next().unwrap();
+        let ty = param.ty().unwrap();
+        assert_eq!(text(ty.token()), "Pair");
+        let args: Vec<_> = ty
+            .type_arg_list()
+            .unwrap()
+            .args()
+            .map(|arg| text(arg.token()))
+            .collect();
+        assert_eq!(args, ["A", "B"]);
+    }
+
+    #[test]
+    fn test_typed_match_accessors() {
+        let parse = parse_with_recovery(
+            "enum E { A, B(int, E) }\nfn f(e: E) -> int { match e { B(1, _) => 1, x => 0 } }",
+        );
+        let tree = parse.tree();
+        let def = tree.enums().next().unwrap();
+        assert_eq!(text(def.name()), "E");
+        let variants: Vec<_> = def.variants().collect();
+        assert_eq!(text(variants[1].name()), "B");
+        let fields: Vec<_> = variants[1].fields().map(|ty| text(ty.token())).collect();
+        assert_eq!(fields, ["int", "E"]);
+
+        let func = tree.functions().next().unwrap();
+        let Some(Expr::Block(body)) = func.body() else {
+            panic!("Expected block body");
+        };
+        let Some(Expr::Match(match_expr)) = body.stmts().next() else {
+            panic!("Expected match");
+        };
+        assert!(matches!(match_expr.scrutinee(), Some(Expr::NameRef(_))));
+        let arms: Vec<_> = match_expr.arms().collect();
+        let Some(Pattern::Variant(variant)) = arms[0].pattern() else {
+            panic!("Expected variant pattern");
+        };
+        assert_eq!(text(variant.name()), "B");
+        let args: Vec<_> = variant.args().collect();
+        assert!(matches!(
+            &args[..],
+            [Pattern::Literal(_), Pattern::Wildcard(_)]
+        ));
+        assert!(matches!(arms[0].body(), Some(Expr::Literal(_))));
+        let Some(Pattern::Name(name)) = arms[1].pattern() else {
+            panic!("Expected name pattern");
+        };
+        assert_eq!(text(name.ident()), "x");
+    }
+
+    #[test]
+    fn test_syntax_kind_round_trips_through_raw() {
+        for &kind in SyntaxKind::ALL {
+            let raw: rowan::SyntaxKind = kind.into();
+            assert_eq!(<FluxLanguage as rowan::Language>::kind_from_raw(raw), kind);
+        }
+    }
+}
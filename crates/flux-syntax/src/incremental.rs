@@ -0,0 +1,290 @@
+//! Incremental reparsing for editors. An edit inside one function only
+//! relexes and reparses that function; every other top-level node keeps its
+//! syntax subtree, AST and errors, moved past the edit. Anything the fast
+//! path can't prove equivalent to a full parse falls back to one.
+
+use crate::ast::SourceFile;
+use crate::cst::{SyntaxKind, SyntaxToken};
+use crate::lexer::{tokenize_with_trivia, Token, TokenKind};
+use crate::parser::{parse_with_recovery, Parse, Parser};
+use flux_errors::Span;
+use std::ops::Range;
+
+/// Replace the bytes in `range` of the old text with `insert`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub range: Range<usize>,
+    pub insert: String,
+}
+
+impl TextEdit {
+    pub fn new(range: Range<usize>, insert: impl Into<String>) -> Self {
+        Self {
+            range,
+            insert: insert.into(),
+        }
+    }
+
+    pub fn apply(&self, text: &str) -> String {
+        let mut result = String::with_capacity(text.len() + self.insert.len());
+        result.push_str(&text[..self.range.start]);
+        result.push_str(&self.insert);
+        result.push_str(&text[self.range.end..]);
+        result
+    }
+
+    /// How far text after the edit moves
+    fn delta(&self) -> isize {
+        self.insert.len() as isize - self.range.len() as isize
+    }
+}
+
+impl Parse {
+    /// Parse the source after `edit`, reusing everything outside the
+    /// function it touches. The result is always the same as parsing the
+    /// edited text from scratch.
+    pub fn reparse(&self, edit: &TextEdit) -> Parse {
+        let text = edit.apply(&self.syntax().to_string());
+        self.reparse_item(edit, &text)
+            .unwrap_or_else(|| parse_with_recovery(&text))
+    }
+
+    /// Reparse only the function holding `edit`, or `None` if the edit
+    /// reaches past its first or last token
+    fn reparse_item(&self, edit: &TextEdit, text: &str) -> Option<Parse> {
+        let root = self.syntax();
+        let (index, node) = root.children().enumerate().find(|(_, node)| {
+            let start = usize::from(node.text_range().start());
+            start < edit.range.start && usize::from(node.text_range().end()) > edit.range.end
+        })?;
+        if node.kind() != SyntaxKind::FnDef {
+            return None;
+        }
+
+        // The last token is left alone, so the relexed item ends where the
+        // old one did and lexing of the rest of the file is unaffected
+        let last = node.last_token()?;
+        if last.kind().is_trivia() || edit.range.end > usize::from(last.text_range().start()) {
+            return None;
+        }
+
+        let delta = edit.delta();
+        let start = usize::from(node.text_range().start());
+        let end = usize::from(node.text_range().end()).checked_add_signed(delta)?;
+        let mut tokens = tokenize_with_trivia(&text[start..end]);
+        tokens.pop();
+        // An unterminated literal could run on past the item in a full lex
+        if tokens.iter().any(|token| token.kind == TokenKind::Error)
+            || tokens.last().map(|token| token.text.as_str()) != Some(last.text())
+        {
+            return None;
+        }
+        for token in &mut tokens {
+            token.span = token.span.shifted(start as isize);
+        }
+
+        // The item parser also sees the trivia and token after the item, as
+        // a full parse would
+        let follower_end = follower_end(&last).checked_add_signed(delta)?;
+        let mut rest = tokenize_with_trivia(&text[end..follower_end]);
+        for token in &mut rest {
+            token.span = token.span.shifted(end as isize);
+        }
+        let follower = tokens.len() + rest.iter().filter(|t| t.kind.is_trivia()).count();
+        tokens.append(&mut rest);
+        if tokens.last().map(|token| token.kind) != Some(TokenKind::Eof) {
+            tokens.push(Token {
+                kind: TokenKind::Eof,
+                text: String::new(),
+                span: Span::new(text.len(), text.len()),
+            });
+        }
+
+        let (item, green, item_errors) = Parser::from_tokens(tokens).parse_lone_item(follower)?;
+
+        let mut items = self.ast.items.clone();
+        let position = root
+            .children()
+            .take(index)
+            .filter(|node| {
+                matches!(
+                    node.kind(),
+                    SyntaxKind::ImportDef
+                        | SyntaxKind::FnDef
+                        | SyntaxKind::TypeAliasDef
+                        | SyntaxKind::StructDef
+                        | SyntaxKind::EnumDef
+                )
+            })
+            .count();
+        items[position] = item;
+        for later in &mut items[position + 1..] {
+            later.shift(delta);
+        }
+
+        let before: usize = self.item_errors[..index].iter().sum();
+        let after = before + self.item_errors[index];
+        let mut counts = self.item_errors.clone();
+        counts[index] = item_errors.len();
+        let mut errors = self.errors[..before].to_vec();
+        errors.extend(item_errors);
+        errors.extend(self.errors[after..].iter().cloned().map(|mut error| {
+            error.shift(delta);
+            error
+        }));
+
+        // The edit is after the first token, so only the end can move
+        let end = items
+            .last()
+            .map_or(self.ast.span.end, |item| item.span().end);
+        let span = Span::new(self.ast.span.start, end);
+
+        Some(Parse {
+            ast: SourceFile { items, span },
+            errors,
+            green: node.replace_with(green),
+            item_errors: counts,
+        })
+    }
+}
+
+/// End of the first significant token after `last`, or of the file
+fn follower_end(last: &SyntaxToken) -> usize {
+    let mut next = last.next_token();
+    while let Some(token) = next.as_ref().filter(|token| token.kind().is_trivia()) {
+        next = token.next_token();
+    }
+    match next {
+        Some(token) => usize::from(token.text_range().end()),
+        None => {
+            let root = last.parent_ancestors().last().expect("tokens have a root");
+            usize::from(root.text_range().end())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rowan::GreenNodeData;
+
+    const SOURCE: &str = r#"// Three functions
+fn first(x: int) -> int {
+    x + 1
+}
+
+fn second() -> int { 1 + ; 2 }
+
+export fn third( -> int { 3 }
+fn fourth() -> bool { true } // trailing
+"#;
+
+    fn assert_same(incremental: &Parse, full: &Parse) {
+        assert_eq!(incremental.ast, full.ast);
+        assert_eq!(incremental.green, full.green);
+        assert_eq!(
+            format!("{:?}", incremental.errors),
+            format!("{:?}", full.errors)
+        );
+        assert_eq!(incremental.item_errors, full.item_errors);
+    }
+
+    fn green_of(parse: &Parse, index: usize) -> *const GreenNodeData {
+        let node = parse.syntax().children().nth(index).unwrap();
+        let green: &GreenNodeData = &node.green();
+        green
+    }
+
+    #[test]
+    fn test_edit_inside_function_reuses_other_items() {
+        let old = parse_with_recovery(SOURCE);
+        let offset = SOURCE.find("x + 1").unwrap();
+        let edit = TextEdit::new(offset..offset + 1, "value * 20");
+        let text = edit.apply(SOURCE);
+
+        let incremental = old.reparse_item(&edit, &text).expect("reparsed in place");
+        assert_same(&incremental, &parse_with_recovery(&text));
+        for index in 1..4 {
+            assert_eq!(green_of(&incremental, index), green_of(&old, index));
+        }
+    }
+
+    #[test]
+    fn test_edit_moves_later_errors() {
+        let old = parse_with_recovery(SOURCE);
+        let offset = SOURCE.find("x + 1").unwrap();
+        let edit = TextEdit::new(offset..offset, "1 + ");
+        let text = edit.apply(SOURCE);
+
+        let incremental = old.reparse_item(&edit, &text).expect("reparsed in place");
+        assert_same(&incremental, &parse_with_recovery(&text));
+        assert_eq!(incremental.errors.len(), 2);
+    }
+
+    #[test]
+    fn test_edit_that_splits_an_item_falls_back() {
+        let old = parse_with_recovery(SOURCE);
+        let offset = SOURCE.find("x + 1").unwrap();
+        let edit = TextEdit::new(offset..offset, "1 }\nfn inserted() -> int { ");
+        let text = edit.apply(SOURCE);
+
+        assert!(old.reparse_item(&edit, &text).is_none());
+        let reparsed = old.reparse(&edit);
+        assert_same(&reparsed, &parse_with_recovery(&text));
+        assert_eq!(reparsed.ast.items.len(), 4);
+    }
+
+    #[test]
+    fn test_typing_matches_full_parse() {
+        let mut text = SOURCE.to_string();
+        let mut parse = parse_with_recovery(&text);
+        let mut offset = SOURCE.find("x + 1").unwrap();
+
+        for ch in "let y = \"quoted\" // note\n    y |> f(#col)\n    ".chars() {
+            let edit = TextEdit::new(offset..offset, ch.to_string());
+            text = edit.apply(&text);
+            parse = parse.reparse(&edit);
+            assert_same(&parse, &parse_with_recovery(&text));
+            offset += ch.len_utf8();
+        }
+        assert_eq!(parse.syntax().to_string(), text);
+    }
+
+    #[test]
+    fn test_every_small_edit_matches_full_parse() {
+        let inserts = [
+            "x", " ", "1 + ", "}", "{", "\"", "fn ", "export ", "// c", ";", "(", ")", "\n", "$",
+        ];
+        let sources = [
+            SOURCE,
+            include_str!("../../../examples/let_binding.flux"),
+            include_str!("../../../examples/plan.flux"),
+        ];
+        let mut in_place = 0;
+
+        for source in sources {
+            let old = parse_with_recovery(source);
+            for offset in (0..=source.len()).filter(|&i| source.is_char_boundary(i)) {
+                let mut edits: Vec<_> = inserts
+                    .iter()
+                    .map(|insert| TextEdit::new(offset..offset, *insert))
+                    .collect();
+                edits.extend(
+                    (1..=3)
+                        .map(|len| offset..offset + len)
+                        .filter(|range| source.get(range.clone()).is_some())
+                        .map(|range| TextEdit::new(range, "")),
+                );
+
+                for edit in edits {
+                    let text = edit.apply(source);
+                    if let Some(incremental) = old.reparse_item(&edit, &text) {
+                        in_place += 1;
+                        assert_same(&incremental, &parse_with_recovery(&text));
+                    }
+                }
+            }
+        }
+        assert!(in_place > 1000, "only {in_place} edits reparsed in place");
+    }
+}
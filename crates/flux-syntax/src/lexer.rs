@@ -1,9 +1,13 @@
 use logos::Logos;
 
-#[derive(Debug, Clone, Copy, PartialEq, Logos)]
-#[logos(skip r"[ \t\n\f]+")]
-#[logos(skip r"//[^\n]*")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Logos)]
 pub enum TokenKind {
+    // Trivia: kept in the syntax tree, skipped by the parser
+    #[regex(r"[ \t\n\f]+")]
+    Whitespace,
+    #[regex(r"//[^\n]*")]
+    Comment,
+
     // Keywords
     #[token("fn")]
     KwFn,
@@ -13,6 +17,22 @@ pub enum TokenKind {
     KwReturn,
     #[token("export")]
     KwExport,
+    #[token("native")]
+    KwNative,
+    #[token("if")]
+    KwIf,
+    #[token("else")]
+    KwElse,
+    #[token("import")]
+    KwImport,
+    #[token("type")]
+    KwType,
+    #[token("struct")]
+    KwStruct,
+    #[token("enum")]
+    KwEnum,
+    #[token("match")]
+    KwMatch,
 
     // Types
     #[token("int")]
@@ -29,8 +49,30 @@ pub enum TokenKind {
     // Operators
     #[token("->")]
     OpArrow,
+    #[token("=>")]
+    OpFatArrow,
     #[token("=")]
     OpEq,
+    #[token("==")]
+    OpEqEq,
+    #[token("!=")]
+    OpNotEq,
+    #[token("<")]
+    OpLt,
+    #[token("<=")]
+    OpLtEq,
+    #[token(">")]
+    OpGt,
+    #[token(">=")]
+    OpGtEq,
+    #[token("&&")]
+    OpAnd,
+    #[token("||")]
+    OpOr,
+    #[token("|>")]
+    OpPipe,
+    #[token("!")]
+    OpBang,
     #[token("+")]
     OpPlus,
     #[token("-")]
@@ -39,6 +81,8 @@ pub enum TokenKind {
     OpStar,
     #[token("/")]
     OpSlash,
+    #[token("?")]
+    OpQuestion,
 
     // Delimiters
     #[token("(")]
@@ -57,6 +101,8 @@ pub enum TokenKind {
     Comma,
     #[token(":")]
     Colon,
+    #[token(".")]
+    Dot,
     #[token(";")]
     Semi,
 
@@ -71,6 +117,18 @@ pub enum TokenKind {
     LitFalse,
     #[regex(r#""([^"\\]|\\["\\bnfrt]|u[a-fA-F0-9]{4})*""#)]
     LitString,
+    #[regex(r"#[a-zA-Z_][a-zA-Z0-9_]*")]
+    #[regex(r#"#"([^"\\]|\\["\\bnfrt]|u[a-fA-F0-9]{4})*""#)]
+    LitColumn,
+    // `@2026-10-16`, `@12:30:00`, `@2026-10-16T12:00:00[Europe/Paris]`,
+    // `@2026-10-16T12:00:00Z`; a date-time without a zone lexes too, so the
+    // parser can reject it with a helpful error
+    #[regex(r"@[0-9]{4}-[0-9]{2}-[0-9]{2}(T[0-9]{2}:[0-9]{2}(:[0-9]{2}(\.[0-9]{1,9})?)?(Z|\[[A-Za-z0-9_/+:\-]+\])?)?")]
+    #[regex(r"@[0-9]{2}:[0-9]{2}(:[0-9]{2}(\.[0-9]{1,9})?)?")]
+    LitTemporal,
+    // One part of a duration: `5d 3h` is two tokens
+    #[regex(r"[0-9]+(y|mo|w|d|h|m|s|ms|us|ns)")]
+    LitDuration,
 
     // Identifiers
     #[regex(r"[a-zA-Z_][a-zA-Z0-9_]*")]
@@ -83,7 +141,12 @@ pub enum TokenKind {
 
 impl TokenKind {
     pub fn is_trivia(&self) -> bool {
-        matches!(self, TokenKind::Error)
+        matches!(self, TokenKind::Whitespace | TokenKind::Comment)
+    }
+
+    /// Keywords that can still be declared as the name of a type
+    pub fn is_contextual_keyword(&self) -> bool {
+        matches!(self, TokenKind::TyProject)
     }
 }
 
@@ -94,7 +157,15 @@ pub struct Token {
     pub span: flux_errors::Span,
 }
 
+/// Tokenize `input`, dropping whitespace and comments
 pub fn tokenize(input: &str) -> Vec<Token> {
+    let mut tokens = tokenize_with_trivia(input);
+    tokens.retain(|token| !token.kind.is_trivia());
+    tokens
+}
+
+/// Tokenize `input` losslessly: the token texts concatenate back to `input`
+pub fn tokenize_with_trivia(input: &str) -> Vec<Token> {
     let mut lexer = TokenKind::lexer(input);
     let mut tokens = Vec::new();
 
@@ -123,11 +194,19 @@ mod tests {
 
     #[test]
     fn test_tokenize_keywords() {
-        let input = "fn let return";
+        let input = "fn let return if else import type struct enum match native";
         let tokens = tokenize(input);
         assert_eq!(tokens[0].kind, TokenKind::KwFn);
         assert_eq!(tokens[1].kind, TokenKind::KwLet);
         assert_eq!(tokens[2].kind, TokenKind::KwReturn);
+        assert_eq!(tokens[3].kind, TokenKind::KwIf);
+        assert_eq!(tokens[4].kind, TokenKind::KwElse);
+        assert_eq!(tokens[5].kind, TokenKind::KwImport);
+        assert_eq!(tokens[6].kind, TokenKind::KwType);
+        assert_eq!(tokens[7].kind, TokenKind::KwStruct);
+        assert_eq!(tokens[8].kind, TokenKind::KwEnum);
+        assert_eq!(tokens[9].kind, TokenKind::KwMatch);
+        assert_eq!(tokens[10].kind, TokenKind::KwNative);
     }
 
     #[test]
@@ -140,4 +219,74 @@ mod tests {
         assert_eq!(tokens[3].kind, TokenKind::LitFalse);
         assert_eq!(tokens[4].kind, TokenKind::LitFloat);
     }
+
+    #[test]
+    fn test_tokenize_operators() {
+        let input = "== != < <= > >= && || ! = -> |>";
+        let kinds: Vec<_> = tokenize(input).into_iter().map(|t| t.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::OpEqEq,
+                TokenKind::OpNotEq,
+                TokenKind::OpLt,
+                TokenKind::OpLtEq,
+                TokenKind::OpGt,
+                TokenKind::OpGtEq,
+                TokenKind::OpAnd,
+                TokenKind::OpOr,
+                TokenKind::OpBang,
+                TokenKind::OpEq,
+                TokenKind::OpArrow,
+                TokenKind::OpPipe,
+                TokenKind::Eof,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_column_references() {
+        let input = r#"#active #"order total" #"#;
+        let tokens = tokenize(input);
+        assert_eq!(tokens[0].kind, TokenKind::LitColumn);
+        assert_eq!(tokens[0].text, "#active");
+        assert_eq!(tokens[1].kind, TokenKind::LitColumn);
+        assert_eq!(tokens[1].text, r#"#"order total""#);
+        assert_eq!(tokens[2].kind, TokenKind::Error);
+    }
+
+    #[test]
+    fn test_tokenize_temporal_literals() {
+        let input =
+            "@2026-10-16 @12:30 @2026-10-16T12:00:00.5[Europe/Paris] @2026-10-16T12:00Z 5d 10ms 3";
+        let tokens = tokenize(input);
+        let kinds: Vec<_> = tokens.iter().map(|t| t.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::LitTemporal,
+                TokenKind::LitTemporal,
+                TokenKind::LitTemporal,
+                TokenKind::LitTemporal,
+                TokenKind::LitDuration,
+                TokenKind::LitDuration,
+                TokenKind::LitInt,
+                TokenKind::Eof,
+            ]
+        );
+        assert_eq!(tokens[2].text, "@2026-10-16T12:00:00.5[Europe/Paris]");
+        assert_eq!(tokens[5].text, "10ms");
+    }
+
+    #[test]
+    fn test_tokenize_with_trivia_is_lossless() {
+        let input = "fn f() { // note\n\t1 }";
+        let tokens = tokenize_with_trivia(input);
+        let text: String = tokens.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(text, input);
+        assert!(tokens
+            .iter()
+            .any(|t| t.kind == TokenKind::Comment && t.text == "// note"));
+        assert_eq!(tokenize(input).len(), 8);
+    }
 }
@@ -1,7 +1,12 @@
 pub mod ast;
+pub mod cst;
+pub mod incremental;
 pub mod lexer;
 pub mod parser;
+pub mod temporal;
 
 pub use ast::*;
+pub use incremental::TextEdit;
 pub use lexer::*;
 pub use parser::*;
+pub use temporal::{Temporal, TemporalKind};
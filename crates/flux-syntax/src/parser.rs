@@ -1,37 +1,134 @@
 use crate::ast::*;
-use crate::lexer::{tokenize, Token, TokenKind};
+use crate::cst::{self, AstNode, SyntaxKind, SyntaxNode};
+use crate::lexer::{tokenize_with_trivia, Token, TokenKind};
+use crate::temporal::Temporal;
 use flux_errors::{FluxError, Result, Span};
+use rowan::{Checkpoint, GreenNode, GreenNodeBuilder};
 
 pub struct Parser {
+    /// Every token of the input, trivia included
     tokens: Vec<Token>,
+    /// Index of the current significant token
     pos: usize,
+    /// Tokens before this index are already in the syntax tree
+    emitted: usize,
+    builder: GreenNodeBuilder<'static>,
+    /// Number of nodes open in `builder`
+    depth: usize,
+    /// Syntax errors recovered from so far
+    errors: Vec<FluxError>,
+}
+
+/// Result of parsing with error recovery: the AST covers everything that
+/// parsed, with `Expr::Error` where a statement did not, and the syntax tree
+/// holds the full source text
+pub struct Parse {
+    pub ast: SourceFile,
+    pub errors: Vec<FluxError>,
+    pub green: GreenNode,
+    /// Number of `errors` from each top-level node of `green`, in order
+    pub(crate) item_errors: Vec<usize>,
+}
+
+impl std::fmt::Debug for Parse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Parse")
+            .field("ast", &self.ast)
+            .field("errors", &self.errors)
+            .finish()
+    }
+}
+
+/// Two parses are equal when their trees are: the tree holds every byte of
+/// the source, which determines the AST and errors
+impl PartialEq for Parse {
+    fn eq(&self, other: &Self) -> bool {
+        self.green == other.green
+    }
+}
+
+impl Eq for Parse {}
+
+impl Parse {
+    /// The AST, or the first syntax error
+    pub fn into_result(self) -> Result<SourceFile> {
+        match self.errors.into_iter().next() {
+            Some(error) => Err(error),
+            None => Ok(self.ast),
+        }
+    }
+
+    /// Root of the lossless syntax tree
+    pub fn syntax(&self) -> SyntaxNode {
+        SyntaxNode::new_root(self.green.clone())
+    }
+
+    /// Typed view of the syntax tree
+    pub fn tree(&self) -> cst::SourceFile {
+        cst::SourceFile::cast(self.syntax()).expect("root is a SourceFile")
+    }
+}
+
+/// Position to wrap a failed statement or item from
+struct Marker {
+    start: usize,
+    checkpoint: Checkpoint,
+    depth: usize,
 }
 
 impl Parser {
     pub fn new(input: &str) -> Self {
-        Self {
-            tokens: tokenize(input),
+        Self::from_tokens(tokenize_with_trivia(input))
+    }
+
+    /// Parser over already lexed `tokens`, which must end with `Eof`
+    pub(crate) fn from_tokens(tokens: Vec<Token>) -> Self {
+        let mut parser = Self {
+            tokens,
             pos: 0,
+            emitted: 0,
+            builder: GreenNodeBuilder::new(),
+            depth: 0,
+            errors: Vec::new(),
+        };
+        parser.pos = parser.skip_trivia(0);
+        parser
+    }
+
+    /// Index of the first significant token at or after `pos`; the trailing
+    /// `Eof` stops the scan
+    fn skip_trivia(&self, mut pos: usize) -> usize {
+        while self.tokens[pos].kind.is_trivia() {
+            pos += 1;
         }
+        pos
     }
 
     fn current(&self) -> &Token {
-        self.tokens
-            .get(self.pos)
-            .unwrap_or(&self.tokens[self.tokens.len() - 1])
+        &self.tokens[self.pos]
     }
 
-    #[allow(dead_code)]
     fn peek(&self, offset: usize) -> &Token {
-        self.tokens
-            .get(self.pos + offset)
+        self.tokens[self.pos..]
+            .iter()
+            .filter(|token| !token.kind.is_trivia())
+            .nth(offset)
             .unwrap_or(&self.tokens[self.tokens.len() - 1])
     }
 
     fn advance(&mut self) -> Token {
+        let kind = SyntaxKind::from(self.current().kind);
+        self.advance_as(kind)
+    }
+
+    /// Consume the current token, entering it in the tree as `kind`
+    fn advance_as(&mut self, kind: SyntaxKind) -> Token {
         let token = self.current().clone();
         if token.kind != TokenKind::Eof {
-            self.pos += 1;
+            self.flush_trivia();
+            self.builder.token(kind.into(), &token.text);
+            self.emitted = self.pos + 1;
+            self.pos = self.skip_trivia(self.pos + 1);
         }
         token
     }
@@ -49,12 +146,85 @@ impl Parser {
         }
     }
 
-    pub fn parse(&mut self) -> Result<SourceFile> {
+    /// Emit the trivia before the current token into the node open now
+    fn flush_trivia(&mut self) {
+        while self.emitted < self.pos {
+            let token = &self.tokens[self.emitted];
+            self.builder
+                .token(SyntaxKind::from(token.kind).into(), &token.text);
+            self.emitted += 1;
+        }
+    }
+
+    fn start_node(&mut self, kind: SyntaxKind) {
+        self.flush_trivia();
+        self.builder.start_node(kind.into());
+        self.depth += 1;
+    }
+
+    /// Open a node starting at `checkpoint`, around what was emitted since
+    fn start_node_at(&mut self, checkpoint: Checkpoint, kind: SyntaxKind) {
+        self.builder.start_node_at(checkpoint, kind.into());
+        self.depth += 1;
+    }
+
+    fn finish_node(&mut self) {
+        self.builder.finish_node();
+        self.depth -= 1;
+    }
+
+    fn checkpoint(&mut self) -> Checkpoint {
+        self.flush_trivia();
+        self.builder.checkpoint()
+    }
+
+    fn marker(&mut self) -> Marker {
+        Marker {
+            start: self.current().span.start,
+            checkpoint: self.checkpoint(),
+            depth: self.depth,
+        }
+    }
+
+    /// Wrap what was parsed since `marker` in an `Error` node, leaving it open
+    /// for the tokens recovery skips
+    fn start_error_at(&mut self, marker: &Marker) {
+        while self.depth > marker.depth {
+            self.finish_node();
+        }
+        self.start_node_at(marker.checkpoint, SyntaxKind::Error);
+    }
+
+    /// Consume the current token as a node of its own
+    fn bump_node(&mut self, kind: SyntaxKind) -> Token {
+        self.start_node(kind);
+        let token = self.advance();
+        self.finish_node();
+        token
+    }
+
+    /// Parse a whole file, recovering from syntax errors: a broken item is
+    /// skipped up to the next `fn`, `export` or `import`, a broken statement
+    /// up to the next `;` or `}` of its block
+    pub fn parse(&mut self) -> Parse {
+        // Opened before any trivia is flushed: leading trivia belongs in the root
+        self.builder.start_node(SyntaxKind::SourceFile.into());
+        self.depth += 1;
         let start = self.current().span.start;
         let mut items = Vec::new();
+        let mut item_errors = Vec::new();
 
         while self.current().kind != TokenKind::Eof {
-            items.push(self.parse_item()?);
+            let marker = self.marker();
+            let errors_before = self.errors.len();
+            match self.parse_item() {
+                Ok(item) => items.push(item),
+                Err(error) => {
+                    self.errors.push(error);
+                    self.recover_to_item(&marker);
+                }
+            }
+            item_errors.push(self.errors.len() - errors_before);
         }
 
         let end = if items.is_empty() {
@@ -63,22 +233,142 @@ impl Parser {
             items.last().unwrap().span().end
         };
 
-        Ok(SourceFile {
-            items,
-            span: Span::new(start, end),
-        })
+        self.flush_trivia();
+        self.finish_node();
+
+        Parse {
+            ast: SourceFile {
+                items,
+                span: Span::new(start, end),
+            },
+            errors: std::mem::take(&mut self.errors),
+            green: std::mem::take(&mut self.builder).finish(),
+            item_errors,
+        }
+    }
+
+    /// Parse one item that must end right before the token at `follower`,
+    /// which is whatever follows the item in its file. `None` when the item
+    /// fails, ends elsewhere, or its recovery takes in the trivia before
+    /// `follower`, since the item then doesn't stand on its own.
+    pub(crate) fn parse_lone_item(
+        mut self,
+        follower: usize,
+    ) -> Option<(Item, GreenNode, Vec<FluxError>)> {
+        let item = self.parse_item().ok()?;
+        if self.pos != follower || self.tokens[self.emitted - 1].kind.is_trivia() {
+            return None;
+        }
+        Some((item, self.builder.finish(), self.errors))
+    }
+
+    fn at_item_start(&self) -> bool {
+        matches!(
+            self.current().kind,
+            TokenKind::KwExport
+                | TokenKind::KwNative
+                | TokenKind::KwImport
+                | TokenKind::KwType
+                | TokenKind::KwStruct
+                | TokenKind::KwEnum
+        ) || self.at_named_fn()
+    }
+
+    /// `fn name` starts a function item; `fn(` is a lambda
+    fn at_named_fn(&self) -> bool {
+        self.current().kind == TokenKind::KwFn && self.peek(1).kind != TokenKind::LParen
+    }
+
+    /// Skip to the start of the next item, always making progress past the
+    /// token the failed item started at
+    fn recover_to_item(&mut self, marker: &Marker) {
+        self.start_error_at(marker);
+        if self.current().span.start == marker.start {
+            self.advance();
+        }
+        while self.current().kind != TokenKind::Eof && !self.at_item_start() {
+            self.advance();
+        }
+        self.finish_node();
+    }
+
+    /// Whether the enclosing block ends here: at its `}`, or at an item
+    /// keyword or the end of input when the `}` is missing
+    fn at_block_end(&self) -> bool {
+        matches!(self.current().kind, TokenKind::RBrace | TokenKind::Eof) || self.at_item_start()
+    }
+
+    /// Record `error` and skip the rest of the statement that started at
+    /// `marker`, which becomes an `Expr::Error`
+    fn recover_statement(&mut self, marker: &Marker, error: FluxError) -> Expr {
+        self.errors.push(error);
+        self.start_error_at(marker);
+        self.recover_in_block();
+        self.finish_node();
+        Expr::Error {
+            span: Span::new(marker.start, self.previous_end().max(marker.start)),
+        }
+    }
+
+    /// Skip the rest of a broken statement: up to and including the next `;`,
+    /// or up to the `}` closing the enclosing block. Nested blocks are skipped
+    /// whole, and an item keyword ends the block.
+    fn recover_in_block(&mut self) {
+        let mut depth = 0usize;
+        loop {
+            if self.at_named_fn() {
+                return;
+            }
+            match self.current().kind {
+                TokenKind::Eof
+                | TokenKind::KwExport
+                | TokenKind::KwNative
+                | TokenKind::KwImport
+                | TokenKind::KwType
+                | TokenKind::KwStruct
+                | TokenKind::KwEnum => return,
+                TokenKind::RBrace if depth == 0 => return,
+                TokenKind::Semi if depth == 0 => {
+                    self.advance();
+                    return;
+                }
+                TokenKind::LBrace => depth += 1,
+                TokenKind::RBrace => depth -= 1,
+                _ => {}
+            }
+            self.advance();
+        }
     }
 
     fn parse_item(&mut self) -> Result<Item> {
+        match self.current().kind {
+            TokenKind::KwImport => return self.parse_import().map(Item::Import),
+            TokenKind::KwType => return self.parse_type_alias().map(Item::TypeAlias),
+            TokenKind::KwStruct => return self.parse_struct().map(Item::Struct),
+            TokenKind::KwEnum => return self.parse_enum().map(Item::Enum),
+            _ => {}
+        }
+
+        self.start_node(SyntaxKind::FnDef);
         let is_export = if self.current().kind == TokenKind::KwExport {
             self.advance();
             true
         } else {
             false
         };
+        let is_native = if self.current().kind == TokenKind::KwNative {
+            self.advance();
+            true
+        } else {
+            false
+        };
 
         match self.current().kind {
-            TokenKind::KwFn => Ok(Item::Function(self.parse_function(is_export)?)),
+            TokenKind::KwFn => {
+                let function = self.parse_function(is_export, is_native)?;
+                self.finish_node();
+                Ok(Item::Function(function))
+            }
             _ => Err(FluxError::Syntax {
                 message: format!("Expected item, found {:?}", self.current().kind),
                 span: self.current().span.to_source_span(),
@@ -86,13 +376,256 @@ impl Parser {
         }
     }
 
-    fn parse_function(&mut self, is_export: bool) -> Result<Function> {
+    fn parse_import(&mut self) -> Result<Import> {
+        self.start_node(SyntaxKind::ImportDef);
+        let start = self.current().span.start;
+        self.expect(TokenKind::KwImport)?;
+
+        let first = self.expect(TokenKind::Ident)?;
+        let mut path = vec![first.text];
+        let mut path_span = first.span;
+        let mut names = None;
+        while self.current().kind == TokenKind::Dot {
+            self.advance();
+            if self.current().kind == TokenKind::LBrace {
+                names = Some(self.parse_import_list()?);
+                break;
+            }
+            let segment = self.expect(TokenKind::Ident)?;
+            path_span = Span::new(path_span.start, segment.span.end);
+            path.push(segment.text);
+        }
+        self.finish_node();
+
+        Ok(Import {
+            path,
+            path_span,
+            names,
+            span: Span::new(start, self.previous_end()),
+        })
+    }
+
+    fn parse_import_list(&mut self) -> Result<Vec<ImportName>> {
+        self.start_node(SyntaxKind::ImportList);
+        self.expect(TokenKind::LBrace)?;
+        let mut names = Vec::new();
+
+        while self.current().kind != TokenKind::RBrace {
+            let name = self.expect(TokenKind::Ident)?;
+            names.push(ImportName {
+                name: name.text,
+                span: name.span,
+            });
+            if self.current().kind == TokenKind::Comma {
+                self.advance();
+            } else {
+                break;
+            }
+        }
+
+        self.expect(TokenKind::RBrace)?;
+        self.finish_node();
+        Ok(names)
+    }
+
+    /// The name a `type`, `struct` or `enum` declares. `Project`, the type
+    /// a plan returns, is a keyword only where a type is named; declared, it
+    /// enters the tree as a name.
+    fn expect_type_name(&mut self) -> Result<Token> {
+        if self.current().kind.is_contextual_keyword() {
+            Ok(self.advance_as(SyntaxKind::Ident))
+        } else {
+            self.expect(TokenKind::Ident)
+        }
+    }
+
+    fn parse_type_alias(&mut self) -> Result<TypeAlias> {
+        self.start_node(SyntaxKind::TypeAliasDef);
+        let start = self.current().span.start;
+        self.expect(TokenKind::KwType)?;
+        let name = self.expect_type_name()?;
+        let type_params = self.parse_type_params()?;
+        self.expect(TokenKind::OpEq)?;
+        let ty = self.parse_type()?;
+        self.finish_node();
+
+        Ok(TypeAlias {
+            name: name.text,
+            name_span: name.span,
+            type_params,
+            span: Span::new(start, ty.span().end),
+            ty,
+        })
+    }
+
+    fn parse_struct(&mut self) -> Result<Struct> {
+        self.start_node(SyntaxKind::StructDef);
+        let start = self.current().span.start;
+        self.expect(TokenKind::KwStruct)?;
+        let name = self.expect_type_name()?;
+        let type_params = self.parse_type_params()?;
+        let fields = self.parse_field_types()?;
+        self.finish_node();
+
+        Ok(Struct {
+            name: name.text,
+            name_span: name.span,
+            type_params,
+            fields,
+            span: Span::new(start, self.previous_end()),
+        })
+    }
+
+    fn parse_enum(&mut self) -> Result<Enum> {
+        self.start_node(SyntaxKind::EnumDef);
+        let start = self.current().span.start;
+        self.expect(TokenKind::KwEnum)?;
+        let name = self.expect_type_name()?;
+        let type_params = self.parse_type_params()?;
+        self.expect(TokenKind::LBrace)?;
+        let mut variants = Vec::new();
+
+        while self.current().kind != TokenKind::RBrace {
+            variants.push(self.parse_variant()?);
+            if self.current().kind == TokenKind::Comma {
+                self.advance();
+            } else {
+                break;
+            }
+        }
+
+        self.expect(TokenKind::RBrace)?;
+        self.finish_node();
+        Ok(Enum {
+            name: name.text,
+            name_span: name.span,
+            type_params,
+            variants,
+            span: Span::new(start, self.previous_end()),
+        })
+    }
+
+    /// `Name` or `Name(type, ..)`
+    fn parse_variant(&mut self) -> Result<Variant> {
+        self.start_node(SyntaxKind::VariantDef);
+        let name = self.expect(TokenKind::Ident)?;
+        let mut fields = Vec::new();
+        if self.current().kind == TokenKind::LParen {
+            self.advance();
+            while self.current().kind != TokenKind::RParen {
+                fields.push(self.parse_type()?);
+                if self.current().kind == TokenKind::Comma {
+                    self.advance();
+                } else {
+                    break;
+                }
+            }
+            self.expect(TokenKind::RParen)?;
+        }
+        self.finish_node();
+
+        Ok(Variant {
+            name: name.text,
+            fields,
+            span: Span::new(name.span.start, self.previous_end()),
+        })
+    }
+
+    /// `{ name: type, .. }` of a record type or a struct
+    fn parse_field_types(&mut self) -> Result<Vec<FieldType>> {
+        self.expect(TokenKind::LBrace)?;
+        let mut fields = Vec::new();
+
+        while self.current().kind != TokenKind::RBrace {
+            self.start_node(SyntaxKind::FieldDef);
+            let name = self.expect(TokenKind::Ident)?;
+            self.expect(TokenKind::Colon)?;
+            let ty = self.parse_type()?;
+            self.finish_node();
+            fields.push(FieldType {
+                name: name.text,
+                span: Span::new(name.span.start, ty.span().end),
+                ty,
+            });
+            if self.current().kind == TokenKind::Comma {
+                self.advance();
+            } else {
+                break;
+            }
+        }
+
+        self.expect(TokenKind::RBrace)?;
+        Ok(fields)
+    }
+
+    /// `fn name(params) -> type body` after `export` and `native`, which
+    /// start the item but not the function. A native function has no body.
+    fn parse_function(&mut self, is_export: bool, is_native: bool) -> Result<Function> {
         let start = self.current().span.start;
         self.expect(TokenKind::KwFn)?;
 
         let name_token = self.expect(TokenKind::Ident)?;
         let name = name_token.text.clone();
+        let type_params = self.parse_type_params()?;
+        let (params, return_type) = self.parse_signature()?;
+
+        let body = if is_native {
+            let end = self.previous_end();
+            Expr::Block {
+                stmts: Vec::new(),
+                span: Span::new(end, end),
+            }
+        } else {
+            self.parse_expr()?
+        };
+        let end = body.span().end;
+
+        Ok(Function {
+            is_export,
+            is_native,
+            name,
+            type_params,
+            params,
+            return_type,
+            body,
+            span: Span::new(start, end),
+        })
+    }
+
+    /// `<T, U>` after the name of a generic function or type; nothing when
+    /// there is no `<`
+    fn parse_type_params(&mut self) -> Result<Vec<TypeParam>> {
+        if self.current().kind != TokenKind::OpLt {
+            return Ok(Vec::new());
+        }
+        self.start_node(SyntaxKind::TypeParamList);
+        self.advance();
+        let mut params = Vec::new();
+
+        while self.current().kind != TokenKind::OpGt {
+            self.start_node(SyntaxKind::TypeParam);
+            let name = self.expect(TokenKind::Ident)?;
+            self.finish_node();
+            params.push(TypeParam {
+                name: name.text,
+                span: name.span,
+            });
+            if self.current().kind == TokenKind::Comma {
+                self.advance();
+            } else {
+                break;
+            }
+        }
+
+        self.expect(TokenKind::OpGt)?;
+        self.finish_node();
+        Ok(params)
+    }
 
+    /// `(params) -> type` of a function or lambda; the return type is
+    /// optional
+    fn parse_signature(&mut self) -> Result<(Vec<Param>, Option<Type>)> {
+        self.start_node(SyntaxKind::ParamList);
         self.expect(TokenKind::LParen)?;
         let mut params = Vec::new();
 
@@ -106,28 +639,37 @@ impl Parser {
         }
 
         self.expect(TokenKind::RParen)?;
+        self.finish_node();
 
         let return_type = if self.current().kind == TokenKind::OpArrow {
+            self.start_node(SyntaxKind::RetType);
             self.advance();
-            Some(self.parse_type()?)
+            let ty = self.parse_type()?;
+            self.finish_node();
+            Some(ty)
         } else {
             None
         };
+        Ok((params, return_type))
+    }
 
-        let body = self.parse_expr()?;
-        let end = body.span().end;
-
-        Ok(Function {
-            is_export,
-            name,
+    /// `fn(x: int) -> int { x * 2 }`
+    fn parse_lambda(&mut self) -> Result<Expr> {
+        self.start_node(SyntaxKind::LambdaExpr);
+        let start = self.expect(TokenKind::KwFn)?.span.start;
+        let (params, return_type) = self.parse_signature()?;
+        let body = self.parse_block()?;
+        self.finish_node();
+        Ok(Expr::Lambda {
             params,
             return_type,
-            body,
-            span: Span::new(start, end),
+            span: Span::new(start, body.span().end),
+            body: Box::new(body),
         })
     }
 
     fn parse_param(&mut self) -> Result<Param> {
+        self.start_node(SyntaxKind::Param);
         let start = self.current().span.start;
         let name_token = self.expect(TokenKind::Ident)?;
         let name = name_token.text.clone();
@@ -138,6 +680,7 @@ impl Parser {
         } else {
             None
         };
+        self.finish_node();
 
         let end = ty
             .as_ref()
@@ -155,29 +698,100 @@ impl Parser {
         let token = self.current().clone();
         match token.kind {
             TokenKind::TyInt => {
-                self.advance();
+                self.bump_node(SyntaxKind::TypeRef);
                 Ok(Type::Int(token.span))
             }
             TokenKind::TyString => {
-                self.advance();
+                self.bump_node(SyntaxKind::TypeRef);
                 Ok(Type::String(token.span))
             }
             TokenKind::TyBool => {
-                self.advance();
+                self.bump_node(SyntaxKind::TypeRef);
                 Ok(Type::Bool(token.span))
             }
             TokenKind::TyFloat => {
-                self.advance();
+                self.bump_node(SyntaxKind::TypeRef);
                 Ok(Type::Float(token.span))
             }
+            TokenKind::Ident if token.text == "List" && self.peek(1).kind == TokenKind::OpLt => {
+                self.start_node(SyntaxKind::TypeRef);
+                self.start_node(SyntaxKind::ListType);
+                self.advance();
+                self.advance();
+                let elem = self.parse_type()?;
+                self.expect(TokenKind::OpGt)?;
+                self.finish_node();
+                self.finish_node();
+                Ok(Type::List {
+                    elem: Box::new(elem),
+                    span: Span::new(token.span.start, self.previous_end()),
+                })
+            }
             TokenKind::Ident | TokenKind::TyProject => {
-                let name = token.text.clone();
+                self.start_node(SyntaxKind::TypeRef);
                 self.advance();
+                let args = if self.current().kind == TokenKind::OpLt {
+                    self.parse_type_args()?
+                } else {
+                    Vec::new()
+                };
+                self.finish_node();
                 Ok(Type::Named {
-                    name,
-                    span: token.span,
+                    name: token.text,
+                    args,
+                    span: Span::new(token.span.start, self.previous_end()),
+                })
+            }
+            TokenKind::LBrace => {
+                self.start_node(SyntaxKind::TypeRef);
+                self.start_node(SyntaxKind::RecordType);
+                let fields = self.parse_field_types()?;
+                self.finish_node();
+                self.finish_node();
+                Ok(Type::Record {
+                    fields,
+                    span: Span::new(token.span.start, self.previous_end()),
                 })
             }
+            // `(int, bool)` is a tuple, `(int, bool) -> int` a function
+            TokenKind::LParen => {
+                self.start_node(SyntaxKind::TypeRef);
+                let checkpoint = self.checkpoint();
+                self.advance();
+                let mut elems = Vec::new();
+                while self.current().kind != TokenKind::RParen {
+                    elems.push(self.parse_type()?);
+                    if self.current().kind == TokenKind::Comma {
+                        self.advance();
+                    } else {
+                        break;
+                    }
+                }
+                self.expect(TokenKind::RParen)?;
+                if self.current().kind == TokenKind::OpArrow {
+                    self.start_node_at(checkpoint, SyntaxKind::FunctionType);
+                    self.advance();
+                    let ret = self.parse_type()?;
+                    self.finish_node();
+                    self.finish_node();
+                    return Ok(Type::Function {
+                        params: elems,
+                        span: Span::new(token.span.start, ret.span().end),
+                        ret: Box::new(ret),
+                    });
+                }
+                self.start_node_at(checkpoint, SyntaxKind::TupleType);
+                self.finish_node();
+                self.finish_node();
+                let span = Span::new(token.span.start, self.previous_end());
+                if elems.len() < 2 {
+                    return Err(FluxError::Syntax {
+                        message: "A tuple type has at least two elements".to_string(),
+                        span: span.to_source_span(),
+                    });
+                }
+                Ok(Type::Tuple { elems, span })
+            }
             _ => Err(FluxError::Syntax {
                 message: format!("Expected type, found {:?}", token.kind),
                 span: token.span.to_source_span(),
@@ -185,12 +799,33 @@ impl Parser {
         }
     }
 
+    /// `<type, ..>` after a type name
+    fn parse_type_args(&mut self) -> Result<Vec<Type>> {
+        self.start_node(SyntaxKind::TypeArgList);
+        self.expect(TokenKind::OpLt)?;
+        let mut args = Vec::new();
+
+        while self.current().kind != TokenKind::OpGt {
+            args.push(self.parse_type()?);
+            if self.current().kind == TokenKind::Comma {
+                self.advance();
+            } else {
+                break;
+            }
+        }
+
+        self.expect(TokenKind::OpGt)?;
+        self.finish_node();
+        Ok(args)
+    }
+
     fn parse_expr(&mut self) -> Result<Expr> {
         self.parse_let()
     }
 
     fn parse_let(&mut self) -> Result<Expr> {
         if self.current().kind == TokenKind::KwLet {
+            self.start_node(SyntaxKind::LetExpr);
             let start = self.current().span.start;
             self.advance();
 
@@ -198,10 +833,33 @@ impl Parser {
             let name = name_token.text.clone();
 
             self.expect(TokenKind::OpEq)?;
-            let value = Box::new(self.parse_expr()?);
+            // A broken value still binds the name, so the body doesn't
+            // report every use of it as unknown
+            let marker = self.marker();
+            let value = match self.parse_expr() {
+                Ok(value) => value,
+                Err(error) => {
+                    let value = self.recover_statement(&marker, error);
+                    if self.at_block_end() {
+                        self.finish_node();
+                        let end = value.span().end;
+                        return Ok(Expr::Let {
+                            name,
+                            value: Box::new(value),
+                            body: Box::new(Expr::Error {
+                                span: Span::new(end, end),
+                            }),
+                            span: Span::new(start, end),
+                        });
+                    }
+                    value
+                }
+            };
+            let value = Box::new(value);
 
             let body = Box::new(self.parse_expr()?);
             let end = body.span().end;
+            self.finish_node();
 
             Ok(Expr::Let {
                 name,
@@ -210,37 +868,37 @@ impl Parser {
                 span: Span::new(start, end),
             })
         } else if self.current().kind == TokenKind::KwReturn {
+            self.start_node(SyntaxKind::ReturnExpr);
             let start = self.current().span.start;
             self.advance();
 
-            let value = Box::new(self.parse_additive()?);
+            let value = Box::new(self.parse_pipeline()?);
             let end = value.span().end;
+            self.finish_node();
 
             Ok(Expr::Return {
                 value,
                 span: Span::new(start, end),
             })
         } else {
-            self.parse_additive()
+            self.parse_pipeline()
         }
     }
 
-    fn parse_additive(&mut self) -> Result<Expr> {
-        let mut left = self.parse_multiplicative()?;
+    /// `|>` binds loosest of all binary operators
+    fn parse_pipeline(&mut self) -> Result<Expr> {
+        let checkpoint = self.checkpoint();
+        let mut left = self.parse_or()?;
 
-        while matches!(self.current().kind, TokenKind::OpPlus | TokenKind::OpMinus) {
+        while self.current().kind == TokenKind::OpPipe {
+            self.start_node_at(checkpoint, SyntaxKind::PipelineExpr);
             let start = left.span().start;
-            let op = match self.current().kind {
-                TokenKind::OpPlus => BinOp::Add,
-                TokenKind::OpMinus => BinOp::Sub,
-                _ => unreachable!(),
-            };
             self.advance();
-            let right = self.parse_multiplicative()?;
+            let right = self.parse_or()?;
             let end = right.span().end;
+            self.finish_node();
 
-            left = Expr::Binary {
-                op,
+            left = Expr::Pipeline {
                 left: Box::new(left),
                 right: Box::new(right),
                 span: Span::new(start, end),
@@ -250,19 +908,71 @@ impl Parser {
         Ok(left)
     }
 
+    fn parse_or(&mut self) -> Result<Expr> {
+        self.parse_binary_level(Self::parse_and, |kind| match kind {
+            TokenKind::OpOr => Some(BinOp::Or),
+            _ => None,
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<Expr> {
+        self.parse_binary_level(Self::parse_equality, |kind| match kind {
+            TokenKind::OpAnd => Some(BinOp::And),
+            _ => None,
+        })
+    }
+
+    fn parse_equality(&mut self) -> Result<Expr> {
+        self.parse_binary_level(Self::parse_comparison, |kind| match kind {
+            TokenKind::OpEqEq => Some(BinOp::Eq),
+            TokenKind::OpNotEq => Some(BinOp::NotEq),
+            _ => None,
+        })
+    }
+
+    fn parse_comparison(&mut self) -> Result<Expr> {
+        self.parse_binary_level(Self::parse_additive, |kind| match kind {
+            TokenKind::OpLt => Some(BinOp::Lt),
+            TokenKind::OpLtEq => Some(BinOp::LtEq),
+            TokenKind::OpGt => Some(BinOp::Gt),
+            TokenKind::OpGtEq => Some(BinOp::GtEq),
+            _ => None,
+        })
+    }
+
+    fn parse_additive(&mut self) -> Result<Expr> {
+        self.parse_binary_level(Self::parse_multiplicative, |kind| match kind {
+            TokenKind::OpPlus => Some(BinOp::Add),
+            TokenKind::OpMinus => Some(BinOp::Sub),
+            _ => None,
+        })
+    }
+
     fn parse_multiplicative(&mut self) -> Result<Expr> {
-        let mut left = self.parse_call()?;
+        self.parse_binary_level(Self::parse_unary, |kind| match kind {
+            TokenKind::OpStar => Some(BinOp::Mul),
+            TokenKind::OpSlash => Some(BinOp::Div),
+            _ => None,
+        })
+    }
+
+    /// Parse one left-associative precedence level: operands come from
+    /// `next`, operators are recognized by `op_for`
+    fn parse_binary_level(
+        &mut self,
+        next: fn(&mut Self) -> Result<Expr>,
+        op_for: fn(TokenKind) -> Option<BinOp>,
+    ) -> Result<Expr> {
+        let checkpoint = self.checkpoint();
+        let mut left = next(self)?;
 
-        while matches!(self.current().kind, TokenKind::OpStar | TokenKind::OpSlash) {
+        while let Some(op) = op_for(self.current().kind) {
+            self.start_node_at(checkpoint, SyntaxKind::BinExpr);
             let start = left.span().start;
-            let op = match self.current().kind {
-                TokenKind::OpStar => BinOp::Mul,
-                TokenKind::OpSlash => BinOp::Div,
-                _ => unreachable!(),
-            };
             self.advance();
-            let right = self.parse_call()?;
+            let right = next(self)?;
             let end = right.span().end;
+            self.finish_node();
 
             left = Expr::Binary {
                 op,
@@ -275,10 +985,72 @@ impl Parser {
         Ok(left)
     }
 
+    fn parse_unary(&mut self) -> Result<Expr> {
+        if self.current().kind == TokenKind::OpBang {
+            self.start_node(SyntaxKind::UnaryExpr);
+            let start = self.current().span.start;
+            self.advance();
+            let operand = self.parse_unary()?;
+            let end = operand.span().end;
+            self.finish_node();
+
+            Ok(Expr::Unary {
+                op: UnaryOp::Not,
+                operand: Box::new(operand),
+                span: Span::new(start, end),
+            })
+        } else {
+            self.parse_call()
+        }
+    }
+
     fn parse_call(&mut self) -> Result<Expr> {
+        let checkpoint = self.checkpoint();
         let mut expr = self.parse_primary()?;
 
-        while self.current().kind == TokenKind::LParen {
+        loop {
+            if self.current().kind == TokenKind::Dot {
+                self.start_node_at(checkpoint, SyntaxKind::FieldExpr);
+                self.advance();
+                let field = self.expect(TokenKind::Ident)?;
+                self.finish_node();
+                expr = Expr::Field {
+                    span: Span::new(expr.span().start, field.span.end),
+                    base: Box::new(expr),
+                    field: field.text,
+                    field_span: field.span,
+                };
+                continue;
+            }
+            if self.current().kind == TokenKind::OpQuestion {
+                self.start_node_at(checkpoint, SyntaxKind::TryExpr);
+                let end_token = self.advance();
+                self.finish_node();
+                expr = Expr::Try {
+                    span: Span::new(expr.span().start, end_token.span.end),
+                    operand: Box::new(expr),
+                };
+                continue;
+            }
+            if self.current().kind == TokenKind::LBracket {
+                self.start_node_at(checkpoint, SyntaxKind::IndexExpr);
+                self.advance();
+                let index = self.parse_expr()?;
+                let end_token = self.expect(TokenKind::RBracket)?;
+                self.finish_node();
+                expr = Expr::Index {
+                    span: Span::new(expr.span().start, end_token.span.end),
+                    base: Box::new(expr),
+                    index: Box::new(index),
+                };
+                continue;
+            }
+            if self.current().kind != TokenKind::LParen {
+                break;
+            }
+
+            self.start_node_at(checkpoint, SyntaxKind::CallExpr);
+            self.start_node(SyntaxKind::ArgList);
             let start = expr.span().start;
             self.advance();
 
@@ -293,6 +1065,8 @@ impl Parser {
             }
 
             let end_token = self.expect(TokenKind::RParen)?;
+            self.finish_node();
+            self.finish_node();
             expr = Expr::Call {
                 func: Box::new(expr),
                 args,
@@ -307,7 +1081,7 @@ impl Parser {
         let token = self.current().clone();
         match token.kind {
             TokenKind::LitInt => {
-                self.advance();
+                self.bump_node(SyntaxKind::Literal);
                 let value = token.text.parse().unwrap_or(0);
                 Ok(Expr::Int {
                     value,
@@ -315,7 +1089,7 @@ impl Parser {
                 })
             }
             TokenKind::LitFloat => {
-                self.advance();
+                self.bump_node(SyntaxKind::Literal);
                 let value = token.text.parse().unwrap_or(0.0);
                 Ok(Expr::Float {
                     value,
@@ -323,69 +1097,390 @@ impl Parser {
                 })
             }
             TokenKind::LitTrue => {
-                self.advance();
+                self.bump_node(SyntaxKind::Literal);
                 Ok(Expr::Bool {
                     value: true,
                     span: token.span,
                 })
             }
             TokenKind::LitFalse => {
-                self.advance();
+                self.bump_node(SyntaxKind::Literal);
                 Ok(Expr::Bool {
                     value: false,
                     span: token.span,
                 })
             }
             TokenKind::LitString => {
-                self.advance();
+                self.bump_node(SyntaxKind::Literal);
                 let value = token.text.trim_matches('"').to_string();
                 Ok(Expr::String {
                     value,
                     span: token.span,
                 })
             }
+            TokenKind::LitTemporal => {
+                let value =
+                    Temporal::parse_point(&token.text).map_err(|message| FluxError::Syntax {
+                        message,
+                        span: token.span.to_source_span(),
+                    })?;
+                self.bump_node(SyntaxKind::Literal);
+                Ok(Expr::Temporal {
+                    value,
+                    span: token.span,
+                })
+            }
+            TokenKind::LitDuration => {
+                // `5d 3h`: consecutive parts make one duration
+                self.start_node(SyntaxKind::Literal);
+                let mut parts = vec![self.advance()];
+                while self.current().kind == TokenKind::LitDuration {
+                    parts.push(self.advance());
+                }
+                self.finish_node();
+                let span = Span::new(token.span.start, self.previous_end());
+                let value = Temporal::parse_duration(parts.iter().map(|part| part.text.as_str()))
+                    .map_err(|message| FluxError::Syntax {
+                    message,
+                    span: span.to_source_span(),
+                })?;
+                Ok(Expr::Temporal { value, span })
+            }
+            TokenKind::LitColumn => {
+                self.bump_node(SyntaxKind::ColumnRef);
+                let name = column_name(&token.text);
+                Ok(Expr::Column {
+                    name,
+                    span: token.span,
+                })
+            }
+            TokenKind::Ident if self.at_record_literal(1) => self.parse_record(),
+            TokenKind::LBrace if self.at_record_literal(0) => self.parse_record(),
             TokenKind::Ident => {
-                self.advance();
+                self.bump_node(SyntaxKind::NameRef);
                 Ok(Expr::Var {
                     name: token.text.clone(),
                     span: token.span,
                 })
             }
-            TokenKind::LParen => {
+            TokenKind::LParen => self.parse_paren_or_tuple(),
+            TokenKind::LBracket => self.parse_list(),
+            TokenKind::LBrace => self.parse_block(),
+            TokenKind::KwIf => self.parse_if(),
+            TokenKind::KwMatch => self.parse_match(),
+            TokenKind::KwFn => self.parse_lambda(),
+            _ => Err(FluxError::Syntax {
+                message: format!("Unexpected token: {:?}", token.kind),
+                span: token.span.to_source_span(),
+            }),
+        }
+    }
+
+    /// `(expr)`, or a tuple `(a, b)` once a comma follows the first element
+    fn parse_paren_or_tuple(&mut self) -> Result<Expr> {
+        let checkpoint = self.checkpoint();
+        let start = self.expect(TokenKind::LParen)?.span.start;
+        let first = self.parse_expr()?;
+        if self.current().kind != TokenKind::Comma {
+            self.start_node_at(checkpoint, SyntaxKind::ParenExpr);
+            self.expect(TokenKind::RParen)?;
+            self.finish_node();
+            return Ok(first);
+        }
+
+        self.start_node_at(checkpoint, SyntaxKind::TupleExpr);
+        let mut elems = vec![first];
+        while self.current().kind == TokenKind::Comma {
+            self.advance();
+            if self.current().kind == TokenKind::RParen {
+                break;
+            }
+            elems.push(self.parse_expr()?);
+        }
+        let end = self.expect(TokenKind::RParen)?.span.end;
+        self.finish_node();
+        let span = Span::new(start, end);
+        if elems.len() < 2 {
+            return Err(FluxError::Syntax {
+                message:
+                    "A tuple has at least two elements; write `(x)` for a parenthesized expression"
+                        .to_string(),
+                span: span.to_source_span(),
+            });
+        }
+        Ok(Expr::Tuple { elems, span })
+    }
+
+    /// `[1, 2, 3]`
+    fn parse_list(&mut self) -> Result<Expr> {
+        self.start_node(SyntaxKind::ListExpr);
+        let start = self.expect(TokenKind::LBracket)?.span.start;
+        let mut elems = Vec::new();
+        while self.current().kind != TokenKind::RBracket {
+            elems.push(self.parse_expr()?);
+            if self.current().kind == TokenKind::Comma {
                 self.advance();
-                let expr = self.parse_expr()?;
-                self.expect(TokenKind::RParen)?;
-                Ok(expr)
+            } else {
+                break;
             }
-            TokenKind::LBrace => {
-                let start = token.span.start;
+        }
+        let end = self.expect(TokenKind::RBracket)?.span.end;
+        self.finish_node();
+        Ok(Expr::List {
+            elems,
+            span: Span::new(start, end),
+        })
+    }
+
+    /// Whether the `{` `offset` tokens ahead opens a record literal rather
+    /// than a block: a record starts with `name:`, which no statement does
+    fn at_record_literal(&self, offset: usize) -> bool {
+        self.peek(offset).kind == TokenKind::LBrace
+            && self.peek(offset + 1).kind == TokenKind::Ident
+            && self.peek(offset + 2).kind == TokenKind::Colon
+    }
+
+    /// `{ x: 1.0, y: 2.0 }` or `User { name: "a" }`
+    fn parse_record(&mut self) -> Result<Expr> {
+        self.start_node(SyntaxKind::RecordExpr);
+        let start = self.current().span.start;
+        let name = if self.current().kind == TokenKind::Ident {
+            Some(self.advance().text)
+        } else {
+            None
+        };
+        self.expect(TokenKind::LBrace)?;
+        let mut fields = Vec::new();
+
+        while self.current().kind != TokenKind::RBrace {
+            self.start_node(SyntaxKind::RecordField);
+            let field = self.expect(TokenKind::Ident)?;
+            self.expect(TokenKind::Colon)?;
+            let value = self.parse_expr()?;
+            self.finish_node();
+            fields.push(FieldInit {
+                name: field.text,
+                span: Span::new(field.span.start, value.span().end),
+                value,
+            });
+            if self.current().kind == TokenKind::Comma {
+                self.advance();
+            } else {
+                break;
+            }
+        }
+
+        let end = self.expect(TokenKind::RBrace)?.span.end;
+        self.finish_node();
+        Ok(Expr::Record {
+            name,
+            fields,
+            span: Span::new(start, end),
+        })
+    }
+
+    fn parse_block(&mut self) -> Result<Expr> {
+        self.start_node(SyntaxKind::BlockExpr);
+        let start = self.expect(TokenKind::LBrace)?.span.start;
+        let mut stmts = Vec::new();
+
+        while !self.at_block_end() {
+            let marker = self.marker();
+            match self.parse_expr() {
+                Ok(stmt) => stmts.push(stmt),
+                Err(error) => {
+                    let stmt = self.recover_statement(&marker, error);
+                    stmts.push(stmt);
+                    continue;
+                }
+            }
+            if self.current().kind == TokenKind::Semi {
                 self.advance();
-                let mut stmts = Vec::new();
+            }
+        }
+
+        // An unclosed block ends where the next item starts
+        let end = match self.expect(TokenKind::RBrace) {
+            Ok(end_token) => end_token.span.end,
+            Err(error) => {
+                self.errors.push(error);
+                self.previous_end().max(start)
+            }
+        };
+        self.finish_node();
+        Ok(Expr::Block {
+            stmts,
+            span: Span::new(start, end),
+        })
+    }
+
+    /// End of the last consumed token
+    fn previous_end(&self) -> usize {
+        self.tokens[..self.pos]
+            .iter()
+            .rev()
+            .find(|token| !token.kind.is_trivia())
+            .map_or(0, |token| token.span.end)
+    }
+
+    /// Parse `if cond { .. } else { .. }`; `else if` chains nest in the else branch
+    fn parse_if(&mut self) -> Result<Expr> {
+        self.start_node(SyntaxKind::IfExpr);
+        let start = self.expect(TokenKind::KwIf)?.span.start;
+        let cond = self.parse_pipeline()?;
+        let then_branch = self.parse_block()?;
+
+        self.expect(TokenKind::KwElse)?;
+        let else_branch = if self.current().kind == TokenKind::KwIf {
+            self.parse_if()?
+        } else {
+            self.parse_block()?
+        };
+        let end = else_branch.span().end;
+        self.finish_node();
+
+        Ok(Expr::If {
+            cond: Box::new(cond),
+            then_branch: Box::new(then_branch),
+            else_branch: Box::new(else_branch),
+            span: Span::new(start, end),
+        })
+    }
+
+    /// Parse `match scrutinee { pattern => body, .. }`; the commas between
+    /// arms are optional
+    fn parse_match(&mut self) -> Result<Expr> {
+        self.start_node(SyntaxKind::MatchExpr);
+        let start = self.expect(TokenKind::KwMatch)?.span.start;
+        let scrutinee = self.parse_pipeline()?;
+        self.expect(TokenKind::LBrace)?;
+        let mut arms = Vec::new();
+
+        while self.current().kind != TokenKind::RBrace {
+            self.start_node(SyntaxKind::MatchArm);
+            let pattern = self.parse_pattern()?;
+            self.expect(TokenKind::OpFatArrow)?;
+            let body = self.parse_expr()?;
+            self.finish_node();
+            arms.push(MatchArm {
+                span: Span::new(pattern.span().start, body.span().end),
+                pattern,
+                body,
+            });
+            if self.current().kind == TokenKind::Comma {
+                self.advance();
+            }
+        }
+
+        let end = self.expect(TokenKind::RBrace)?.span.end;
+        self.finish_node();
+        Ok(Expr::Match {
+            scrutinee: Box::new(scrutinee),
+            arms,
+            span: Span::new(start, end),
+        })
+    }
 
-                while self.current().kind != TokenKind::RBrace {
-                    stmts.push(self.parse_expr()?);
-                    if self.current().kind == TokenKind::Semi {
+    fn parse_pattern(&mut self) -> Result<Pattern> {
+        let token = self.current().clone();
+        let span = token.span;
+        match token.kind {
+            TokenKind::Ident if token.text == "_" => {
+                self.bump_node(SyntaxKind::WildcardPat);
+                Ok(Pattern::Wildcard { span })
+            }
+            TokenKind::Ident if self.peek(1).kind == TokenKind::LParen => {
+                self.start_node(SyntaxKind::VariantPat);
+                self.advance();
+                self.advance();
+                let mut args = Vec::new();
+                while self.current().kind != TokenKind::RParen {
+                    args.push(self.parse_pattern()?);
+                    if self.current().kind == TokenKind::Comma {
                         self.advance();
+                    } else {
+                        break;
                     }
                 }
-
-                let end_token = self.expect(TokenKind::RBrace)?;
-                Ok(Expr::Block {
-                    stmts,
-                    span: Span::new(start, end_token.span.end),
+                let end = self.expect(TokenKind::RParen)?.span.end;
+                self.finish_node();
+                Ok(Pattern::Variant {
+                    name: token.text,
+                    args,
+                    span: Span::new(span.start, end),
+                })
+            }
+            TokenKind::Ident => {
+                self.bump_node(SyntaxKind::NamePat);
+                Ok(Pattern::Name {
+                    name: token.text,
+                    span,
+                })
+            }
+            TokenKind::LitInt => {
+                self.bump_node(SyntaxKind::LiteralPat);
+                let value = token.text.parse().unwrap_or(0);
+                Ok(Pattern::Int { value, span })
+            }
+            TokenKind::LitTrue | TokenKind::LitFalse => {
+                self.bump_node(SyntaxKind::LiteralPat);
+                Ok(Pattern::Bool {
+                    value: token.kind == TokenKind::LitTrue,
+                    span,
                 })
             }
+            TokenKind::LitString => {
+                self.bump_node(SyntaxKind::LiteralPat);
+                let value = token.text.trim_matches('"').to_string();
+                Ok(Pattern::String { value, span })
+            }
             _ => Err(FluxError::Syntax {
-                message: format!("Unexpected token: {:?}", token.kind),
-                span: token.span.to_source_span(),
+                message: format!("Expected pattern, found {:?}", token.kind),
+                span: span.to_source_span(),
             }),
         }
     }
 }
 
+/// Parse `input`, stopping at the first syntax error
 pub fn parse(input: &str) -> Result<SourceFile> {
-    let mut parser = Parser::new(input);
-    parser.parse()
+    parse_with_recovery(input).into_result()
+}
+
+/// Parse `input` into a partial AST plus every syntax error found
+pub fn parse_with_recovery(input: &str) -> Parse {
+    Parser::new(input).parse()
+}
+
+/// The name a `#name` or `#"quoted name"` column reference refers to: a
+/// quoted name loses its quotes and has its escapes resolved
+fn column_name(text: &str) -> String {
+    let name = &text[1..];
+    let Some(quoted) = name
+        .strip_prefix('"')
+        .and_then(|name| name.strip_suffix('"'))
+    else {
+        return name.to_string();
+    };
+    let mut unescaped = String::with_capacity(quoted.len());
+    let mut chars = quoted.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        // The lexer only lets a backslash through before one of these
+        match chars.next() {
+            Some('b') => unescaped.push('\u{8}'),
+            Some('f') => unescaped.push('\u{c}'),
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some('t') => unescaped.push('\t'),
+            Some(escaped) => unescaped.push(escaped),
+            None => {}
+        }
+    }
+    unescaped
 }
 
 #[cfg(test)]
@@ -415,8 +1510,97 @@ mod tests {
         assert!(result.is_ok());
         let ast = result.unwrap();
         assert_eq!(ast.items.len(), 1);
-        let Item::Function(func) = &ast.items[0];
+        let func = ast.functions().next().unwrap();
         assert!(func.is_export);
         assert_eq!(func.name, "plan");
     }
+
+    #[test]
+    fn test_quoted_column_names_are_unescaped() {
+        let input = r#"fn pick() { pick(#id, #"order total", #"say \"hi\"", #"a\\b\tc", #"") }"#;
+        let ast = parse(input).unwrap();
+        let func = ast.functions().next().unwrap();
+        let Expr::Block { stmts, .. } = &func.body else {
+            panic!("Expected block body");
+        };
+        let Expr::Call { args, .. } = &stmts[0] else {
+            panic!("Expected call");
+        };
+        let names: Vec<&str> = args
+            .iter()
+            .map(|arg| match arg {
+                Expr::Column { name, .. } => name.as_str(),
+                other => panic!("Expected column, got {:?}", other),
+            })
+            .collect();
+        assert_eq!(names, ["id", "order total", "say \"hi\"", "a\\b\tc", ""]);
+    }
+
+    #[test]
+    fn test_plan_declares_project() {
+        let input = "enum Project { Done }\nexport fn plan(ctx: Project) -> Project { ctx }";
+        let parse = parse_with_recovery(input);
+        assert!(parse.errors.is_empty(), "{:?}", parse.errors);
+        let def = parse.ast.enums().next().unwrap();
+        assert_eq!(def.name, "Project");
+        let name = parse.tree().enums().next().and_then(|def| def.name());
+        assert_eq!(name.unwrap().text(), "Project");
+    }
+
+    #[test]
+    fn test_pipeline_desugars_to_call() {
+        let ast = parse("fn f(x: int) -> int { x |> add(1) }").unwrap();
+        let func = ast.functions().next().unwrap();
+        let Expr::Block { stmts, .. } = &func.body else {
+            panic!("Expected block body");
+        };
+        let Expr::Pipeline { left, right, span } = &stmts[0] else {
+            panic!("Expected pipeline");
+        };
+
+        let Expr::Call {
+            func: callee,
+            args,
+            span: call_span,
+        } = Expr::pipeline_to_call(left, right, *span)
+        else {
+            panic!("Expected call");
+        };
+        assert!(matches!(*callee, Expr::Var { ref name, .. } if name == "add"));
+        assert_eq!(args.len(), 2);
+        assert!(matches!(args[0], Expr::Var { ref name, .. } if name == "x"));
+        assert!(matches!(args[1], Expr::Int { value: 1, .. }));
+        assert_eq!(call_span, *span);
+    }
+
+    #[test]
+    fn test_recovery_reports_every_error() {
+        let input = "fn f() -> int { 1 + ; ) ; 2 } fn g( { } fn h() -> int { 3 }";
+        let result = parse_with_recovery(input);
+
+        let names: Vec<_> = result
+            .ast
+            .functions()
+            .map(|func| func.name.as_str())
+            .collect();
+        assert_eq!(names, ["f", "h"]);
+        assert_eq!(result.errors.len(), 3);
+
+        let f = result.ast.functions().next().unwrap();
+        let Expr::Block { stmts, .. } = &f.body else {
+            panic!("Expected block body");
+        };
+        assert!(matches!(
+            stmts[..],
+            [
+                Expr::Error { .. },
+                Expr::Error { .. },
+                Expr::Int { value: 2, .. }
+            ]
+        ));
+
+        // `parse` stops at the first of them
+        assert!(parse_with_recovery(input).into_result().is_err());
+        assert!(parse(input).is_err());
+    }
 }
@@ -0,0 +1,606 @@
+//! Values of temporal literals: `@2026-10-16`, `@12:30:00`,
+//! `@2026-10-16T12:00:00[Europe/Paris]`, `@2026-10-16T12:00:00Z` and
+//! `5d 3h`. A date with a time of day must say where it is: a zone in
+//! brackets makes a `DateTime`, `Z` a UTC `Timestamp`. Naive date-times are
+//! rejected.
+
+use std::fmt;
+
+/// Calendar date in the proleptic Gregorian calendar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Date {
+    pub year: i32,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl Date {
+    /// Days since 1970-01-01, negative before it
+    pub fn days_since_epoch(&self) -> i64 {
+        // Howard Hinnant's `days_from_civil`
+        let year = i64::from(self.year) - i64::from(self.month <= 2);
+        let era = year.div_euclid(400);
+        let year_of_era = year.rem_euclid(400);
+        let month = i64::from(self.month);
+        let day_of_year =
+            (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(self.day) - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+}
+
+/// Time of day
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Time {
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+    pub nanos: u32,
+}
+
+impl Time {
+    pub fn nanos_since_midnight(&self) -> i64 {
+        (i64::from(self.hour) * 3600 + i64::from(self.minute) * 60 + i64::from(self.second))
+            * NANOS_PER_SECOND
+            + i64::from(self.nanos)
+    }
+}
+
+pub const NANOS_PER_SECOND: i64 = 1_000_000_000;
+pub const NANOS_PER_DAY: i64 = 86_400 * NANOS_PER_SECOND;
+
+const MINUTE: i64 = 60;
+const HOUR: i64 = 60 * MINUTE;
+
+/// IANA zones a DateTime may use besides `UTC` and fixed offsets. Flux ships
+/// no time-zone database, so names are checked against this list, and every
+/// year follows the rules each zone keeps today.
+pub const ZONES: &[Zone] = &[
+    Zone::new("Africa/Cairo", 2 * HOUR, Some(&EGYPT)),
+    Zone::new("Africa/Johannesburg", 2 * HOUR, None),
+    Zone::new("Africa/Lagos", HOUR, None),
+    Zone::new("Africa/Nairobi", 3 * HOUR, None),
+    Zone::new("America/Anchorage", -9 * HOUR, Some(&NORTH_AMERICA)),
+    Zone::new("America/Argentina/Buenos_Aires", -3 * HOUR, None),
+    Zone::new("America/Bogota", -5 * HOUR, None),
+    Zone::new("America/Chicago", -6 * HOUR, Some(&NORTH_AMERICA)),
+    Zone::new("America/Denver", -7 * HOUR, Some(&NORTH_AMERICA)),
+    Zone::new("America/Halifax", -4 * HOUR, Some(&NORTH_AMERICA)),
+    Zone::new("America/Los_Angeles", -8 * HOUR, Some(&NORTH_AMERICA)),
+    Zone::new("America/Mexico_City", -6 * HOUR, None),
+    Zone::new("America/New_York", -5 * HOUR, Some(&NORTH_AMERICA)),
+    Zone::new("America/Phoenix", -7 * HOUR, None),
+    Zone::new("America/Santiago", -4 * HOUR, Some(&CHILE)),
+    Zone::new("America/Sao_Paulo", -3 * HOUR, None),
+    Zone::new(
+        "America/St_Johns",
+        -(3 * HOUR + 30 * MINUTE),
+        Some(&NORTH_AMERICA),
+    ),
+    Zone::new("America/Toronto", -5 * HOUR, Some(&NORTH_AMERICA)),
+    Zone::new("America/Vancouver", -8 * HOUR, Some(&NORTH_AMERICA)),
+    Zone::new("Asia/Bangkok", 7 * HOUR, None),
+    Zone::new("Asia/Dhaka", 6 * HOUR, None),
+    Zone::new("Asia/Dubai", 4 * HOUR, None),
+    Zone::new("Asia/Hong_Kong", 8 * HOUR, None),
+    Zone::new("Asia/Jakarta", 7 * HOUR, None),
+    Zone::new("Asia/Jerusalem", 2 * HOUR, Some(&ISRAEL)),
+    Zone::new("Asia/Karachi", 5 * HOUR, None),
+    Zone::new("Asia/Kathmandu", 5 * HOUR + 45 * MINUTE, None),
+    Zone::new("Asia/Kolkata", 5 * HOUR + 30 * MINUTE, None),
+    Zone::new("Asia/Manila", 8 * HOUR, None),
+    Zone::new("Asia/Seoul", 9 * HOUR, None),
+    Zone::new("Asia/Shanghai", 8 * HOUR, None),
+    Zone::new("Asia/Singapore", 8 * HOUR, None),
+    Zone::new("Asia/Taipei", 8 * HOUR, None),
+    Zone::new("Asia/Tehran", 3 * HOUR + 30 * MINUTE, None),
+    Zone::new("Asia/Tokyo", 9 * HOUR, None),
+    Zone::new("Atlantic/Reykjavik", 0, None),
+    Zone::new(
+        "Australia/Adelaide",
+        9 * HOUR + 30 * MINUTE,
+        Some(&AUSTRALIA),
+    ),
+    Zone::new("Australia/Brisbane", 10 * HOUR, None),
+    Zone::new("Australia/Melbourne", 10 * HOUR, Some(&AUSTRALIA)),
+    Zone::new("Australia/Perth", 8 * HOUR, None),
+    Zone::new("Australia/Sydney", 10 * HOUR, Some(&AUSTRALIA)),
+    Zone::new("Europe/Amsterdam", HOUR, Some(&EU)),
+    Zone::new("Europe/Athens", 2 * HOUR, Some(&EU)),
+    Zone::new("Europe/Berlin", HOUR, Some(&EU)),
+    Zone::new("Europe/Brussels", HOUR, Some(&EU)),
+    Zone::new("Europe/Dublin", 0, Some(&EU)),
+    Zone::new("Europe/Helsinki", 2 * HOUR, Some(&EU)),
+    Zone::new("Europe/Istanbul", 3 * HOUR, None),
+    Zone::new("Europe/Lisbon", 0, Some(&EU)),
+    Zone::new("Europe/London", 0, Some(&EU)),
+    Zone::new("Europe/Madrid", HOUR, Some(&EU)),
+    Zone::new("Europe/Moscow", 3 * HOUR, None),
+    Zone::new("Europe/Oslo", HOUR, Some(&EU)),
+    Zone::new("Europe/Paris", HOUR, Some(&EU)),
+    Zone::new("Europe/Prague", HOUR, Some(&EU)),
+    Zone::new("Europe/Rome", HOUR, Some(&EU)),
+    Zone::new("Europe/Stockholm", HOUR, Some(&EU)),
+    Zone::new("Europe/Vienna", HOUR, Some(&EU)),
+    Zone::new("Europe/Warsaw", HOUR, Some(&EU)),
+    Zone::new("Europe/Zurich", HOUR, Some(&EU)),
+    Zone::new("Pacific/Auckland", 12 * HOUR, Some(&NEW_ZEALAND)),
+    Zone::new("Pacific/Honolulu", -10 * HOUR, None),
+];
+
+/// A zone of [`ZONES`]
+#[derive(Debug)]
+pub struct Zone {
+    pub name: &'static str,
+    /// Offset from UTC in seconds outside daylight saving time
+    offset: i64,
+    dst: Option<&'static Dst>,
+}
+
+/// Daylight saving time: clocks go an hour ahead at `start` and back at
+/// `end`, which comes first in the year in the southern hemisphere
+#[derive(Debug)]
+struct Dst {
+    start: Switch,
+    end: Switch,
+}
+
+/// When clocks change in a year: on the first `weekday` (0 is Sunday) on
+/// or after `day` of `month`, at `at` seconds past that day's midnight in
+/// UTC or in standard time
+#[derive(Debug)]
+struct Switch {
+    month: u8,
+    day: SwitchDay,
+    weekday: i64,
+    at: i64,
+    utc: bool,
+}
+
+#[derive(Debug)]
+enum SwitchDay {
+    OnOrAfter(u8),
+    /// In the last seven days of the month
+    Last,
+}
+
+const SUNDAY: i64 = 0;
+const THURSDAY: i64 = 4;
+const FRIDAY: i64 = 5;
+
+const fn switch(month: u8, day: SwitchDay, weekday: i64, at: i64, utc: bool) -> Switch {
+    Switch {
+        month,
+        day,
+        weekday,
+        at,
+        utc,
+    }
+}
+
+/// Last Sunday of March to last Sunday of October, at 01:00 UTC
+const EU: Dst = Dst {
+    start: switch(3, SwitchDay::Last, SUNDAY, HOUR, true),
+    end: switch(10, SwitchDay::Last, SUNDAY, HOUR, true),
+};
+/// Second Sunday of March to first Sunday of November, at 02:00 local time
+const NORTH_AMERICA: Dst = Dst {
+    start: switch(3, SwitchDay::OnOrAfter(8), SUNDAY, 2 * HOUR, false),
+    end: switch(11, SwitchDay::OnOrAfter(1), SUNDAY, HOUR, false),
+};
+const CHILE: Dst = Dst {
+    start: switch(9, SwitchDay::OnOrAfter(2), SUNDAY, 4 * HOUR, true),
+    end: switch(4, SwitchDay::OnOrAfter(2), SUNDAY, 3 * HOUR, true),
+};
+/// Last Friday of April to the end of the last Thursday of October
+const EGYPT: Dst = Dst {
+    start: switch(4, SwitchDay::Last, FRIDAY, 0, false),
+    end: switch(10, SwitchDay::Last, THURSDAY, 23 * HOUR, false),
+};
+/// Friday before the last Sunday of March to the last Sunday of October
+const ISRAEL: Dst = Dst {
+    start: switch(3, SwitchDay::OnOrAfter(23), FRIDAY, 2 * HOUR, false),
+    end: switch(10, SwitchDay::Last, SUNDAY, HOUR, false),
+};
+/// First Sunday of October to first Sunday of April, at 02:00 standard time
+const AUSTRALIA: Dst = Dst {
+    start: switch(10, SwitchDay::OnOrAfter(1), SUNDAY, 2 * HOUR, false),
+    end: switch(4, SwitchDay::OnOrAfter(1), SUNDAY, 2 * HOUR, false),
+};
+/// Last Sunday of September to first Sunday of April, at 02:00 standard time
+const NEW_ZEALAND: Dst = Dst {
+    start: switch(9, SwitchDay::Last, SUNDAY, 2 * HOUR, false),
+    end: switch(4, SwitchDay::OnOrAfter(1), SUNDAY, 2 * HOUR, false),
+};
+
+impl Zone {
+    const fn new(name: &'static str, offset: i64, dst: Option<&'static Dst>) -> Self {
+        Self { name, offset, dst }
+    }
+
+    /// Offset from UTC in seconds of the local time `local`, in seconds
+    /// since 1970-01-01T00:00 on the zone's clocks, in `year`. Of a time
+    /// read twice as clocks go back, the earlier is taken; a time skipped as
+    /// they go ahead is read in standard time, which moves it forward.
+    fn offset_at(&self, year: i32, local: i64) -> i64 {
+        let Some(dst) = self.dst else {
+            return self.offset;
+        };
+        let daylight = self.offset + HOUR;
+        let instant = local - daylight;
+        let start = dst.start.instant(year, self.offset);
+        let end = dst.end.instant(year, self.offset);
+        let in_effect = if start < end {
+            start <= instant && instant < end
+        } else {
+            instant >= start || instant < end
+        };
+        if in_effect {
+            daylight
+        } else {
+            self.offset
+        }
+    }
+}
+
+impl Switch {
+    /// Seconds since the epoch at which clocks change in `year`, in a zone
+    /// `offset` seconds ahead of UTC in standard time
+    fn instant(&self, year: i32, offset: i64) -> i64 {
+        let from = match self.day {
+            SwitchDay::OnOrAfter(day) => day,
+            SwitchDay::Last => days_in_month(year, self.month) - 6,
+        };
+        let from = Date {
+            year,
+            month: self.month,
+            day: from,
+        }
+        .days_since_epoch();
+        // 1970-01-01 was a Thursday
+        let day = from + (self.weekday - (from + THURSDAY)).rem_euclid(7);
+        day * 86_400 + self.at - if self.utc { 0 } else { offset }
+    }
+}
+
+/// Offset from UTC in seconds of the local `date` and `time` in `zone`:
+/// `UTC`, a fixed offset or one of [`ZONES`]
+pub fn utc_offset(zone: &str, date: &Date, time: &Time) -> Option<i64> {
+    if let Some(offset) = fixed_offset(zone) {
+        return Some(offset);
+    }
+    let zone = ZONES.iter().find(|known| known.name == zone)?;
+    let local = date.days_since_epoch() * 86_400 + time.nanos_since_midnight() / NANOS_PER_SECOND;
+    Some(zone.offset_at(date.year, local))
+}
+
+/// Offset from UTC in seconds of `UTC` or a `+HH:MM` / `-HH:MM` zone between
+/// -12:00 and +14:00
+pub fn fixed_offset(zone: &str) -> Option<i64> {
+    if zone == "UTC" {
+        return Some(0);
+    }
+    let (sign, offset) = match zone.strip_prefix('+') {
+        Some(offset) => (1, offset),
+        None => (-1, zone.strip_prefix('-')?),
+    };
+    let (hours, minutes) = offset.split_once(':')?;
+    if hours.len() != 2 || minutes.len() != 2 {
+        return None;
+    }
+    let (hours, minutes): (i64, i64) = (hours.parse().ok()?, minutes.parse().ok()?);
+    let offset = sign * (hours * 3600 + minutes * 60);
+    (minutes < 60 && (-12 * 3600..=14 * 3600).contains(&offset)).then_some(offset)
+}
+
+/// Whether a DateTime may be in `zone`: `UTC`, a fixed offset or one of
+/// [`ZONES`]
+pub fn is_known_zone(zone: &str) -> bool {
+    fixed_offset(zone).is_some() || ZONES.iter().any(|known| known.name == zone)
+}
+
+/// A duration. Months and days are calendar units whose length depends on
+/// where they are applied, so they are kept apart from the exact `nanos`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Duration {
+    pub months: i64,
+    pub days: i64,
+    pub nanos: i64,
+}
+
+impl Duration {
+    /// Add one `<count><unit>` part of a duration literal, e.g. `5d`
+    fn add_part(&mut self, part: &str) -> Result<(), String> {
+        let digits = part
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(part.len());
+        let (count, unit) = part.split_at(digits);
+        let count: i64 = count
+            .parse()
+            .map_err(|_| format!("Duration `{}` is too large", part))?;
+        let (field, scale) = match unit {
+            "y" => (&mut self.months, 12),
+            "mo" => (&mut self.months, 1),
+            "w" => (&mut self.days, 7),
+            "d" => (&mut self.days, 1),
+            "h" => (&mut self.nanos, 3600 * NANOS_PER_SECOND),
+            "m" => (&mut self.nanos, 60 * NANOS_PER_SECOND),
+            "s" => (&mut self.nanos, NANOS_PER_SECOND),
+            "ms" => (&mut self.nanos, 1_000_000),
+            "us" => (&mut self.nanos, 1_000),
+            "ns" => (&mut self.nanos, 1),
+            _ => return Err(format!("Unknown duration unit `{}`", unit)),
+        };
+        *field = count
+            .checked_mul(scale)
+            .and_then(|value| field.checked_add(value))
+            .ok_or_else(|| format!("Duration `{}` is too large", part))?;
+        Ok(())
+    }
+}
+
+/// Kind of a temporal value, one per temporal type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TemporalKind {
+    Date,
+    Time,
+    DateTime,
+    Timestamp,
+    Duration,
+}
+
+impl fmt::Display for TemporalKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// Value of a temporal literal
+#[derive(Debug, Clone, PartialEq)]
+pub enum Temporal {
+    Date(Date),
+    Time(Time),
+    /// Local date and time in a zone, e.g. `Europe/Paris` or `+02:00`
+    DateTime {
+        date: Date,
+        time: Time,
+        zone: String,
+    },
+    /// Absolute UTC time
+    Timestamp {
+        date: Date,
+        time: Time,
+    },
+    Duration(Duration),
+}
+
+impl Temporal {
+    pub fn kind(&self) -> TemporalKind {
+        match self {
+            Temporal::Date(_) => TemporalKind::Date,
+            Temporal::Time(_) => TemporalKind::Time,
+            Temporal::DateTime { .. } => TemporalKind::DateTime,
+            Temporal::Timestamp { .. } => TemporalKind::Timestamp,
+            Temporal::Duration(_) => TemporalKind::Duration,
+        }
+    }
+
+    /// Parse an `@` literal's text, including the `@`
+    pub fn parse_point(text: &str) -> Result<Temporal, String> {
+        let body = text.strip_prefix('@').unwrap_or(text);
+        let invalid = |reason: &str| format!("Invalid temporal literal `{}`: {}", text, reason);
+
+        // `@HH:mm[:ss[.fff]]`
+        if body.as_bytes().get(2) == Some(&b':') {
+            return parse_time(body)
+                .map(Temporal::Time)
+                .map_err(|e| invalid(&e));
+        }
+
+        let (date, rest) = body.split_at(body.find('T').unwrap_or(body.len()));
+        let date = parse_date(date).map_err(|e| invalid(&e))?;
+        let Some(rest) = rest.strip_prefix('T') else {
+            return Ok(Temporal::Date(date));
+        };
+
+        if let Some(time) = rest.strip_suffix('Z') {
+            let time = parse_time(time).map_err(|e| invalid(&e))?;
+            return Ok(Temporal::Timestamp { date, time });
+        }
+        match rest.split_once('[') {
+            Some((time, zone)) => {
+                let time = parse_time(time).map_err(|e| invalid(&e))?;
+                let zone = zone.trim_end_matches(']').to_string();
+                Ok(Temporal::DateTime { date, time, zone })
+            }
+            None => Err(format!(
+                "Date-time `{}` has no zone. Flux has no naive date-times: add a zone \
+                 (`{}[Europe/Paris]`) for a DateTime or `Z` (`{}Z`) for a UTC Timestamp.",
+                text, text, text
+            )),
+        }
+    }
+
+    /// Parse the parts of a duration literal, e.g. `["5d", "3h"]`
+    pub fn parse_duration<'a>(
+        parts: impl IntoIterator<Item = &'a str>,
+    ) -> Result<Temporal, String> {
+        let mut duration = Duration::default();
+        for part in parts {
+            duration.add_part(part)?;
+        }
+        Ok(Temporal::Duration(duration))
+    }
+}
+
+/// `YYYY-MM-DD`
+fn parse_date(text: &str) -> Result<Date, String> {
+    let mut fields = text.splitn(3, '-');
+    let mut field = || fields.next().and_then(|field| field.parse::<u32>().ok());
+    let (Some(year), Some(month), Some(day)) = (field(), field(), field()) else {
+        return Err("expected a date `YYYY-MM-DD`".to_string());
+    };
+    if !(1..=12).contains(&month) {
+        return Err(format!("month {} is not between 1 and 12", month));
+    }
+    if !(1..=u32::from(days_in_month(year as i32, month as u8))).contains(&day) {
+        return Err(format!("day {} is not in month {}", day, month));
+    }
+    Ok(Date {
+        year: year as i32,
+        month: month as u8,
+        day: day as u8,
+    })
+}
+
+fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// `HH:mm[:ss[.fffffffff]]`
+fn parse_time(text: &str) -> Result<Time, String> {
+    let (clock, fraction) = text.split_once('.').unwrap_or((text, ""));
+    let mut fields = clock.split(':').map(|field| field.parse::<u32>().ok());
+    let (Some(Some(hour)), Some(Some(minute)), second) =
+        (fields.next(), fields.next(), fields.next())
+    else {
+        return Err("expected a time `HH:mm:ss`".to_string());
+    };
+    let second = second.flatten().unwrap_or(0);
+    if hour > 23 || minute > 59 || second > 59 {
+        return Err(format!("{} is not a time of day", clock));
+    }
+    let nanos = if fraction.is_empty() {
+        0
+    } else {
+        format!("{:0<9}", fraction).parse().unwrap_or(0)
+    };
+    Ok(Time {
+        hour: hour as u8,
+        minute: minute as u8,
+        second: second as u8,
+        nanos,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_points() {
+        let date = Date {
+            year: 2026,
+            month: 10,
+            day: 16,
+        };
+        let noon = Time {
+            hour: 12,
+            minute: 0,
+            second: 0,
+            nanos: 0,
+        };
+        assert_eq!(
+            Temporal::parse_point("@2026-10-16"),
+            Ok(Temporal::Date(date))
+        );
+        assert_eq!(
+            Temporal::parse_point("@12:30:00.25"),
+            Ok(Temporal::Time(Time {
+                hour: 12,
+                minute: 30,
+                second: 0,
+                nanos: 250_000_000,
+            }))
+        );
+        assert_eq!(
+            Temporal::parse_point("@2026-10-16T12:00:00[Europe/Paris]"),
+            Ok(Temporal::DateTime {
+                date,
+                time: noon,
+                zone: "Europe/Paris".to_string(),
+            })
+        );
+        assert_eq!(
+            Temporal::parse_point("@2026-10-16T12:00Z"),
+            Ok(Temporal::Timestamp { date, time: noon })
+        );
+    }
+
+    #[test]
+    fn test_parse_point_rejects_invalid_and_naive() {
+        assert!(Temporal::parse_point("@2026-02-29").is_err());
+        assert!(Temporal::parse_point("@2024-02-29").is_ok());
+        assert!(Temporal::parse_point("@2026-13-01").is_err());
+        assert!(Temporal::parse_point("@24:00").is_err());
+        let naive = Temporal::parse_point("@2026-10-16T12:00:00").unwrap_err();
+        assert!(naive.contains("no naive date-times"), "{}", naive);
+    }
+
+    #[test]
+    fn test_parse_duration_keeps_calendar_units_apart() {
+        assert_eq!(
+            Temporal::parse_duration(["1y", "2mo", "1w", "5d", "3h", "30m", "10ms"]),
+            Ok(Temporal::Duration(Duration {
+                months: 14,
+                days: 12,
+                nanos: 3 * 3600 * NANOS_PER_SECOND + 30 * 60 * NANOS_PER_SECOND + 10_000_000,
+            }))
+        );
+    }
+
+    #[test]
+    fn test_known_zones() {
+        assert_eq!(fixed_offset("UTC"), Some(0));
+        assert_eq!(fixed_offset("+02:00"), Some(7200));
+        assert_eq!(fixed_offset("-03:30"), Some(-12_600));
+        assert_eq!(fixed_offset("+15:00"), None);
+        assert_eq!(fixed_offset("+2:00"), None);
+        assert!(is_known_zone("Europe/Paris"));
+        assert!(!is_known_zone("Not/AZone"));
+        assert!(!is_known_zone("europe/paris"));
+    }
+
+    #[test]
+    fn test_zone_offsets_follow_daylight_saving_time() {
+        let at = |zone, year, month, day, hour, minute| {
+            let date = Date { year, month, day };
+            let time = Time {
+                hour,
+                minute,
+                second: 0,
+                nanos: 0,
+            };
+            utc_offset(zone, &date, &time).map(|offset| offset / 60)
+        };
+        assert_eq!(at("+05:30", 2026, 1, 1, 0, 0), Some(330));
+        assert_eq!(at("Asia/Tokyo", 2026, 7, 1, 12, 0), Some(540));
+        assert_eq!(at("Europe/Paris", 2026, 10, 16, 12, 0), Some(120));
+        assert_eq!(at("Europe/Paris", 2026, 12, 1, 12, 0), Some(60));
+        // Clocks go back at 03:00 on 2026-10-25, reading 02:30 twice
+        assert_eq!(at("Europe/Paris", 2026, 10, 25, 2, 30), Some(120));
+        assert_eq!(at("Europe/Paris", 2026, 10, 25, 3, 30), Some(60));
+        // 02:30 on 2026-03-08 is skipped in New York
+        assert_eq!(at("America/New_York", 2026, 3, 8, 1, 30), Some(-300));
+        assert_eq!(at("America/New_York", 2026, 3, 8, 2, 30), Some(-300));
+        assert_eq!(at("America/New_York", 2026, 3, 8, 3, 30), Some(-240));
+        // Summer is in January in the south
+        assert_eq!(at("Australia/Sydney", 2026, 1, 15, 12, 0), Some(660));
+        assert_eq!(at("Australia/Sydney", 2026, 7, 15, 12, 0), Some(600));
+        assert_eq!(at("Not/AZone", 2026, 1, 1, 0, 0), None);
+    }
+
+    #[test]
+    fn test_days_since_epoch() {
+        let date = |year, month, day| Date { year, month, day };
+        assert_eq!(date(1970, 1, 1).days_since_epoch(), 0);
+        assert_eq!(date(1969, 12, 31).days_since_epoch(), -1);
+        assert_eq!(date(2000, 3, 1).days_since_epoch(), 11_017);
+        assert_eq!(date(2026, 10, 16).days_since_epoch(), 20_742);
+    }
+}
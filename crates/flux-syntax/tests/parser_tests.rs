@@ -1,4 +1,4 @@
-use flux_syntax::parse;
+use flux_syntax::{parse, parse_with_recovery};
 
 #[test]
 fn test_parse_simple_function() {
@@ -56,3 +56,282 @@ fn test_parse_block() {
     let result = parse(input);
     insta::assert_debug_snapshot!(result);
 }
+
+#[test]
+fn test_parse_comparison_operators() {
+    let input = r#"fn check(x: int) -> bool { return x + 1 <= 10 == true }"#;
+    let result = parse(input);
+    insta::assert_debug_snapshot!(result);
+}
+
+#[test]
+fn test_parse_logical_operators() {
+    let input = r#"fn check(a: bool, b: bool) -> bool { return !a || a && b != false }"#;
+    let result = parse(input);
+    insta::assert_debug_snapshot!(result);
+}
+
+#[test]
+fn test_parse_if_else() {
+    let input =
+        r#"fn sign(x: int) -> int { if x < 0 { return 0 - 1 } else if x == 0 { 0 } else { 1 } }"#;
+    let result = parse(input);
+    insta::assert_debug_snapshot!(result);
+}
+
+#[test]
+fn test_parse_if_requires_else() {
+    let input = r#"fn f(x: bool) -> int { if x { 1 } }"#;
+    let result = parse(input);
+    insta::assert_debug_snapshot!(result);
+}
+
+#[test]
+fn test_parse_pipeline_operator() {
+    let input = r#"fn process(data) { data |> filter |> map |> reduce }"#;
+    let result = parse(input);
+    insta::assert_debug_snapshot!(result);
+}
+
+#[test]
+fn test_parse_pipeline_precedence() {
+    let input = r#"fn f(x: int) -> bool { return x + 1 |> clamp(0, 10) |> is_even || false }"#;
+    let result = parse(input);
+    insta::assert_debug_snapshot!(result);
+}
+
+#[test]
+fn test_parse_column_references() {
+    let input = r#"fn pick() { select(#id, #"order total") }"#;
+    let result = parse(input);
+    insta::assert_debug_snapshot!(result);
+}
+
+#[test]
+fn test_parse_complex_pipeline() {
+    let input = r#"
+fn analyze(value: int) -> int {
+    value |> filter(#active) |> sum
+}"#;
+    let result = parse(input);
+    insta::assert_debug_snapshot!(result);
+}
+
+#[test]
+fn test_parse_recovers_broken_statements() {
+    let input = r#"
+fn broken(x: int) -> int {
+    let y = x + ;
+    y * 2
+}"#;
+    let result = parse_with_recovery(input);
+    insta::assert_debug_snapshot!(result);
+}
+
+#[test]
+fn test_parse_recovers_at_item_boundary() {
+    let input = r#"
+fn also_broken( -> int { 1 }
+export fn fine() -> int { 1 }"#;
+    let result = parse_with_recovery(input);
+    insta::assert_debug_snapshot!(result);
+}
+
+#[test]
+fn test_parse_recovers_unclosed_block() {
+    let input = r#"
+fn open() -> int { 1 + 2
+fn next() -> int { 2 }"#;
+    let result = parse_with_recovery(input);
+    insta::assert_debug_snapshot!(result);
+}
+
+#[test]
+fn test_parse_imports() {
+    let input = r#"import util
+import lib.text.{trim, pad}
+fn main() -> int { trim(1) }"#;
+    let result = parse(input);
+    insta::assert_debug_snapshot!(result);
+}
+
+#[test]
+fn test_parse_recovers_broken_import() {
+    let input = r#"import lib.{trim pad}
+import 42
+fn main() -> int { 1 }"#;
+    let result = parse_with_recovery(input);
+    insta::assert_debug_snapshot!(result);
+}
+
+#[test]
+fn test_syntax_tree_round_trips_source() {
+    let inputs = [
+        include_str!("../../../examples/simple.flux"),
+        include_str!("../../../examples/let_binding.flux"),
+        include_str!("../../../examples/plan.flux"),
+        include_str!("../../../examples/type_error.flux"),
+        "  // leading comment\nfn f() -> int { 1 } // trailing\n\n",
+        "fn broken(x: int) -> int {\n    let y = x + ;\n    y * 2\n}",
+        "fn also_broken( -> int { 1 }\nexport fn fine() -> int { 1 }",
+        "fn open() -> int { 1 + 2\nfn next() -> int { 2 }",
+        "fn f() -> int { $ 1 } ?? )",
+        "import a.b.{c, d}\nimport . fn f() -> int { 1 }",
+        "",
+    ];
+    for input in inputs {
+        let result = parse_with_recovery(input);
+        assert_eq!(result.syntax().to_string(), input);
+    }
+}
+
+#[test]
+fn test_syntax_tree_keeps_trivia() {
+    let input = r#"// Adds one
+fn inc(x: int) -> int {
+    x + 1 // no overflow check
+}"#;
+    let result = parse_with_recovery(input);
+    insta::assert_snapshot!(format!("{:#?}", result.syntax()));
+}
+
+#[test]
+fn test_syntax_tree_wraps_skipped_source_in_error_nodes() {
+    let input = r#"
+fn broken(x: int) -> int {
+    let y = x + ;
+    y * 2
+}
+fn also_broken( -> int { 1 }"#;
+    let result = parse_with_recovery(input);
+    insta::assert_snapshot!(format!("{:#?}", result.syntax()));
+}
+
+#[test]
+fn test_parse_temporal_literals() {
+    let input = r#"fn schedule(logged: Timestamp) -> DateTime {
+    let day = @2026-10-16
+    let at = @12:30:00
+    let start = @2026-10-16T10:00:00Z
+    @2026-10-16T12:00:00[Europe/Paris] + 5d 3h
+}"#;
+    let result = parse(input);
+    insta::assert_debug_snapshot!(result);
+}
+
+#[test]
+fn test_parse_rejects_naive_date_time() {
+    let input = r#"fn meeting() -> DateTime { @2026-10-16T12:00:00 }
+fn deadline() -> Date { @2026-02-30 }"#;
+    let result = parse_with_recovery(input);
+    insta::assert_debug_snapshot!(result);
+}
+
+#[test]
+fn test_parse_records() {
+    let input = r#"type Point = { x: float, y: float }
+struct User { name: string, home: Point }
+fn make() -> User {
+    let origin = { x: 0.0, y: 0.0 }
+    User { name: "ada", home: origin }
+}
+fn dist(u: User) -> float { u.home.x * u.home.y }"#;
+    let result = parse(input);
+    insta::assert_debug_snapshot!(result);
+}
+
+#[test]
+fn test_parse_record_literal_is_not_a_block() {
+    let input = r#"fn f(flag: bool, p: { x: int }) -> int { if flag { p.x } else { { x: 1 }.x } }"#;
+    let result = parse(input);
+    insta::assert_debug_snapshot!(result);
+}
+
+#[test]
+fn test_parse_enum_and_match() {
+    let input = r#"enum Outcome { Done, Failed(string), Retry(int, Duration) }
+fn code(o: Outcome) -> int {
+    match o {
+        Done => 0,
+        Failed("timeout") => 1
+        Retry(0, _) => 2,
+        other => { 3 }
+    }
+}"#;
+    let result = parse_with_recovery(input);
+    insta::assert_debug_snapshot!(result);
+}
+
+#[test]
+fn test_parse_match_patterns() {
+    let input = r#"fn f(flag: bool, n: int) -> int {
+    match flag { true => match n { 0 => 1, _ => n } false => 0 }
+}"#;
+    let result = parse(input);
+    insta::assert_debug_snapshot!(result);
+}
+
+#[test]
+fn test_parse_lambda() {
+    let input = r#"fn main() -> int {
+    let double = fn(x: int) -> int { x * 2 }
+    let offset = 10
+    5 |> apply(fn(y) { double(y) + offset })
+}"#;
+    let result = parse_with_recovery(input);
+    insta::assert_debug_snapshot!(result);
+}
+
+#[test]
+fn test_parse_lists_tuples_and_indexing() {
+    let input = r#"fn pick(xs: List<int>, pair: (int, List<bool>)) -> int {
+    let ys = [1, 2, (3), ]
+    xs[0] + ys[pair[0]]
+}"#;
+    let result = parse_with_recovery(input);
+    insta::assert_debug_snapshot!(result);
+}
+
+#[test]
+fn test_parse_function_types() {
+    let input = r#"fn adder(k: int) -> (int) -> int { fn(x: int) { x + k } }
+fn apply(f: (int, (bool, int)) -> bool, g: () -> (int) -> int) -> bool { f(g()(1), (true, 2)) }"#;
+    let result = parse_with_recovery(input);
+    insta::assert_debug_snapshot!(result);
+}
+
+#[test]
+fn test_parse_native_functions() {
+    // A native function is a signature without a body
+    let input = "export native fn count<T>(values: Column<T>) -> int
+native fn tick() -> int
+fn main() -> int { tick() }";
+    let result = parse_with_recovery(input);
+    insta::assert_debug_snapshot!(result);
+}
+
+#[test]
+fn test_parse_single_element_tuple_is_an_error() {
+    let result = parse("fn main() { (1,) }");
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_parse_generic_functions_and_types() {
+    let input = r#"struct Pair<A, B> { first: A, second: B }
+enum Maybe<T> { Nothing, Just(T) }
+fn swap<A, B>(pair: Pair<A, B>) -> Pair<B, A> {
+    Pair { first: pair.second, second: pair.first }
+}"#;
+    let result = parse_with_recovery(input);
+    insta::assert_debug_snapshot!(result);
+}
+
+#[test]
+fn test_parse_try_operator() {
+    let input = r#"fn total(xs: List<int>) -> Option<int> {
+    some(first(xs)? + lookup(xs)?.count + xs[0]?)
+}"#;
+    let result = parse_with_recovery(input);
+    insta::assert_debug_snapshot!(result);
+}
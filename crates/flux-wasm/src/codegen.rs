@@ -1,9 +1,14 @@
-use flux_errors::{FluxError, Result};
-use flux_syntax::{Expr, SourceFile, Type};
+use crate::mono;
+use flux_errors::{FluxError, Result, Span};
+use flux_sema::TypeInfo;
+use flux_syntax::temporal::{utc_offset, NANOS_PER_DAY, NANOS_PER_SECOND};
+use flux_syntax::{BinOp, Expr, Pattern, SourceFile, Temporal, TemporalKind, Type, UnaryOp};
+use std::borrow::Cow;
 use std::collections::HashMap;
 use wasm_encoder::{
-    CodeSection, ExportKind, ExportSection, Function, FunctionSection, Instruction, Module,
-    TypeSection, ValType,
+    BlockType, CodeSection, ConstExpr, DataSection, ElementSection, Elements, Encode, ExportKind,
+    ExportSection, Function, FunctionSection, GlobalSection, GlobalType, Instruction, MemArg,
+    MemorySection, MemoryType, Module, RefType, TableSection, TableType, TypeSection, ValType,
 };
 use wit_component::ComponentEncoder;
 
@@ -11,46 +16,211 @@ use wit_component::ComponentEncoder;
 struct LocalContext {
     /// Maps variable names to local indices
     locals: HashMap<String, u32>,
-    /// Next available local index
-    next_index: u32,
+    /// Type of every local, parameters included, by index
+    types: Vec<ValType>,
 }
 
 impl LocalContext {
     fn new() -> Self {
         Self {
             locals: HashMap::new(),
-            next_index: 0,
+            types: Vec::new(),
         }
     }
 
     /// Add a parameter as a local (parameters come first)
-    fn add_param(&mut self, name: &str) -> u32 {
-        let idx = self.next_index;
-        self.locals.insert(name.to_string(), idx);
-        self.next_index += 1;
-        idx
+    fn add_param(&mut self, name: &str, ty: ValType) -> u32 {
+        self.add_local(name, ty)
     }
 
     /// Add a local variable (allocated after parameters)
-    fn add_local(&mut self, name: &str) -> u32 {
-        let idx = self.next_index;
+    fn add_local(&mut self, name: &str, ty: ValType) -> u32 {
+        let idx = self.add_temp(ty);
         self.locals.insert(name.to_string(), idx);
-        self.next_index += 1;
         idx
     }
 
+    /// Add an unnamed local holding an intermediate value
+    fn add_temp(&mut self, ty: ValType) -> u32 {
+        self.types.push(ty);
+        self.types.len() as u32 - 1
+    }
+
     /// Get the index of a local variable
     fn get(&self, name: &str) -> Option<u32> {
         self.locals.get(name).copied()
     }
+
+    /// Type of the local at `idx`
+    fn ty(&self, idx: u32) -> ValType {
+        self.types[idx as usize]
+    }
+}
+
+/// Instructions of a function being compiled. The types of its locals are
+/// only known once its body is compiled, as `LocalContext` records them, so
+/// the instructions are kept apart until `finish` declares the locals in
+/// front of them.
+struct Code {
+    bytes: Vec<u8>,
+}
+
+impl Code {
+    fn new() -> Self {
+        Self { bytes: Vec::new() }
+    }
+
+    fn instruction(&mut self, instruction: &Instruction) -> &mut Self {
+        instruction.encode(&mut self.bytes);
+        self
+    }
+
+    /// The function declaring the locals of `locals` past its first
+    /// `params`, followed by the instructions and the final `end`
+    fn finish(mut self, locals: &LocalContext, params: usize) -> Function {
+        self.instruction(&Instruction::End);
+        let mut func = Function::new_with_locals_types(locals.types[params..].iter().copied());
+        func.raw(self.bytes);
+        func
+    }
+}
+
+/// Signature of a closure, leaving out the environment pointer every lifted
+/// function takes first
+#[derive(Debug, Clone, PartialEq)]
+struct ClosureType {
+    params: Vec<ValType>,
+    ret: ValType,
+}
+
+/// A function of the module compiled from a Flux function
+#[derive(Debug, Clone)]
+struct FunctionRef {
+    index: u32,
+    params: Vec<ValType>,
+    ret: ValType,
+}
+
+/// A lambda, or a function used as a value, lifted into a function of its
+/// own called through the table
+struct LiftedFunction {
+    type_index: u32,
+    body: Function,
+}
+
+/// Global holding the next free address of the heap, exported as `heap`;
+/// the globals after it hold columns
+const HEAP: u32 = 0;
+/// Where the heap starts. The 8 zero bytes before it read as an empty
+/// list or column, which every column holds until the host sets it.
+const HEAP_START: i32 = 8;
+/// Initial size of linear memory, in 64 KiB pages; allocations grow it
+/// past that
+const MEMORY_PAGES: u64 = 16;
+
+/// Layout of an enum variant. A value of an enum is a pointer to a cell in
+/// linear memory: the tag is an i32 at offset 0 and field `i` sits in an
+/// 8-byte slot at offset `8 + 8 * i`.
+#[derive(Debug, Clone)]
+struct VariantLayout {
+    /// Index of the variant in its enum
+    tag: i32,
+    /// Type of each field; `None` for the payload of `Option` and `Result`,
+    /// whose type is the one sema inferred where the value is built or
+    /// matched
+    fields: Vec<Option<ValType>>,
+}
+
+impl VariantLayout {
+    fn size(&self) -> i32 {
+        8 + 8 * self.fields.len() as i32
+    }
+
+    fn field_offset(index: usize) -> u64 {
+        8 + 8 * index as u64
+    }
+}
+
+/// Lists and tuples share one layout: a pointer to a cell holding the
+/// number of elements as an i32 at offset 0 and element `i` in an 8-byte
+/// slot at `VariantLayout::field_offset(i)`. Indexing checks the index
+/// against the stored length either way.
+const ELEMENTS_OFFSET: u64 = 8;
+
+/// A string is a pointer to a cell holding its length in bytes as an i32
+/// at offset 0 and its UTF-8 bytes from `BYTES_OFFSET`. Literals sit in a
+/// data segment at the start of the heap.
+const BYTES_OFFSET: u64 = 4;
+
+/// Loads leading from the matched value to a nested one: each step loads a
+/// field at an offset from the cell the previous value points to
+type Path = Vec<(u64, ValType)>;
+
+/// What a match arm's pattern requires of the matched value and what it
+/// binds, in the order the checks must run: an inner value is only loaded
+/// once the tags above it matched
+#[derive(Default)]
+struct PatternPlan {
+    checks: Vec<(Path, Check)>,
+    bindings: Vec<(String, Path, ValType)>,
+}
+
+enum Check {
+    /// The value points to a cell with this tag
+    Tag(i32),
+    /// The value is this int
+    Int(i64),
+    /// The value is this bool
+    Bool(bool),
 }
 
 /// WASM code generator for Flux
-pub struct WasmCodegen {}
+pub struct WasmCodegen {
+    /// Every enum variant of the file being compiled, by name
+    variants: HashMap<String, VariantLayout>,
+    /// WASM types of the enums and aliases of the file being compiled
+    named_types: HashMap<String, ValType>,
+    /// Types sema inferred for the expressions and patterns of the function
+    /// being compiled, by span
+    expr_types: HashMap<Span, TypeInfo>,
+    /// Every function of the module compiled from a Flux function, by
+    /// name; instances of generic functions are named after their type
+    /// arguments
+    functions: HashMap<String, FunctionRef>,
+    /// Function types of the module, by type index
+    signatures: Vec<(Vec<ValType>, Vec<ValType>)>,
+    /// Lambdas lifted out of the code compiled so far. Lifted function `i`
+    /// is entry `i` of the module's table and follows the functions in
+    /// `functions`.
+    lifted: Vec<LiftedFunction>,
+    /// Table index of the lifted function forwarding to each function of
+    /// `functions` used as a value, by name
+    forwarders: HashMap<String, i32>,
+    /// Columns the code compiled so far refers to with `#name`. Column `i`
+    /// is held by global `1 + i`, which the host sets through its export
+    /// `column:<name>`.
+    columns: Vec<String>,
+    /// Cells of the string literals compiled so far, laid out from
+    /// `HEAP_START`; the heap starts past them
+    string_data: Vec<u8>,
+    /// Address of the cell of each string literal in `string_data`
+    strings: HashMap<String, i32>,
+}
 
 impl WasmCodegen {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            variants: HashMap::new(),
+            named_types: HashMap::new(),
+            expr_types: HashMap::new(),
+            functions: HashMap::new(),
+            signatures: Vec::new(),
+            lifted: Vec::new(),
+            forwarders: HashMap::new(),
+            columns: Vec::new(),
+            string_data: Vec::new(),
+            strings: HashMap::new(),
+        }
     }
 
     /// Compile a Flux source file to a WASM component
@@ -74,82 +244,196 @@ impl WasmCodegen {
     }
 
     /// Compile the core WASM module
-    fn compile_core_module(&mut self, ast: &SourceFile) -> Result<Vec<u8>> {
+    pub fn compile_core_module(&mut self, ast: &SourceFile) -> Result<Vec<u8>> {
         let mut module = Module::new();
+        self.variants = Self::variant_layouts(ast)?;
+        self.named_types = Self::named_val_types(ast);
+        self.signatures.clear();
+        self.lifted.clear();
+        self.forwarders.clear();
+        self.columns.clear();
+        self.string_data.clear();
+        self.strings.clear();
+
+        // Every function, and every instance of a generic one, is a function
+        // of the module in the order `monomorphize` gives them; compiling
+        // them lifts the lambdas in them out after them. Every signature is
+        // known up front, so calls can come before their callee.
+        let instances = mono::monomorphize(ast)?;
+        self.functions.clear();
+        for (index, instance) in instances.iter().enumerate() {
+            let TypeInfo::Function { params, ret } = &instance.ty else {
+                return Err(FluxError::WasmError {
+                    message: format!("`{}` has no function type", instance.func.name),
+                });
+            };
+            // Only declared type parameters are instantiated; a function
+            // inferred generic, like `fn id(x) { x }`, has no single signature
+            if params
+                .iter()
+                .chain([&**ret])
+                .any(|ty| matches!(ty, TypeInfo::Var(_)))
+            {
+                return Err(FluxError::WasmError {
+                    message: format!(
+                        "`{}` is generic, {}, but declares no type parameters; annotate its \
+                         parameters or declare them, e.g. `fn {}<T>(x: T)`",
+                        instance.func.name, instance.ty, instance.func.name
+                    ),
+                });
+            }
+            let function = FunctionRef {
+                index: index as u32,
+                params: params
+                    .iter()
+                    .map(|ty| self.info_val_type(ty))
+                    .collect::<Result<_>>()?,
+                ret: self.info_val_type(ret)?,
+            };
+            self.functions.insert(instance.func.name.clone(), function);
+        }
+        let mut compiled = Vec::with_capacity(instances.len());
+        let mut exported = Vec::new();
+        for (index, instance) in instances.into_iter().enumerate() {
+            // `main` is the entry point, exported whether or not it says so
+            if instance.func.is_export || instance.func.name == "main" {
+                exported.push((instance.func.name.clone(), index as u32));
+            }
+            compiled.push(self.compile_function(instance)?);
+        }
 
         // Create type section
         let mut types = TypeSection::new();
-        // Function type: () -> i32
-        types.ty().function(vec![], vec![ValType::I32]);
+        for (params, results) in &self.signatures {
+            types.ty().function(params.clone(), results.clone());
+        }
         module.section(&types);
 
         // Create function section
         let mut functions = FunctionSection::new();
-        functions.function(0); // Function 0 uses type 0
+        for (type_index, _) in &compiled {
+            functions.function(*type_index);
+        }
+        for lifted in &self.lifted {
+            functions.function(lifted.type_index);
+        }
         module.section(&functions);
 
-        // Create export section
-        let mut exports = ExportSection::new();
-        exports.export("main", ExportKind::Func, 0);
-        module.section(&exports);
-
-        // Create code section
-        let mut codes = CodeSection::new();
-
-        // Generate code for the first function's body
-        if let Some(flux_syntax::Item::Function(first_func)) = ast.items.first() {
-            let mut locals_ctx = LocalContext::new();
+        // Closures call their lifted function through the table
+        let table_size = self.lifted.len() as u64;
+        if table_size > 0 {
+            let mut tables = TableSection::new();
+            tables.table(TableType {
+                element_type: RefType::FUNCREF,
+                table64: false,
+                minimum: table_size,
+                maximum: Some(table_size),
+                shared: false,
+            });
+            module.section(&tables);
+        }
 
-            // Add parameters as locals
-            for param in &first_func.params {
-                locals_ctx.add_param(&param.name);
-            }
+        // Enum values, closures, lists and columns live in linear memory,
+        // bump-allocated from `HEAP` and never freed
+        let mut memories = MemorySection::new();
+        memories.memory(MemoryType {
+            minimum: MEMORY_PAGES,
+            maximum: None,
+            memory64: false,
+            shared: false,
+            page_size_log2: None,
+        });
+        module.section(&memories);
 
-            // Count additional locals needed for let bindings
-            let additional_locals = self.count_let_bindings(&first_func.body);
+        let mut globals = GlobalSection::new();
+        let pointer = GlobalType {
+            val_type: ValType::I32,
+            mutable: true,
+            shared: false,
+        };
+        let heap_start = HEAP_START + self.string_data.len() as i32;
+        globals.global(pointer, &ConstExpr::i32_const(heap_start));
+        for _ in &self.columns {
+            globals.global(pointer, &ConstExpr::i32_const(0));
+        }
+        module.section(&globals);
 
-            let func_locals = if additional_locals > 0 {
-                vec![(additional_locals, ValType::I32)]
-            } else {
-                vec![]
-            };
+        // Create export section. The host fills columns in by allocating
+        // their cells from `heap` in `memory`.
+        let mut exports = ExportSection::new();
+        for (name, index) in &exported {
+            exports.export(name, ExportKind::Func, *index);
+        }
+        exports.export("memory", ExportKind::Memory, 0);
+        exports.export("heap", ExportKind::Global, HEAP);
+        for (index, name) in self.columns.iter().enumerate() {
+            exports.export(
+                &format!("column:{}", name),
+                ExportKind::Global,
+                1 + index as u32,
+            );
+        }
+        module.section(&exports);
 
-            let mut func = Function::new(func_locals);
-            self.compile_expr_with_locals(&first_func.body, &mut locals_ctx, &mut func)?;
-            func.instruction(&Instruction::End);
-            codes.function(&func);
-        } else {
-            // Default: return 42
-            let mut func = Function::new(vec![]);
-            func.instruction(&Instruction::I32Const(42));
-            func.instruction(&Instruction::End);
-            codes.function(&func);
+        if table_size > 0 {
+            let first_lifted = compiled.len() as u32;
+            let lifted_indices: Vec<u32> =
+                (first_lifted..first_lifted + self.lifted.len() as u32).collect();
+            let mut elements = ElementSection::new();
+            elements.active(
+                Some(0),
+                &ConstExpr::i32_const(0),
+                Elements::Functions(Cow::Owned(lifted_indices)),
+            );
+            module.section(&elements);
         }
 
+        // Create code section
+        let mut codes = CodeSection::new();
+        for (_, body) in &compiled {
+            codes.function(body);
+        }
+        for lifted in &self.lifted {
+            codes.function(&lifted.body);
+        }
         module.section(&codes);
+
+        if !self.string_data.is_empty() {
+            let mut data = DataSection::new();
+            data.active(
+                0,
+                &ConstExpr::i32_const(HEAP_START),
+                self.string_data.iter().copied(),
+            );
+            module.section(&data);
+        }
         Ok(module.finish())
     }
 
-    /// Count let bindings to determine how many locals we need
-    fn count_let_bindings(&self, expr: &Expr) -> u32 {
-        match expr {
-            Expr::Let { value, body, .. } => {
-                1 + self.count_let_bindings(value) + self.count_let_bindings(body)
-            }
-            Expr::Binary { left, right, .. } => {
-                self.count_let_bindings(left) + self.count_let_bindings(right)
-            }
-            Expr::Call { func, args, .. } => {
-                let mut count = self.count_let_bindings(func);
-                for arg in args {
-                    count += self.count_let_bindings(arg);
-                }
-                count
-            }
-            Expr::Block { stmts, .. } => stmts.iter().map(|s| self.count_let_bindings(s)).sum(),
-            Expr::Return { value, .. } => self.count_let_bindings(value),
-            _ => 0,
+    /// Compile a function, lifting the lambdas in it out as it goes. Returns
+    /// the index of its type and its code.
+    fn compile_function(&mut self, instance: mono::Instance) -> Result<(u32, Function)> {
+        let FunctionRef { params, ret, .. } = self.functions[&instance.func.name].clone();
+        self.expr_types = instance.expr_types;
+
+        let mut locals_ctx = LocalContext::new();
+        for (param, ty) in instance.func.params.iter().zip(&params) {
+            locals_ctx.add_param(&param.name, *ty);
         }
+
+        let mut body = Code::new();
+        if instance.func.is_native {
+            // Linking names the natives of the std library after their module
+            let name = &instance.declared_name;
+            let name = name
+                .rsplit_once('.')
+                .map_or(name.as_str(), |(_, name)| name);
+            self.compile_native(name, &instance.ty, &mut locals_ctx, &mut body)?;
+        } else {
+            self.compile_expr_with_locals(&instance.func.body, &mut locals_ctx, &mut body)?;
+        }
+        let func = body.finish(&locals_ctx, params.len());
+        Ok((self.signature_index(params, vec![ret]), func))
     }
 
     /// Compile an expression with local variable context
@@ -157,11 +441,11 @@ impl WasmCodegen {
         &mut self,
         expr: &Expr,
         locals: &mut LocalContext,
-        func: &mut Function,
+        func: &mut Code,
     ) -> Result<()> {
         match expr {
             Expr::Int { value, .. } => {
-                func.instruction(&Instruction::I32Const(*value as i32));
+                func.instruction(&Instruction::I64Const(*value));
             }
             Expr::Float { value, .. } => {
                 func.instruction(&Instruction::F64Const(*value));
@@ -169,36 +453,184 @@ impl WasmCodegen {
             Expr::Bool { value, .. } => {
                 func.instruction(&Instruction::I32Const(if *value { 1 } else { 0 }));
             }
-            Expr::String { .. } => {
-                // Strings are not yet fully supported - return placeholder
-                func.instruction(&Instruction::I32Const(0));
+            Expr::String { value, .. } => {
+                func.instruction(&Instruction::I32Const(self.string_literal(value)));
             }
-            Expr::Var { name, .. } => {
-                let local_idx = locals.get(name).ok_or_else(|| FluxError::WasmError {
-                    message: format!("Undefined variable: {}", name),
-                })?;
-                func.instruction(&Instruction::LocalGet(local_idx));
+            Expr::Temporal { value, .. } => {
+                func.instruction(&Self::temporal_const(value)?);
+            }
+            Expr::Column { name, .. } => {
+                let index = match self.columns.iter().position(|column| column == name) {
+                    Some(index) => index,
+                    None => {
+                        self.columns.push(name.clone());
+                        self.columns.len() - 1
+                    }
+                };
+                func.instruction(&Instruction::GlobalGet(1 + index as u32));
+            }
+            Expr::Record { .. } | Expr::Field { .. } => {
+                return Err(FluxError::WasmError {
+                    message: "Records have no runtime representation yet".to_string(),
+                });
+            }
+            Expr::List { elems, .. } | Expr::Tuple { elems, .. } => {
+                let cell = Self::compile_alloc(
+                    ELEMENTS_OFFSET as i32 + 8 * elems.len() as i32,
+                    locals,
+                    func,
+                );
+                func.instruction(&Instruction::LocalGet(cell));
+                func.instruction(&Instruction::I32Const(elems.len() as i32));
+                func.instruction(&Self::store(ValType::I32, 0));
+                for (index, elem) in elems.iter().enumerate() {
+                    func.instruction(&Instruction::LocalGet(cell));
+                    self.compile_expr_with_locals(elem, locals, func)?;
+                    func.instruction(&Self::store(
+                        self.expr_val_type(elem)?,
+                        VariantLayout::field_offset(index),
+                    ));
+                }
+                func.instruction(&Instruction::LocalGet(cell));
+            }
+            Expr::Index { base, index, .. } => {
+                self.compile_expr_with_locals(base, locals, func)?;
+                let cell = locals.add_temp(ValType::I32);
+                func.instruction(&Instruction::LocalSet(cell));
+                self.compile_expr_with_locals(index, locals, func)?;
+                let position = locals.add_temp(ValType::I64);
+                func.instruction(&Instruction::LocalSet(position));
+
+                // Trap unless 0 <= index < length; compared unsigned, a
+                // negative index is out of bounds too
+                func.instruction(&Instruction::LocalGet(position));
+                func.instruction(&Instruction::LocalGet(cell));
+                func.instruction(&Self::load(ValType::I32, 0));
+                func.instruction(&Instruction::I64ExtendI32U);
+                func.instruction(&Instruction::I64GeU);
+                func.instruction(&Instruction::If(BlockType::Empty));
+                func.instruction(&Instruction::Unreachable);
+                func.instruction(&Instruction::End);
+
+                func.instruction(&Instruction::LocalGet(cell));
+                func.instruction(&Instruction::LocalGet(position));
+                func.instruction(&Instruction::I32WrapI64);
+                func.instruction(&Instruction::I32Const(3));
+                func.instruction(&Instruction::I32Shl);
+                func.instruction(&Instruction::I32Add);
+                func.instruction(&Self::load(self.expr_val_type(expr)?, ELEMENTS_OFFSET));
+            }
+            Expr::Error { .. } => {
+                return Err(FluxError::WasmError {
+                    message: "Cannot compile source with syntax errors".to_string(),
+                });
+            }
+            Expr::Var { name, .. } => match (locals.get(name), self.variants.get(name)) {
+                (Some(local_idx), _) => {
+                    func.instruction(&Instruction::LocalGet(local_idx));
+                }
+                (None, Some(layout)) if layout.fields.is_empty() => {
+                    // A cell holding only the tag
+                    let cell = Self::compile_alloc(layout.size(), locals, func);
+                    func.instruction(&Instruction::LocalGet(cell));
+                    func.instruction(&Instruction::I32Const(layout.tag));
+                    func.instruction(&Self::store(ValType::I32, 0));
+                    func.instruction(&Instruction::LocalGet(cell));
+                }
+                (None, Some(_)) => {
+                    return Err(FluxError::WasmError {
+                        message: format!(
+                            "Variant `{}` has fields and must be called with them",
+                            name
+                        ),
+                    });
+                }
+                (None, None) if self.functions.contains_key(name) => {
+                    self.compile_function_value(name, locals, func);
+                }
+                (None, None) => {
+                    return Err(FluxError::WasmError {
+                        message: format!("Undefined variable: {}", name),
+                    });
+                }
+            },
+            Expr::Try { operand, .. } => {
+                self.compile_expr_with_locals(operand, locals, func)?;
+                let cell = locals.add_temp(ValType::I32);
+                func.instruction(&Instruction::LocalSet(cell));
+
+                // `some` and `ok` are tag 0. Sema checked that the function
+                // returns an Option or a Result like the operand, so a
+                // `none` or `err` cell is returned as it is.
+                func.instruction(&Instruction::LocalGet(cell));
+                func.instruction(&Self::load(ValType::I32, 0));
+                func.instruction(&Instruction::If(BlockType::Empty));
+                func.instruction(&Instruction::LocalGet(cell));
+                func.instruction(&Instruction::Return);
+                func.instruction(&Instruction::End);
+
+                func.instruction(&Instruction::LocalGet(cell));
+                func.instruction(&Self::load(
+                    self.expr_val_type(expr)?,
+                    VariantLayout::field_offset(0),
+                ));
+            }
+            Expr::Unary { op, operand, .. } => {
+                self.compile_expr_with_locals(operand, locals, func)?;
+                match op {
+                    UnaryOp::Not => {
+                        func.instruction(&Instruction::I32Eqz);
+                    }
+                }
             }
             Expr::Binary {
-                op, left, right, ..
+                op: op @ (BinOp::And | BinOp::Or),
+                left,
+                right,
+                ..
             } => {
+                // Short-circuit: the right operand only runs when it decides the result
+                self.compile_expr_with_locals(left, locals, func)?;
+                func.instruction(&Instruction::If(BlockType::Result(ValType::I32)));
+                if *op == BinOp::And {
+                    self.compile_expr_with_locals(right, locals, func)?;
+                    func.instruction(&Instruction::Else);
+                    func.instruction(&Instruction::I32Const(0));
+                } else {
+                    func.instruction(&Instruction::I32Const(1));
+                    func.instruction(&Instruction::Else);
+                    self.compile_expr_with_locals(right, locals, func)?;
+                }
+                func.instruction(&Instruction::End);
+            }
+            Expr::Binary {
+                op, left, right, ..
+            } if op.is_arithmetic()
+                && (self.temporal_kind(left).is_some() || self.temporal_kind(right).is_some()) =>
+            {
+                self.compile_temporal_arithmetic(*op, left, right, locals, func)?;
+            }
+            Expr::Binary {
+                op: op @ (BinOp::Eq | BinOp::NotEq),
+                left,
+                right,
+                ..
+            } if matches!(self.expr_types.get(&left.span()), Some(TypeInfo::String)) => {
                 self.compile_expr_with_locals(left, locals, func)?;
                 self.compile_expr_with_locals(right, locals, func)?;
-                match op {
-                    flux_syntax::BinOp::Add => {
-                        func.instruction(&Instruction::I32Add);
-                    }
-                    flux_syntax::BinOp::Sub => {
-                        func.instruction(&Instruction::I32Sub);
-                    }
-                    flux_syntax::BinOp::Mul => {
-                        func.instruction(&Instruction::I32Mul);
-                    }
-                    flux_syntax::BinOp::Div => {
-                        func.instruction(&Instruction::I32DivS);
-                    }
+                Self::compile_string_eq(locals, func);
+                if *op == BinOp::NotEq {
+                    func.instruction(&Instruction::I32Eqz);
                 }
             }
+            Expr::Binary {
+                op, left, right, ..
+            } => {
+                let operand_ty = self.expr_val_type(left)?;
+                self.compile_expr_with_locals(left, locals, func)?;
+                self.compile_expr_with_locals(right, locals, func)?;
+                func.instruction(&Self::binary_instruction(*op, operand_ty));
+            }
             Expr::Let {
                 name, value, body, ..
             } => {
@@ -206,33 +638,1231 @@ impl WasmCodegen {
                 self.compile_expr_with_locals(value, locals, func)?;
 
                 // Allocate a local and store
-                let local_idx = locals.add_local(name);
+                let local_idx = locals.add_local(name, self.expr_val_type(value)?);
                 func.instruction(&Instruction::LocalSet(local_idx));
 
                 // Compile the body
                 self.compile_expr_with_locals(body, locals, func)?;
             }
+            Expr::If {
+                cond,
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                self.compile_expr_with_locals(cond, locals, func)?;
+                let result_ty = self.expr_val_type(expr)?;
+                func.instruction(&Instruction::If(BlockType::Result(result_ty)));
+                self.compile_expr_with_locals(then_branch, locals, func)?;
+                func.instruction(&Instruction::Else);
+                self.compile_expr_with_locals(else_branch, locals, func)?;
+                func.instruction(&Instruction::End);
+            }
             Expr::Return { value, .. } => {
                 self.compile_expr_with_locals(value, locals, func)?;
                 func.instruction(&Instruction::Return);
             }
             Expr::Block { stmts, .. } => {
-                if let Some(last) = stmts.last() {
-                    self.compile_expr_with_locals(last, locals, func)?;
+                let Some((last, earlier)) = stmts.split_last() else {
+                    // `{}` is a zero of the type sema gave it
+                    func.instruction(&Self::zero(self.expr_val_type(expr)?));
+                    return Ok(());
+                };
+                // Earlier statements run for what they do, like a `?` or a
+                // `return` leaving the function or an index trapping; their
+                // values are dropped
+                for stmt in earlier {
+                    self.compile_expr_with_locals(stmt, locals, func)?;
+                    func.instruction(&Instruction::Drop);
+                }
+                self.compile_expr_with_locals(last, locals, func)?;
+            }
+            Expr::Pipeline { left, right, span } => {
+                let call = Expr::pipeline_to_call(left, right, *span);
+                self.compile_expr_with_locals(&call, locals, func)?;
+            }
+            Expr::Call {
+                func: callee, args, ..
+            } => {
+                if let Some(name) = self.called_function(callee, locals) {
+                    self.compile_call(name, args, locals, func)?;
+                } else if let Some(name) = self.constructor_name(callee) {
+                    self.compile_constructor(name, args, locals, func)?;
+                } else if let Some(closure) = self.closure_type(callee)? {
+                    self.compile_closure_call(callee, &closure, args, locals, func)?;
                 } else {
-                    func.instruction(&Instruction::I32Const(0));
+                    return Err(FluxError::WasmError {
+                        message: "Only functions, variants and closures can be called".to_string(),
+                    });
                 }
             }
-            Expr::Call { .. } => {
-                // Function calls not yet implemented
+            Expr::Lambda { params, body, .. } => {
+                let param_names: Vec<&str> = params.iter().map(|p| p.name.as_str()).collect();
+                self.compile_lambda(expr, &param_names, body, locals, func)?;
+            }
+            Expr::Match {
+                scrutinee, arms, ..
+            } => {
+                let scrutinee_ty = self.expr_val_type(scrutinee)?;
+                self.compile_expr_with_locals(scrutinee, locals, func)?;
+                let value = locals.add_temp(scrutinee_ty);
+                func.instruction(&Instruction::LocalSet(value));
+
+                // One `if` per arm, each nested in the `else` of the one
+                // before; sema rejects matches that can fall off the end
+                let result_ty = self.expr_val_type(expr)?;
+                for arm in arms {
+                    let plan = self.plan_pattern(&arm.pattern, scrutinee_ty)?;
+                    Self::compile_checks(&plan.checks, value, func);
+                    func.instruction(&Instruction::If(BlockType::Result(result_ty)));
+                    for (name, path, ty) in &plan.bindings {
+                        Self::compile_load(value, path, func);
+                        let local_idx = locals.add_local(name, *ty);
+                        func.instruction(&Instruction::LocalSet(local_idx));
+                    }
+                    self.compile_expr_with_locals(&arm.body, locals, func)?;
+                    func.instruction(&Instruction::Else);
+                }
+                func.instruction(&Instruction::Unreachable);
+                for _ in arms {
+                    func.instruction(&Instruction::End);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Layouts of the variants of every enum in `ast`
+    fn variant_layouts(ast: &SourceFile) -> Result<HashMap<String, VariantLayout>> {
+        let mut layouts = HashMap::new();
+        for def in flux_sema::builtin_types().enums() {
+            for (tag, variant) in def.variants.iter().enumerate() {
+                let layout = VariantLayout {
+                    tag: tag as i32,
+                    fields: vec![None; variant.fields.len()],
+                };
+                layouts.insert(variant.name.clone(), layout);
+            }
+        }
+        for def in ast.enums() {
+            for (tag, variant) in def.variants.iter().enumerate() {
+                let fields = variant
+                    .fields
+                    .iter()
+                    .map(|ty| match ty {
+                        Type::Named { name, .. }
+                            if def.type_params.iter().any(|param| param.name == *name) =>
+                        {
+                            Err(FluxError::WasmError {
+                                message: format!(
+                                    "Enum `{}` stores a value of its type parameter `{}`, \
+                                     which has no runtime representation yet",
+                                    def.name, name
+                                ),
+                            })
+                        }
+                        _ => Self::field_val_type(ty, ast, 0).map(Some),
+                    })
+                    .collect::<Result<_>>()?;
+                let layout = VariantLayout {
+                    tag: tag as i32,
+                    fields,
+                };
+                layouts.insert(variant.name.clone(), layout);
+            }
+        }
+        Ok(layouts)
+    }
+
+    /// WASM type of a variant field of type `ty`. Enums are pointers to
+    /// their cell; aliases are expanded, at most `depth` levels deep.
+    fn field_val_type(ty: &Type, ast: &SourceFile, depth: usize) -> Result<ValType> {
+        let unsupported = || FluxError::WasmError {
+            message: "Records have no runtime representation yet".to_string(),
+        };
+        Ok(match ty {
+            Type::Int(_) => ValType::I64,
+            Type::Bool(_) | Type::String(_) => ValType::I32,
+            Type::Float(_) => ValType::F64,
+            Type::List { .. } | Type::Tuple { .. } | Type::Function { .. } => ValType::I32,
+            Type::Record { .. } => return Err(unsupported()),
+            Type::Named { name, .. } => match name.as_str() {
+                "Date" => ValType::I32,
+                "Time" | "DateTime" | "Timestamp" | "Duration" => ValType::I64,
+                "Column" => ValType::I32,
+                _ if ast
+                    .enums()
+                    .chain(flux_sema::builtin_types().enums())
+                    .any(|def| def.name == *name) =>
+                {
+                    ValType::I32
+                }
+                _ => match ast.type_aliases().find(|alias| alias.name == *name) {
+                    // Sema reports aliases that expand into themselves
+                    Some(alias) if depth < 64 => Self::field_val_type(&alias.ty, ast, depth + 1)?,
+                    Some(_) => return Err(unsupported()),
+                    None if ast.structs().any(|def| def.name == *name) => return Err(unsupported()),
+                    None => {
+                        return Err(FluxError::WasmError {
+                            message: format!("Unknown type: {}", name),
+                        })
+                    }
+                },
+            },
+        })
+    }
+
+    /// WASM types of the enums and aliases of `ast`, for resolving
+    /// annotations once the file itself is out of reach
+    fn named_val_types(ast: &SourceFile) -> HashMap<String, ValType> {
+        let mut types: HashMap<String, ValType> = flux_sema::builtin_types()
+            .enums()
+            .chain(ast.enums())
+            .map(|def| (def.name.clone(), ValType::I32))
+            .collect();
+        for alias in ast.type_aliases() {
+            if let Ok(ty) = Self::field_val_type(&alias.ty, ast, 1) {
+                types.insert(alias.name.clone(), ty);
+            }
+        }
+        types
+    }
+
+    /// WASM type of values of the type `ty` sema inferred
+    fn info_val_type(&self, ty: &TypeInfo) -> Result<ValType> {
+        match ty {
+            TypeInfo::Bool | TypeInfo::String | TypeInfo::Date => Ok(ValType::I32),
+            TypeInfo::Float => Ok(ValType::F64),
+            TypeInfo::Int
+            | TypeInfo::Time
+            | TypeInfo::DateTime
+            | TypeInfo::Timestamp
+            | TypeInfo::Duration => Ok(ValType::I64),
+            TypeInfo::Enum { .. }
+            | TypeInfo::List(_)
+            | TypeInfo::Column(_)
+            | TypeInfo::Tuple(_)
+            | TypeInfo::Function { .. } => Ok(ValType::I32),
+            TypeInfo::Named { name } if self.named_types.contains_key(name) => {
+                Ok(self.named_types[name])
+            }
+            // A type left open, like `x` in `let id = fn(x) { x }`, stands
+            // for values of any representation
+            TypeInfo::Var(_) => Err(FluxError::WasmError {
+                message: format!(
+                    "A value of the generic type {} has no single runtime representation; \
+                     add a type annotation",
+                    ty
+                ),
+            }),
+            TypeInfo::Record { .. } => Err(FluxError::WasmError {
+                message: "Records have no runtime representation yet".to_string(),
+            }),
+            _ => Err(FluxError::WasmError {
+                message: format!("Values of type {} are not supported yet", ty),
+            }),
+        }
+    }
+
+    /// Address of the cell of the string literal `value`, laid out in the
+    /// data segment the first time it is used
+    fn string_literal(&mut self, value: &str) -> i32 {
+        if let Some(address) = self.strings.get(value) {
+            return *address;
+        }
+        let address = HEAP_START + self.string_data.len() as i32;
+        self.string_data
+            .extend_from_slice(&(value.len() as u32).to_le_bytes());
+        self.string_data.extend_from_slice(value.as_bytes());
+        // Keep the next cell 8-byte aligned
+        let padded = self.string_data.len().next_multiple_of(8);
+        self.string_data.resize(padded, 0);
+        self.strings.insert(value.to_string(), address);
+        address
+    }
+
+    /// Compare the two strings on top of the stack byte by byte, leaving 1
+    /// when they are equal
+    fn compile_string_eq(locals: &mut LocalContext, func: &mut Code) {
+        let right = locals.add_temp(ValType::I32);
+        let left = locals.add_temp(ValType::I32);
+        let remaining = locals.add_temp(ValType::I32);
+        func.instruction(&Instruction::LocalSet(right));
+        func.instruction(&Instruction::LocalSet(left));
+
+        func.instruction(&Instruction::Block(BlockType::Result(ValType::I32)));
+        func.instruction(&Instruction::LocalGet(left));
+        func.instruction(&Self::load(ValType::I32, 0));
+        func.instruction(&Instruction::LocalTee(remaining));
+        func.instruction(&Instruction::LocalGet(right));
+        func.instruction(&Self::load(ValType::I32, 0));
+        func.instruction(&Instruction::I32Ne);
+        func.instruction(&Instruction::If(BlockType::Empty));
+        func.instruction(&Instruction::I32Const(0));
+        func.instruction(&Instruction::Br(1));
+        func.instruction(&Instruction::End);
+
+        // Compare from the last byte down; every byte matching means equal
+        func.instruction(&Instruction::Loop(BlockType::Empty));
+        func.instruction(&Instruction::LocalGet(remaining));
+        func.instruction(&Instruction::I32Eqz);
+        func.instruction(&Instruction::If(BlockType::Empty));
+        func.instruction(&Instruction::I32Const(1));
+        func.instruction(&Instruction::Br(2));
+        func.instruction(&Instruction::End);
+        func.instruction(&Instruction::LocalGet(remaining));
+        func.instruction(&Instruction::I32Const(1));
+        func.instruction(&Instruction::I32Sub);
+        func.instruction(&Instruction::LocalSet(remaining));
+        Self::compile_byte(left, remaining, func);
+        Self::compile_byte(right, remaining, func);
+        func.instruction(&Instruction::I32Ne);
+        func.instruction(&Instruction::If(BlockType::Empty));
+        func.instruction(&Instruction::I32Const(0));
+        func.instruction(&Instruction::Br(2));
+        func.instruction(&Instruction::End);
+        func.instruction(&Instruction::Br(0));
+        func.instruction(&Instruction::End);
+        func.instruction(&Instruction::Unreachable);
+        func.instruction(&Instruction::End);
+    }
+
+    /// Move `HEAP` to the address on top of the stack, growing memory by
+    /// as many pages as it takes to hold everything below it. Trap when
+    /// memory cannot grow.
+    fn compile_move_heap(locals: &mut LocalContext, func: &mut Code) {
+        let end = locals.add_temp(ValType::I32);
+        let size = locals.add_temp(ValType::I32);
+        func.instruction(&Instruction::LocalTee(end));
+        func.instruction(&Instruction::GlobalSet(HEAP));
+        func.instruction(&Instruction::LocalGet(end));
+        func.instruction(&Instruction::MemorySize(0));
+        func.instruction(&Instruction::I32Const(16));
+        func.instruction(&Instruction::I32Shl);
+        func.instruction(&Instruction::LocalTee(size));
+        func.instruction(&Instruction::I32GtU);
+        func.instruction(&Instruction::If(BlockType::Empty));
+        // The missing bytes, rounded up to whole 64 KiB pages
+        func.instruction(&Instruction::LocalGet(end));
+        func.instruction(&Instruction::LocalGet(size));
+        func.instruction(&Instruction::I32Sub);
+        func.instruction(&Instruction::I32Const(0xFFFF));
+        func.instruction(&Instruction::I32Add);
+        func.instruction(&Instruction::I32Const(16));
+        func.instruction(&Instruction::I32ShrU);
+        func.instruction(&Instruction::MemoryGrow(0));
+        func.instruction(&Instruction::I32Const(-1));
+        func.instruction(&Instruction::I32Eq);
+        func.instruction(&Instruction::If(BlockType::Empty));
+        func.instruction(&Instruction::Unreachable);
+        func.instruction(&Instruction::End);
+        func.instruction(&Instruction::End);
+    }
+
+    /// Reserve `size` bytes of the heap and return a new temporary local
+    /// holding their address
+    fn compile_alloc(size: i32, locals: &mut LocalContext, func: &mut Code) -> u32 {
+        let cell = locals.add_temp(ValType::I32);
+        func.instruction(&Instruction::GlobalGet(HEAP));
+        func.instruction(&Instruction::LocalSet(cell));
+        func.instruction(&Instruction::GlobalGet(HEAP));
+        func.instruction(&Instruction::I32Const(size));
+        func.instruction(&Instruction::I32Add);
+        Self::compile_move_heap(locals, func);
+        cell
+    }
+
+    /// Reserve room for a list or column of as many elements as the local
+    /// `length` says and return a new temporary local holding its address.
+    /// Its length is left for the caller to store.
+    fn compile_alloc_elements(length: u32, locals: &mut LocalContext, func: &mut Code) -> u32 {
+        let cell = locals.add_temp(ValType::I32);
+        func.instruction(&Instruction::GlobalGet(HEAP));
+        func.instruction(&Instruction::LocalSet(cell));
+        func.instruction(&Instruction::GlobalGet(HEAP));
+        func.instruction(&Instruction::I32Const(ELEMENTS_OFFSET as i32));
+        func.instruction(&Instruction::LocalGet(length));
+        func.instruction(&Instruction::I32Const(3));
+        func.instruction(&Instruction::I32Shl);
+        func.instruction(&Instruction::I32Add);
+        func.instruction(&Instruction::I32Add);
+        Self::compile_move_heap(locals, func);
+        cell
+    }
+
+    /// Reserve room for a string of as many bytes as the local `length`
+    /// says, store its length and return a new temporary local holding its
+    /// address. Cells stay 8-byte aligned.
+    fn compile_alloc_string(length: u32, locals: &mut LocalContext, func: &mut Code) -> u32 {
+        let cell = locals.add_temp(ValType::I32);
+        func.instruction(&Instruction::GlobalGet(HEAP));
+        func.instruction(&Instruction::LocalSet(cell));
+        func.instruction(&Instruction::GlobalGet(HEAP));
+        func.instruction(&Instruction::LocalGet(length));
+        func.instruction(&Instruction::I32Const(BYTES_OFFSET as i32 + 7));
+        func.instruction(&Instruction::I32Add);
+        func.instruction(&Instruction::I32Const(!7));
+        func.instruction(&Instruction::I32And);
+        func.instruction(&Instruction::I32Add);
+        Self::compile_move_heap(locals, func);
+        func.instruction(&Instruction::LocalGet(cell));
+        func.instruction(&Instruction::LocalGet(length));
+        func.instruction(&Self::store(ValType::I32, 0));
+        cell
+    }
+
+    /// Index of the function type `(params) -> results`, added if new
+    fn signature_index(&mut self, params: Vec<ValType>, results: Vec<ValType>) -> u32 {
+        let signature = (params, results);
+        match self.signatures.iter().position(|known| *known == signature) {
+            Some(index) => index as u32,
+            None => {
+                self.signatures.push(signature);
+                self.signatures.len() as u32 - 1
+            }
+        }
+    }
+
+    /// Signature of the closure `expr` evaluates to, when sema typed it as
+    /// a function
+    fn closure_type(&self, expr: &Expr) -> Result<Option<ClosureType>> {
+        match self.expr_types.get(&expr.span()) {
+            Some(TypeInfo::Function { params, ret }) => Ok(Some(ClosureType {
+                params: params
+                    .iter()
+                    .map(|ty| self.info_val_type(ty))
+                    .collect::<Result<_>>()?,
+                ret: self.info_val_type(ret)?,
+            })),
+            _ => Ok(None),
+        }
+    }
+
+    /// Closure conversion: lift the lambda into a function taking a pointer
+    /// to its environment before its parameters, and leave the closure on
+    /// the stack. A closure is a cell holding the table index of its lifted
+    /// function at offset 0 and the captured values in 8-byte slots after
+    /// it, laid out like an enum's fields. The cell is its own environment.
+    fn compile_lambda(
+        &mut self,
+        lambda: &Expr,
+        params: &[&str],
+        body: &Expr,
+        locals: &mut LocalContext,
+        func: &mut Code,
+    ) -> Result<()> {
+        let Some(closure) = self.closure_type(lambda)? else {
+            return Err(FluxError::WasmError {
+                message: "Lambda has no inferred function type".to_string(),
+            });
+        };
+        // Bindings never change, so captures are copied into the cell
+        let captures: Vec<(String, u32)> = lambda
+            .free_variables()
+            .into_iter()
+            .filter_map(|name| locals.get(&name).map(|idx| (name, idx)))
+            .collect();
+
+        let mut inner = LocalContext::new();
+        let env = inner.add_temp(ValType::I32);
+        for (name, ty) in params.iter().zip(&closure.params) {
+            inner.add_param(name, *ty);
+        }
+
+        let mut lifted = Code::new();
+        for (index, (name, idx)) in captures.iter().enumerate() {
+            let ty = locals.ty(*idx);
+            let captured = inner.add_local(name, ty);
+            lifted.instruction(&Instruction::LocalGet(env));
+            lifted.instruction(&Self::load(ty, VariantLayout::field_offset(index)));
+            lifted.instruction(&Instruction::LocalSet(captured));
+        }
+        self.compile_expr_with_locals(body, &mut inner, &mut lifted)?;
+        let lifted = lifted.finish(&inner, 1 + params.len());
+
+        let mut lifted_params = vec![ValType::I32];
+        lifted_params.extend(&closure.params);
+        let type_index = self.signature_index(lifted_params, vec![closure.ret]);
+        let table_index = self.lifted.len() as i32;
+        self.lifted.push(LiftedFunction {
+            type_index,
+            body: lifted,
+        });
+
+        let cell = Self::compile_alloc(8 + 8 * captures.len() as i32, locals, func);
+        func.instruction(&Instruction::LocalGet(cell));
+        func.instruction(&Instruction::I32Const(table_index));
+        func.instruction(&Self::store(ValType::I32, 0));
+        for (index, (_, idx)) in captures.iter().enumerate() {
+            func.instruction(&Instruction::LocalGet(cell));
+            func.instruction(&Instruction::LocalGet(*idx));
+            func.instruction(&Self::store(
+                locals.ty(*idx),
+                VariantLayout::field_offset(index),
+            ));
+        }
+        func.instruction(&Instruction::LocalGet(cell));
+        Ok(())
+    }
+
+    /// Leave a closure calling the function `name` of the module on the
+    /// stack. Its lifted function, lifted once per function, drops the
+    /// environment and forwards its arguments; the closure captures nothing.
+    fn compile_function_value(&mut self, name: &str, locals: &mut LocalContext, func: &mut Code) {
+        let table_index = match self.forwarders.get(name) {
+            Some(table_index) => *table_index,
+            None => {
+                let function = self.functions[name].clone();
+                let mut forwarder = Function::new([]);
+                for param in 0..function.params.len() {
+                    forwarder.instruction(&Instruction::LocalGet(1 + param as u32));
+                }
+                forwarder.instruction(&Instruction::Call(function.index));
+                forwarder.instruction(&Instruction::End);
+
+                let mut params = vec![ValType::I32];
+                params.extend(&function.params);
+                let type_index = self.signature_index(params, vec![function.ret]);
+                let table_index = self.lifted.len() as i32;
+                self.lifted.push(LiftedFunction {
+                    type_index,
+                    body: forwarder,
+                });
+                self.forwarders.insert(name.to_string(), table_index);
+                table_index
+            }
+        };
+
+        let cell = Self::compile_alloc(8, locals, func);
+        func.instruction(&Instruction::LocalGet(cell));
+        func.instruction(&Instruction::I32Const(table_index));
+        func.instruction(&Self::store(ValType::I32, 0));
+        func.instruction(&Instruction::LocalGet(cell));
+    }
+
+    /// Call a closure: its lifted function gets the closure as its
+    /// environment, then the arguments
+    fn compile_closure_call(
+        &mut self,
+        callee: &Expr,
+        closure: &ClosureType,
+        args: &[Expr],
+        locals: &mut LocalContext,
+        func: &mut Code,
+    ) -> Result<()> {
+        if args.len() != closure.params.len() {
+            return Err(FluxError::WasmError {
+                message: format!(
+                    "Closure expects {} argument(s), found {}",
+                    closure.params.len(),
+                    args.len()
+                ),
+            });
+        }
+
+        self.compile_expr_with_locals(callee, locals, func)?;
+        let cell = locals.add_temp(ValType::I32);
+        func.instruction(&Instruction::LocalSet(cell));
+        func.instruction(&Instruction::LocalGet(cell));
+        for arg in args {
+            self.compile_expr_with_locals(arg, locals, func)?;
+        }
+        let mut params = vec![ValType::I32];
+        params.extend(&closure.params);
+        let type_index = self.signature_index(params, vec![closure.ret]);
+        Self::compile_call_indirect(cell, type_index, func);
+        Ok(())
+    }
+
+    /// Body of an instance of type `ty` of the native function `name` of
+    /// `std.column`, looping over the column in its first parameter.
+    /// Columns share the layout of lists.
+    fn compile_native(
+        &mut self,
+        name: &str,
+        ty: &TypeInfo,
+        locals: &mut LocalContext,
+        func: &mut Code,
+    ) -> Result<()> {
+        let TypeInfo::Function { params, ret } = ty else {
+            unreachable!("every instance has a function type");
+        };
+        let values = 0;
+        let length = locals.add_temp(ValType::I32);
+        func.instruction(&Instruction::LocalGet(values));
+        func.instruction(&Self::load(ValType::I32, 0));
+        func.instruction(&Instruction::LocalSet(length));
+        let index = locals.add_temp(ValType::I32);
+
+        match (name, params.as_slice()) {
+            // The values whose row of `keep` is true, copied slot by slot
+            ("filter", [_, _]) => {
+                let keep = 1;
+                func.instruction(&Instruction::LocalGet(keep));
+                func.instruction(&Self::load(ValType::I32, 0));
+                func.instruction(&Instruction::LocalGet(length));
+                func.instruction(&Instruction::I32Ne);
+                func.instruction(&Instruction::If(BlockType::Empty));
+                func.instruction(&Instruction::Unreachable);
+                func.instruction(&Instruction::End);
+
+                let out = Self::compile_alloc_elements(length, locals, func);
+                let kept = locals.add_temp(ValType::I32);
+                Self::compile_loop(index, length, func, |func| {
+                    Self::compile_element_address(keep, index, func);
+                    func.instruction(&Self::load(ValType::I32, ELEMENTS_OFFSET));
+                    func.instruction(&Instruction::If(BlockType::Empty));
+                    Self::compile_element_address(out, kept, func);
+                    Self::compile_element_address(values, index, func);
+                    func.instruction(&Self::load(ValType::I64, ELEMENTS_OFFSET));
+                    func.instruction(&Self::store(ValType::I64, ELEMENTS_OFFSET));
+                    func.instruction(&Instruction::LocalGet(kept));
+                    func.instruction(&Instruction::I32Const(1));
+                    func.instruction(&Instruction::I32Add);
+                    func.instruction(&Instruction::LocalSet(kept));
+                    func.instruction(&Instruction::End);
+                });
+                func.instruction(&Instruction::LocalGet(out));
+                func.instruction(&Instruction::LocalGet(kept));
+                func.instruction(&Self::store(ValType::I32, 0));
+                func.instruction(&Instruction::LocalGet(out));
+            }
+            // `f` applied to every value
+            ("map", [column, _]) => {
+                let f = 1;
+                let elem = self.elem_val_type(column)?;
+                let result = self.elem_val_type(ret)?;
+                let type_index = self.signature_index(vec![ValType::I32, elem], vec![result]);
+
+                let out = Self::compile_alloc_elements(length, locals, func);
+                func.instruction(&Instruction::LocalGet(out));
+                func.instruction(&Instruction::LocalGet(length));
+                func.instruction(&Self::store(ValType::I32, 0));
+                Self::compile_loop(index, length, func, |func| {
+                    Self::compile_element_address(out, index, func);
+                    func.instruction(&Instruction::LocalGet(f));
+                    Self::compile_element_address(values, index, func);
+                    func.instruction(&Self::load(elem, ELEMENTS_OFFSET));
+                    Self::compile_call_indirect(f, type_index, func);
+                    func.instruction(&Self::store(result, ELEMENTS_OFFSET));
+                });
+                func.instruction(&Instruction::LocalGet(out));
+            }
+            // The values folded into the accumulator `init` with `f`
+            ("reduce", [column, _, _]) => {
+                let (f, acc) = (1, 2);
+                let elem = self.elem_val_type(column)?;
+                let acc_ty = self.info_val_type(ret)?;
+                let type_index =
+                    self.signature_index(vec![ValType::I32, acc_ty, elem], vec![acc_ty]);
+
+                Self::compile_loop(index, length, func, |func| {
+                    func.instruction(&Instruction::LocalGet(f));
+                    func.instruction(&Instruction::LocalGet(acc));
+                    Self::compile_element_address(values, index, func);
+                    func.instruction(&Self::load(elem, ELEMENTS_OFFSET));
+                    Self::compile_call_indirect(f, type_index, func);
+                    func.instruction(&Instruction::LocalSet(acc));
+                });
+                func.instruction(&Instruction::LocalGet(acc));
+            }
+            ("count", [_]) => {
+                func.instruction(&Instruction::LocalGet(length));
+                func.instruction(&Instruction::I64ExtendI32U);
+            }
+            // Strings share the length at offset 0 with columns, in bytes.
+            // Characters are counted by the bytes that start one, every
+            // byte but a UTF-8 continuation byte `0b10xxxxxx`.
+            ("length", [TypeInfo::String]) => {
+                let chars = locals.add_temp(ValType::I32);
+                Self::compile_loop(index, length, func, |func| {
+                    func.instruction(&Instruction::LocalGet(chars));
+                    Self::compile_byte(values, index, func);
+                    func.instruction(&Instruction::I32Const(0xC0));
+                    func.instruction(&Instruction::I32And);
+                    func.instruction(&Instruction::I32Const(0x80));
+                    func.instruction(&Instruction::I32Ne);
+                    func.instruction(&Instruction::I32Add);
+                    func.instruction(&Instruction::LocalSet(chars));
+                });
+                func.instruction(&Instruction::LocalGet(chars));
+                func.instruction(&Instruction::I64ExtendI32U);
+            }
+            // A new string holding the bytes of `a` and then those of `b`
+            ("concat", [TypeInfo::String, TypeInfo::String]) => {
+                let b = 1;
+                let b_length = locals.add_temp(ValType::I32);
+                func.instruction(&Instruction::LocalGet(b));
+                func.instruction(&Self::load(ValType::I32, 0));
+                func.instruction(&Instruction::LocalSet(b_length));
+                let total = locals.add_temp(ValType::I32);
+                func.instruction(&Instruction::LocalGet(length));
+                func.instruction(&Instruction::LocalGet(b_length));
+                func.instruction(&Instruction::I32Add);
+                func.instruction(&Instruction::LocalSet(total));
+
+                let out = Self::compile_alloc_string(total, locals, func);
+                Self::compile_loop(index, length, func, |func| {
+                    Self::compile_byte_address(out, index, func);
+                    Self::compile_byte(values, index, func);
+                    func.instruction(&Instruction::I32Store8(Self::mem_arg(BYTES_OFFSET, 0)));
+                });
+                // `b` goes where `a` ends
+                let tail = locals.add_temp(ValType::I32);
+                func.instruction(&Instruction::LocalGet(out));
+                func.instruction(&Instruction::LocalGet(length));
+                func.instruction(&Instruction::I32Add);
+                func.instruction(&Instruction::LocalSet(tail));
+                Self::compile_loop(index, b_length, func, |func| {
+                    Self::compile_byte_address(tail, index, func);
+                    Self::compile_byte(b, index, func);
+                    func.instruction(&Instruction::I32Store8(Self::mem_arg(BYTES_OFFSET, 0)));
+                });
+                func.instruction(&Instruction::LocalGet(out));
+            }
+            // Whether the bytes of `part` appear in `s` from some start
+            ("contains", [TypeInfo::String, TypeInfo::String]) => {
+                let part = 1;
+                let part_length = locals.add_temp(ValType::I32);
+                func.instruction(&Instruction::LocalGet(part));
+                func.instruction(&Self::load(ValType::I32, 0));
+                func.instruction(&Instruction::LocalSet(part_length));
+
+                func.instruction(&Instruction::Block(BlockType::Result(ValType::I32)));
+                func.instruction(&Instruction::LocalGet(part_length));
+                func.instruction(&Instruction::LocalGet(length));
+                func.instruction(&Instruction::I32GtU);
+                func.instruction(&Instruction::If(BlockType::Empty));
+                func.instruction(&Instruction::I32Const(0));
+                func.instruction(&Instruction::Br(1));
+                func.instruction(&Instruction::End);
+
+                let starts = locals.add_temp(ValType::I32);
+                func.instruction(&Instruction::LocalGet(length));
+                func.instruction(&Instruction::LocalGet(part_length));
+                func.instruction(&Instruction::I32Sub);
+                func.instruction(&Instruction::I32Const(1));
+                func.instruction(&Instruction::I32Add);
+                func.instruction(&Instruction::LocalSet(starts));
+                let start = locals.add_temp(ValType::I32);
+                let at = locals.add_temp(ValType::I32);
+                Self::compile_loop(start, starts, func, |func| {
+                    // Leave the block on the first byte that differs; past
+                    // the last one, `part` is found
+                    func.instruction(&Instruction::Block(BlockType::Empty));
+                    Self::compile_loop(index, part_length, func, |func| {
+                        func.instruction(&Instruction::LocalGet(start));
+                        func.instruction(&Instruction::LocalGet(index));
+                        func.instruction(&Instruction::I32Add);
+                        func.instruction(&Instruction::LocalSet(at));
+                        Self::compile_byte(values, at, func);
+                        Self::compile_byte(part, index, func);
+                        func.instruction(&Instruction::I32Ne);
+                        func.instruction(&Instruction::BrIf(2));
+                    });
+                    func.instruction(&Instruction::I32Const(1));
+                    func.instruction(&Instruction::Br(3));
+                    func.instruction(&Instruction::End);
+                });
+                func.instruction(&Instruction::I32Const(0));
+                func.instruction(&Instruction::End);
+            }
+            // A new string with the ASCII letters of `s` in the other case;
+            // other characters are kept as they are
+            (case @ ("to_upper" | "to_lower"), [TypeInfo::String]) => {
+                let (from, shift) = if case == "to_upper" {
+                    (b'a', -32)
+                } else {
+                    (b'A', 32)
+                };
+                let out = Self::compile_alloc_string(length, locals, func);
+                let byte = locals.add_temp(ValType::I32);
+                Self::compile_loop(index, length, func, |func| {
+                    Self::compile_byte_address(out, index, func);
+                    Self::compile_byte(values, index, func);
+                    func.instruction(&Instruction::LocalTee(byte));
+                    func.instruction(&Instruction::I32Const(from.into()));
+                    func.instruction(&Instruction::I32Sub);
+                    func.instruction(&Instruction::I32Const(26));
+                    func.instruction(&Instruction::I32LtU);
+                    func.instruction(&Instruction::If(BlockType::Result(ValType::I32)));
+                    func.instruction(&Instruction::LocalGet(byte));
+                    func.instruction(&Instruction::I32Const(shift));
+                    func.instruction(&Instruction::I32Add);
+                    func.instruction(&Instruction::Else);
+                    func.instruction(&Instruction::LocalGet(byte));
+                    func.instruction(&Instruction::End);
+                    func.instruction(&Instruction::I32Store8(Self::mem_arg(BYTES_OFFSET, 0)));
+                });
+                func.instruction(&Instruction::LocalGet(out));
+            }
+            (name, _) => {
                 return Err(FluxError::WasmError {
-                    message: "Function calls are not yet implemented".to_string(),
+                    message: format!(
+                        "Native function `{}` has no implementation in the WASM backend",
+                        name
+                    ),
                 });
             }
         }
         Ok(())
     }
 
+    /// WASM type of the elements of a column or list of type `ty`
+    fn elem_val_type(&self, ty: &TypeInfo) -> Result<ValType> {
+        match ty {
+            TypeInfo::Column(elem) | TypeInfo::List(elem) => self.info_val_type(elem),
+            _ => Err(FluxError::WasmError {
+                message: format!("Expected a column, found {}", ty),
+            }),
+        }
+    }
+
+    /// Run `body` for every `index` from 0 up to the local `length`
+    fn compile_loop(index: u32, length: u32, func: &mut Code, body: impl FnOnce(&mut Code)) {
+        func.instruction(&Instruction::I32Const(0));
+        func.instruction(&Instruction::LocalSet(index));
+        func.instruction(&Instruction::Block(BlockType::Empty));
+        func.instruction(&Instruction::Loop(BlockType::Empty));
+        func.instruction(&Instruction::LocalGet(index));
+        func.instruction(&Instruction::LocalGet(length));
+        func.instruction(&Instruction::I32GeU);
+        func.instruction(&Instruction::BrIf(1));
+        body(func);
+        func.instruction(&Instruction::LocalGet(index));
+        func.instruction(&Instruction::I32Const(1));
+        func.instruction(&Instruction::I32Add);
+        func.instruction(&Instruction::LocalSet(index));
+        func.instruction(&Instruction::Br(0));
+        func.instruction(&Instruction::End);
+        func.instruction(&Instruction::End);
+    }
+
+    /// Address of element `index` of the list or column `cell`, less
+    /// `ELEMENTS_OFFSET`, which loads and stores add
+    fn compile_element_address(cell: u32, index: u32, func: &mut Code) {
+        func.instruction(&Instruction::LocalGet(cell));
+        func.instruction(&Instruction::LocalGet(index));
+        func.instruction(&Instruction::I32Const(3));
+        func.instruction(&Instruction::I32Shl);
+        func.instruction(&Instruction::I32Add);
+    }
+
+    /// Address of byte `index` of the string `cell`, less `BYTES_OFFSET`,
+    /// which loads and stores add
+    fn compile_byte_address(cell: u32, index: u32, func: &mut Code) {
+        func.instruction(&Instruction::LocalGet(cell));
+        func.instruction(&Instruction::LocalGet(index));
+        func.instruction(&Instruction::I32Add);
+    }
+
+    /// Push byte `index` of the string `cell`
+    fn compile_byte(cell: u32, index: u32, func: &mut Code) {
+        Self::compile_byte_address(cell, index, func);
+        func.instruction(&Instruction::I32Load8U(Self::mem_arg(BYTES_OFFSET, 0)));
+    }
+
+    /// Call the closure in the local `closure`, whose environment and
+    /// arguments are on the stack, through the table
+    fn compile_call_indirect(closure: u32, type_index: u32, func: &mut Code) {
+        func.instruction(&Instruction::LocalGet(closure));
+        func.instruction(&Self::load(ValType::I32, 0));
+        func.instruction(&Instruction::CallIndirect {
+            type_index,
+            table_index: 0,
+        });
+    }
+
+    /// The function of the module a call calls, if its callee names one
+    /// that no local shadows
+    fn called_function<'e>(&self, callee: &'e Expr, locals: &LocalContext) -> Option<&'e str> {
+        match callee {
+            Expr::Var { name, .. }
+                if locals.get(name).is_none() && self.functions.contains_key(name) =>
+            {
+                Some(name)
+            }
+            _ => None,
+        }
+    }
+
+    /// Call the function `name` of the module with `args`, evaluated left
+    /// to right as sema checks them
+    fn compile_call(
+        &mut self,
+        name: &str,
+        args: &[Expr],
+        locals: &mut LocalContext,
+        func: &mut Code,
+    ) -> Result<()> {
+        let function = self.functions[name].clone();
+        if args.len() != function.params.len() {
+            return Err(FluxError::WasmError {
+                message: format!(
+                    "`{}` expects {} argument(s), found {}",
+                    name,
+                    function.params.len(),
+                    args.len()
+                ),
+            });
+        }
+        for arg in args {
+            self.compile_expr_with_locals(arg, locals, func)?;
+        }
+        func.instruction(&Instruction::Call(function.index));
+        Ok(())
+    }
+
+    /// The variant a call constructs, if its callee names one
+    fn constructor_name<'e>(&self, callee: &'e Expr) -> Option<&'e str> {
+        match callee {
+            Expr::Var { name, .. } if self.variants.contains_key(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// Allocate a cell for variant `name`, store its tag and fields and
+    /// leave a pointer to it on the stack
+    fn compile_constructor(
+        &mut self,
+        name: &str,
+        args: &[Expr],
+        locals: &mut LocalContext,
+        func: &mut Code,
+    ) -> Result<()> {
+        let layout = self.variants[name].clone();
+        if args.len() != layout.fields.len() {
+            return Err(FluxError::WasmError {
+                message: format!(
+                    "`{}` expects {} field(s), found {}",
+                    name,
+                    layout.fields.len(),
+                    args.len()
+                ),
+            });
+        }
+
+        // The cell is allocated before the fields are evaluated, so fields
+        // that build cells of their own get the next ones
+        let cell = Self::compile_alloc(layout.size(), locals, func);
+
+        func.instruction(&Instruction::LocalGet(cell));
+        func.instruction(&Instruction::I32Const(layout.tag));
+        func.instruction(&Self::store(ValType::I32, 0));
+        for (index, (arg, ty)) in args.iter().zip(&layout.fields).enumerate() {
+            let ty = match ty {
+                Some(ty) => *ty,
+                None => self.expr_val_type(arg)?,
+            };
+            func.instruction(&Instruction::LocalGet(cell));
+            self.compile_expr_with_locals(arg, locals, func)?;
+            func.instruction(&Self::store(ty, VariantLayout::field_offset(index)));
+        }
+        func.instruction(&Instruction::LocalGet(cell));
+        Ok(())
+    }
+
+    /// Checks and bindings of a pattern matching a value of type `ty`
+    fn plan_pattern(&self, pattern: &Pattern, ty: ValType) -> Result<PatternPlan> {
+        let mut plan = PatternPlan::default();
+        self.plan_subpattern(pattern, &Vec::new(), ty, &mut plan)?;
+        Ok(plan)
+    }
+
+    fn plan_subpattern(
+        &self,
+        pattern: &Pattern,
+        path: &Path,
+        ty: ValType,
+        plan: &mut PatternPlan,
+    ) -> Result<()> {
+        match pattern {
+            Pattern::Wildcard { .. } => {}
+            Pattern::Name { name, .. } => match self.variants.get(name) {
+                Some(layout) => plan.checks.push((path.clone(), Check::Tag(layout.tag))),
+                None => plan.bindings.push((name.clone(), path.clone(), ty)),
+            },
+            Pattern::Int { value, .. } => {
+                plan.checks.push((path.clone(), Check::Int(*value)));
+            }
+            Pattern::Bool { value, .. } => {
+                plan.checks.push((path.clone(), Check::Bool(*value)));
+            }
+            Pattern::String { .. } => {
+                return Err(FluxError::WasmError {
+                    message: "Matching on strings is not supported yet".to_string(),
+                });
+            }
+            Pattern::Variant { name, args, .. } => {
+                let layout = self
+                    .variants
+                    .get(name)
+                    .ok_or_else(|| FluxError::WasmError {
+                        message: format!("Unknown variant: {}", name),
+                    })?;
+                if args.len() != layout.fields.len() {
+                    return Err(FluxError::WasmError {
+                        message: format!(
+                            "`{}` has {} field(s), the pattern gives {}",
+                            name,
+                            layout.fields.len(),
+                            args.len()
+                        ),
+                    });
+                }
+                plan.checks.push((path.clone(), Check::Tag(layout.tag)));
+                for (index, (arg, field_ty)) in args.iter().zip(&layout.fields).enumerate() {
+                    let field_ty = match field_ty {
+                        Some(ty) => *ty,
+                        None => self.span_val_type(arg.span())?,
+                    };
+                    let mut field_path = path.clone();
+                    field_path.push((VariantLayout::field_offset(index), field_ty));
+                    self.plan_subpattern(arg, &field_path, field_ty, plan)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Push 1 when every check passes on the value in local `value`, 0
+    /// otherwise. Checks short-circuit, so a field is only loaded from a
+    /// cell whose tag matched.
+    fn compile_checks(checks: &[(Path, Check)], value: u32, func: &mut Code) {
+        let compile_check = |(path, check): &(Path, Check), func: &mut Code| {
+            Self::compile_load(value, path, func);
+            match check {
+                Check::Tag(tag) => {
+                    func.instruction(&Self::load(ValType::I32, 0));
+                    func.instruction(&Instruction::I32Const(*tag));
+                    func.instruction(&Instruction::I32Eq);
+                }
+                Check::Int(expected) => {
+                    func.instruction(&Instruction::I64Const(*expected));
+                    func.instruction(&Instruction::I64Eq);
+                }
+                Check::Bool(expected) => {
+                    func.instruction(&Instruction::I32Const(i32::from(*expected)));
+                    func.instruction(&Instruction::I32Eq);
+                }
+            }
+        };
+
+        let Some((first, rest)) = checks.split_first() else {
+            func.instruction(&Instruction::I32Const(1));
+            return;
+        };
+        compile_check(first, func);
+        for check in rest {
+            func.instruction(&Instruction::If(BlockType::Result(ValType::I32)));
+            compile_check(check, func);
+            func.instruction(&Instruction::Else);
+            func.instruction(&Instruction::I32Const(0));
+            func.instruction(&Instruction::End);
+        }
+    }
+
+    /// Push the value `path` leads to from the value in local `value`
+    fn compile_load(value: u32, path: &Path, func: &mut Code) {
+        func.instruction(&Instruction::LocalGet(value));
+        for (offset, ty) in path {
+            func.instruction(&Self::load(*ty, *offset));
+        }
+    }
+
+    fn load(ty: ValType, offset: u64) -> Instruction<'static> {
+        match ty {
+            ValType::I64 => Instruction::I64Load(Self::mem_arg(offset, 3)),
+            ValType::F64 => Instruction::F64Load(Self::mem_arg(offset, 3)),
+            _ => Instruction::I32Load(Self::mem_arg(offset, 2)),
+        }
+    }
+
+    fn store(ty: ValType, offset: u64) -> Instruction<'static> {
+        match ty {
+            ValType::I64 => Instruction::I64Store(Self::mem_arg(offset, 3)),
+            ValType::F64 => Instruction::F64Store(Self::mem_arg(offset, 3)),
+            _ => Instruction::I32Store(Self::mem_arg(offset, 2)),
+        }
+    }
+
+    fn zero(ty: ValType) -> Instruction<'static> {
+        match ty {
+            ValType::I64 => Instruction::I64Const(0),
+            ValType::F64 => Instruction::F64Const(0.0),
+            _ => Instruction::I32Const(0),
+        }
+    }
+
+    fn mem_arg(offset: u64, align: u32) -> MemArg {
+        MemArg {
+            offset,
+            align,
+            memory_index: 0,
+        }
+    }
+
+    /// Lower arithmetic with a temporal operand. Dates are whole days, so a
+    /// Duration moves them by its whole days; times of day wrap at midnight.
+    fn compile_temporal_arithmetic(
+        &mut self,
+        op: BinOp,
+        left: &Expr,
+        right: &Expr,
+        locals: &mut LocalContext,
+        func: &mut Code,
+    ) -> Result<()> {
+        use TemporalKind::{Date, Duration, Time};
+        // A Date moves by whole days; sema rejects durations not known to be
+        let days = |func: &mut Code| {
+            func.instruction(&Instruction::I64Const(NANOS_PER_DAY));
+            func.instruction(&Instruction::I64DivS);
+            func.instruction(&Instruction::I32WrapI64);
+        };
+
+        match (op, self.temporal_kind(left), self.temporal_kind(right)) {
+            (BinOp::Add | BinOp::Sub, Some(Date), Some(Duration)) => {
+                self.compile_expr_with_locals(left, locals, func)?;
+                self.compile_expr_with_locals(right, locals, func)?;
+                days(func);
+                func.instruction(&Self::binary_instruction(op, ValType::I32));
+            }
+            (BinOp::Add, Some(Duration), Some(Date)) => {
+                self.compile_expr_with_locals(left, locals, func)?;
+                days(func);
+                self.compile_expr_with_locals(right, locals, func)?;
+                func.instruction(&Instruction::I32Add);
+            }
+            (BinOp::Sub, Some(Date), Some(Date)) => {
+                self.compile_expr_with_locals(left, locals, func)?;
+                self.compile_expr_with_locals(right, locals, func)?;
+                func.instruction(&Instruction::I32Sub);
+                func.instruction(&Instruction::I64ExtendI32S);
+                func.instruction(&Instruction::I64Const(NANOS_PER_DAY));
+                func.instruction(&Instruction::I64Mul);
+            }
+            (BinOp::Add | BinOp::Sub, Some(Time), Some(Duration))
+            | (BinOp::Add, Some(Duration), Some(Time)) => {
+                self.compile_expr_with_locals(left, locals, func)?;
+                self.compile_expr_with_locals(right, locals, func)?;
+                func.instruction(&Self::binary_instruction(op, ValType::I64));
+                // rem_euclid(NANOS_PER_DAY)
+                func.instruction(&Instruction::I64Const(NANOS_PER_DAY));
+                func.instruction(&Instruction::I64RemS);
+                func.instruction(&Instruction::I64Const(NANOS_PER_DAY));
+                func.instruction(&Instruction::I64Add);
+                func.instruction(&Instruction::I64Const(NANOS_PER_DAY));
+                func.instruction(&Instruction::I64RemS);
+            }
+            // Everything else is nanoseconds on both sides, or a Duration
+            // scaled by an int
+            _ => {
+                self.compile_expr_with_locals(left, locals, func)?;
+                self.compile_expr_with_locals(right, locals, func)?;
+                func.instruction(&Self::binary_instruction(op, ValType::I64));
+            }
+        }
+        Ok(())
+    }
+
+    /// Constant for a temporal literal. Dates are i32 days since 1970-01-01;
+    /// times of day are i64 nanoseconds since midnight; DateTimes and
+    /// Timestamps are i64 nanoseconds since the Unix epoch, UTC; Durations are
+    /// i64 nanoseconds.
+    fn temporal_const(value: &Temporal) -> Result<Instruction<'static>> {
+        let instant = |date: &flux_syntax::temporal::Date, time: &flux_syntax::temporal::Time| {
+            date.days_since_epoch() * NANOS_PER_DAY + time.nanos_since_midnight()
+        };
+        Ok(match value {
+            Temporal::Date(date) => Instruction::I32Const(date.days_since_epoch() as i32),
+            Temporal::Time(time) => Instruction::I64Const(time.nanos_since_midnight()),
+            Temporal::Timestamp { date, time } => Instruction::I64Const(instant(date, time)),
+            Temporal::DateTime { date, time, zone } => {
+                let offset = utc_offset(zone, date, time).ok_or_else(|| FluxError::WasmError {
+                    message: format!("Unknown time zone `{}`", zone),
+                })?;
+                Instruction::I64Const(instant(date, time) - offset * NANOS_PER_SECOND)
+            }
+            Temporal::Duration(duration) => {
+                if duration.months != 0 {
+                    return Err(FluxError::WasmError {
+                        message: "Durations in months or years have no fixed length and no \
+                                  runtime representation yet"
+                            .to_string(),
+                    });
+                }
+                Instruction::I64Const(duration.days * NANOS_PER_DAY + duration.nanos)
+            }
+        })
+    }
+
+    /// Temporal type of an expression, as sema inferred it
+    fn temporal_kind(&self, expr: &Expr) -> Option<TemporalKind> {
+        self.expr_types.get(&expr.span())?.temporal_kind()
+    }
+
+    /// Select the instruction for an arithmetic or comparison operator given
+    /// the (shared) type of its operands
+    fn binary_instruction(op: BinOp, operand_ty: ValType) -> Instruction<'static> {
+        let is_float = operand_ty == ValType::F64;
+        let is_i64 = operand_ty == ValType::I64;
+        match op {
+            BinOp::Add if is_float => Instruction::F64Add,
+            BinOp::Add if is_i64 => Instruction::I64Add,
+            BinOp::Add => Instruction::I32Add,
+            BinOp::Sub if is_float => Instruction::F64Sub,
+            BinOp::Sub if is_i64 => Instruction::I64Sub,
+            BinOp::Sub => Instruction::I32Sub,
+            BinOp::Mul if is_float => Instruction::F64Mul,
+            BinOp::Mul if is_i64 => Instruction::I64Mul,
+            BinOp::Mul => Instruction::I32Mul,
+            BinOp::Div if is_float => Instruction::F64Div,
+            BinOp::Div if is_i64 => Instruction::I64DivS,
+            BinOp::Div => Instruction::I32DivS,
+            BinOp::Eq if is_float => Instruction::F64Eq,
+            BinOp::Eq if is_i64 => Instruction::I64Eq,
+            BinOp::Eq => Instruction::I32Eq,
+            BinOp::NotEq if is_float => Instruction::F64Ne,
+            BinOp::NotEq if is_i64 => Instruction::I64Ne,
+            BinOp::NotEq => Instruction::I32Ne,
+            BinOp::Lt if is_float => Instruction::F64Lt,
+            BinOp::Lt if is_i64 => Instruction::I64LtS,
+            BinOp::Lt => Instruction::I32LtS,
+            BinOp::LtEq if is_float => Instruction::F64Le,
+            BinOp::LtEq if is_i64 => Instruction::I64LeS,
+            BinOp::LtEq => Instruction::I32LeS,
+            BinOp::Gt if is_float => Instruction::F64Gt,
+            BinOp::Gt if is_i64 => Instruction::I64GtS,
+            BinOp::Gt => Instruction::I32GtS,
+            BinOp::GtEq if is_float => Instruction::F64Ge,
+            BinOp::GtEq if is_i64 => Instruction::I64GeS,
+            BinOp::GtEq => Instruction::I32GeS,
+            // Bools are 0/1, so the bitwise ops agree; `compile_expr_with_locals`
+            // short-circuits instead of using these
+            BinOp::And => Instruction::I32And,
+            BinOp::Or => Instruction::I32Or,
+        }
+    }
+
+    /// WASM value type an expression evaluates to
+    fn expr_val_type(&self, expr: &Expr) -> Result<ValType> {
+        self.span_val_type(expr.span())
+    }
+
+    /// WASM type of the expression or pattern at `span`, from the type sema
+    /// inferred for it
+    fn span_val_type(&self, span: Span) -> Result<ValType> {
+        match self.expr_types.get(&span) {
+            Some(ty) => self.info_val_type(ty),
+            None => Err(FluxError::WasmError {
+                message: format!(
+                    "No type was inferred for the code at {}..{}",
+                    span.start, span.end
+                ),
+            }),
+        }
+    }
+
     /// Map a Flux type to the corresponding WIT type name
     ///
     /// This helper will be used when implementing the full WIT adapter layer
@@ -245,7 +1875,15 @@ impl WasmCodegen {
             Type::Float(_) => "f64",
             Type::Bool(_) => "bool",
             Type::String(_) => "string",
-            Type::Named { .. } => "named",
+            Type::Named { name, .. } => match name.as_str() {
+                "Date" => "s32",
+                "Time" | "DateTime" | "Timestamp" | "Duration" => "s64",
+                _ => "named",
+            },
+            Type::Record { .. } => "record",
+            Type::List { .. } => "list",
+            Type::Tuple { .. } => "tuple",
+            Type::Function { .. } => "closure",
         }
     }
 }
@@ -263,6 +1901,13 @@ pub fn compile_to_component(source: &str) -> Result<Vec<u8>> {
     codegen.compile_component(&ast)
 }
 
+/// Helper function to compile Flux source to a core WASM module
+pub fn compile_to_module(source: &str) -> Result<Vec<u8>> {
+    let ast = flux_syntax::parse(source)?;
+    let mut codegen = WasmCodegen::new();
+    codegen.compile_core_module(&ast)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
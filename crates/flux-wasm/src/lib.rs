@@ -1,3 +1,4 @@
 pub mod codegen;
+pub mod mono;
 
 pub use codegen::*;
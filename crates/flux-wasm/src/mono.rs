@@ -0,0 +1,262 @@
+//! Monomorphization: a generic function is compiled once for every list of
+//! type arguments it is used at. Sema records the type arguments of each use
+//! of a generic function; starting from the functions that are not generic,
+//! every use is renamed to the instance for its type arguments, e.g.
+//! `identity<int>`, and the instances reached this way are compiled too.
+
+use flux_errors::{FluxError, Result, Span};
+use flux_sema::{FileTypes, TypeInfo};
+use flux_syntax::{Expr, Function, SourceFile};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Instances past which instantiation is taken not to terminate, e.g. a
+/// function calling itself at `List<T>`
+const MAX_INSTANCES: usize = 1000;
+
+/// A function to compile: a function that is not generic, or a generic one
+/// at concrete type arguments
+#[derive(Debug, Clone)]
+pub struct Instance {
+    /// The function, named after its type arguments when it instantiates a
+    /// generic one, with every use of a generic function renamed to the
+    /// instance it uses
+    pub func: Function,
+    /// The name the function is declared under
+    pub declared_name: String,
+    /// The types the function's type parameters stand for
+    pub type_args: HashMap<String, TypeInfo>,
+    /// Type of the function at `type_args`, as sema inferred it
+    pub ty: TypeInfo,
+    /// Types of the expressions and patterns of the function's body at
+    /// `type_args`, by span
+    pub expr_types: HashMap<Span, TypeInfo>,
+}
+
+/// Every function of `ast` to compile: the functions that are not generic,
+/// in source order, then the instances of generic functions they reach.
+/// The first type error of `ast` is an error.
+pub fn monomorphize(ast: &SourceFile) -> Result<Vec<Instance>> {
+    let types = flux_sema::infer_file(ast);
+    // Types of ill-typed code say nothing about its values
+    if let Some(error) = types.errors.iter().find(|error| !error.is_warning()) {
+        return Err(error.clone());
+    }
+    let generic: HashMap<&str, &Function> = ast
+        .functions()
+        .filter(|func| !func.type_params.is_empty())
+        .map(|func| (func.name.as_str(), func))
+        .collect();
+
+    let mut pending: VecDeque<(String, &Function, HashMap<String, TypeInfo>)> = ast
+        .functions()
+        .filter(|func| func.type_params.is_empty())
+        .map(|func| (func.name.clone(), func, HashMap::new()))
+        .collect();
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut instances = Vec::new();
+    while let Some((name, func, type_args)) = pending.pop_front() {
+        let ty = types
+            .signatures
+            .get(&func.name)
+            .map_or(TypeInfo::Unknown, |scheme| {
+                scheme.ty.substitute_params(&type_args)
+            });
+        let expr_types = types
+            .expr_types
+            .iter()
+            .filter(|((function, _), _)| *function == func.name)
+            .map(|((_, span), ty)| (*span, ty.substitute_params(&type_args)))
+            .collect();
+        let mut instance = Instance {
+            func: func.clone(),
+            declared_name: func.name.clone(),
+            type_args,
+            ty,
+            expr_types,
+        };
+        instance.func.name = name;
+        let mut uses = Vec::new();
+        rename_uses(
+            &mut instance.func.body,
+            &func.name,
+            &instance.type_args,
+            &types,
+            &generic,
+            &mut uses,
+        )?;
+        for (name, callee, type_args) in uses {
+            if seen.insert(name.clone()) {
+                pending.push_back((name, callee, type_args));
+            }
+        }
+        instances.push(instance);
+        if instances.len() > MAX_INSTANCES {
+            return Err(FluxError::WasmError {
+                message: format!(
+                    "Generic functions are used at more than {} different types; \
+                     does one call itself at ever larger types?",
+                    MAX_INSTANCES
+                ),
+            });
+        }
+    }
+    Ok(instances)
+}
+
+/// Rename the uses of generic functions in `expr`, the body of `function`
+/// instantiated at `type_args`, to their instances, collecting each instance
+/// with the function it instantiates and its type arguments
+fn rename_uses<'a>(
+    expr: &mut Expr,
+    function: &str,
+    type_args: &HashMap<String, TypeInfo>,
+    types: &FileTypes,
+    generic: &HashMap<&str, &'a Function>,
+    uses: &mut Vec<(String, &'a Function, HashMap<String, TypeInfo>)>,
+) -> Result<()> {
+    if let Expr::Var { name, span } = expr {
+        // A use sema recorded no type arguments for names something else,
+        // e.g. a variable shadowing the function
+        let recorded = types.instantiations.get(&(function.to_string(), *span));
+        if let (Some(&callee), Some(args)) = (generic.get(name.as_str()), recorded) {
+            let args: Vec<TypeInfo> = args
+                .iter()
+                .map(|arg| arg.substitute_params(type_args))
+                .collect();
+            if !args.iter().all(TypeInfo::is_concrete) {
+                return Err(FluxError::WasmError {
+                    message: format!(
+                        "Cannot infer the type arguments of `{}` in `{}`; add type annotations",
+                        name, function
+                    ),
+                });
+            }
+            let instance = instance_name(name, &args);
+            let callee_args = callee
+                .type_params
+                .iter()
+                .map(|param| param.name.clone())
+                .zip(args)
+                .collect();
+            uses.push((instance.clone(), callee, callee_args));
+            *name = instance;
+        }
+        return Ok(());
+    }
+    for child in expr.children_mut() {
+        rename_uses(child, function, type_args, types, generic, uses)?;
+    }
+    Ok(())
+}
+
+/// `identity<int>`: the name of the instance of `name` at `args`
+fn instance_name(name: &str, args: &[TypeInfo]) -> String {
+    let args: Vec<String> = args.iter().map(ToString::to_string).collect();
+    format!("{}<{}>", name, args.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instances(source: &str) -> Vec<Instance> {
+        monomorphize(&flux_syntax::parse(source).unwrap()).unwrap()
+    }
+
+    /// Names of the functions the body of `instance` calls
+    fn callees(instance: &Instance) -> Vec<String> {
+        fn collect(expr: &mut Expr, out: &mut Vec<String>) {
+            if let Expr::Call { func, .. } = expr {
+                if let Expr::Var { name, .. } = &**func {
+                    out.push(name.clone());
+                }
+            }
+            for child in expr.children_mut() {
+                collect(child, out);
+            }
+        }
+        let mut body = instance.func.body.clone();
+        let mut out = Vec::new();
+        collect(&mut body, &mut out);
+        out
+    }
+
+    #[test]
+    fn test_generic_functions_are_instantiated_per_type() {
+        let instances = instances(
+            r#"
+            fn main() -> int {
+                if identity(true) { twice(identity(1))[0] } else { first([2, 3]) }
+            }
+            fn identity<T>(x: T) -> T { x }
+            fn twice<T>(x: T) -> (T, T) { (identity(x), identity(x)) }
+            fn first<T>(xs: List<T>) -> T { xs[0] }
+        "#,
+        );
+
+        let names: Vec<&str> = instances
+            .iter()
+            .map(|instance| instance.func.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "main",
+                "identity<bool>",
+                "twice<int>",
+                "identity<int>",
+                "first<int>"
+            ]
+        );
+        assert_eq!(
+            callees(&instances[0]),
+            [
+                "identity<bool>",
+                "twice<int>",
+                "identity<int>",
+                "first<int>"
+            ]
+        );
+        // Uses inside an instance are instantiated at its type arguments
+        assert_eq!(callees(&instances[2]), ["identity<int>", "identity<int>"]);
+        assert_eq!(instances[2].type_args["T"], TypeInfo::Int);
+    }
+
+    #[test]
+    fn test_type_errors_are_errors() {
+        let ast = flux_syntax::parse("fn main() -> int { 1 + true }").unwrap();
+        let error = monomorphize(&ast).unwrap_err();
+        assert!(matches!(error, FluxError::TypeError { .. }), "{:?}", error);
+    }
+
+    #[test]
+    fn test_shadowed_and_unused_generics_are_not_instantiated() {
+        let instances = instances(
+            r#"
+            fn identity<T>(x: T) -> T { x }
+            fn unused<T>(x: T) -> int { 0 }
+            fn main() -> int {
+                let identity = fn(x: int) { x + 1 }
+                identity(1)
+            }
+        "#,
+        );
+
+        let names: Vec<&str> = instances
+            .iter()
+            .map(|instance| instance.func.name.as_str())
+            .collect();
+        assert_eq!(names, ["main"]);
+        assert_eq!(callees(&instances[0]), ["identity"]);
+    }
+
+    #[test]
+    fn test_runaway_instantiation_is_an_error() {
+        let source = r#"
+            fn nest<T>(x: T) -> int { nest([x]) }
+            fn main() -> int { nest(1) }
+        "#;
+        let error = monomorphize(&flux_syntax::parse(source).unwrap()).unwrap_err();
+        assert!(error.to_string().contains("ever larger types"), "{}", error);
+    }
+}
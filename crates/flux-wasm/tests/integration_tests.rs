@@ -1,4 +1,6 @@
-use flux_wasm::compile_to_component;
+use flux_sema::{FluxDatabase, SemaDatabase, Vfs};
+use flux_wasm::{compile_to_component, WasmCodegen};
+use std::path::Path;
 use wasmtime::component::Component;
 use wasmtime::*;
 
@@ -103,3 +105,694 @@ fn test_component_compilation_with_operations() {
     let engine = Engine::default();
     Component::from_binary(&engine, &component_bytes).expect("Failed to create component");
 }
+
+// Execution Tests
+
+/// Compile `source` to a core module and run its exported `main`, which
+/// returns an int or a bool
+fn run_main(source: &str) -> i64 {
+    run_module(&flux_wasm::compile_to_module(source).expect("Compilation failed"))
+}
+
+/// Compile `source` to a core module and whether running its exported
+/// `main` traps
+fn main_traps(source: &str) -> bool {
+    let wasm_bytes = flux_wasm::compile_to_module(source).expect("Compilation failed");
+    let engine = Engine::default();
+    let module = Module::from_binary(&engine, &wasm_bytes).expect("Failed to create module");
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[]).expect("Failed to instantiate");
+    let main = instance
+        .get_func(&mut store, "main")
+        .expect("Missing main export");
+    let mut results = [Val::I64(0)];
+    main.call(&mut store, &[], &mut results).is_err()
+}
+
+/// Run the exported `main` of the core module `wasm_bytes`
+fn run_module(wasm_bytes: &[u8]) -> i64 {
+    run_module_with_columns(wasm_bytes, &[])
+}
+
+/// Run the exported `main` of the core module `wasm_bytes` after filling in
+/// its columns of ints or bools, as a host does: each column is a cell
+/// allocated from `heap` holding its length and then its values in 8-byte
+/// slots
+fn run_module_with_columns(wasm_bytes: &[u8], columns: &[(&str, &[i64])]) -> i64 {
+    let engine = Engine::default();
+    let module = Module::from_binary(&engine, wasm_bytes).expect("Failed to create module");
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[]).expect("Failed to instantiate");
+    let memory = instance.get_memory(&mut store, "memory").unwrap();
+    let heap = instance.get_global(&mut store, "heap").unwrap();
+    for (name, values) in columns {
+        let cell = heap.get(&mut store).unwrap_i32();
+        let mut bytes = (values.len() as i64).to_le_bytes().to_vec();
+        bytes.extend(values.iter().flat_map(|value| value.to_le_bytes()));
+        memory.write(&mut store, cell as usize, &bytes).unwrap();
+        heap.set(&mut store, Val::I32(cell + bytes.len() as i32))
+            .unwrap();
+        instance
+            .get_global(&mut store, &format!("column:{}", name))
+            .expect("Missing column export")
+            .set(&mut store, Val::I32(cell))
+            .unwrap();
+    }
+    let main = instance
+        .get_func(&mut store, "main")
+        .expect("Missing main export");
+    let mut results = [Val::I64(0)];
+    main.call(&mut store, &[], &mut results)
+        .expect("Execution failed");
+    match results[0] {
+        Val::I64(value) => value,
+        Val::I32(value) => value.into(),
+        ref other => panic!("`main` returned {:?}", other),
+    }
+}
+
+/// Compile `source` to a core module, with the std prelude linked in
+fn compile_program(source: &str) -> Vec<u8> {
+    WasmCodegen::new()
+        .compile_core_module(&link(&[("main.flux", source)]))
+        .expect("Compilation failed")
+}
+
+/// Link the program whose first file is `files[0]`, the others being
+/// modules it can import, as `flux compile` does
+fn link(files: &[(&str, &str)]) -> flux_syntax::SourceFile {
+    let vfs = Vfs::new();
+    let ids: Vec<_> = files
+        .iter()
+        .map(|(path, text)| vfs.set_file_content(Path::new(path), text.to_string()))
+        .collect();
+    let mut db = FluxDatabase::new();
+    db.load_std_lib(&vfs);
+    db.set_file_text(ids[0], files[0].1);
+    db.load_imports(&vfs, ids[0]);
+    db.link_program(ids[0])
+}
+
+#[test]
+fn test_examples_check_and_compile() {
+    let examples = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../examples");
+    let mut checked = 0;
+    for entry in std::fs::read_dir(&examples).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|ext| ext != "flux") {
+            continue;
+        }
+        let vfs = Vfs::new();
+        let file = vfs.load_file(&path).unwrap();
+        let mut db = FluxDatabase::new();
+        db.load_std_lib(&vfs);
+        db.set_file_text(file, &vfs.get_file(file).unwrap().content);
+        db.load_imports(&vfs, file);
+        let errors: Vec<String> = db
+            .diagnostics(file)
+            .iter()
+            .filter(|error| !error.is_warning())
+            .map(ToString::to_string)
+            .collect();
+
+        // `type_error.flux` shows what `flux check` rejects
+        if path.ends_with("type_error.flux") {
+            assert!(!errors.is_empty(), "{} has no errors", path.display());
+            continue;
+        }
+        assert!(errors.is_empty(), "{}: {:?}", path.display(), errors);
+        let program = db.link_program(file);
+        if let Err(error) = WasmCodegen::new().compile_component(&program) {
+            panic!("{}: {}", path.display(), error);
+        }
+        checked += 1;
+    }
+    assert!(checked >= 3, "only {} examples found", checked);
+}
+
+#[test]
+fn test_wasm_execution_int_comparisons() {
+    assert_eq!(run_main("fn main() { return 1 < 2 }"), 1);
+    assert_eq!(run_main("fn main() { return 2 <= 1 }"), 0);
+    assert_eq!(run_main("fn main() { return 3 > 2 }"), 1);
+    assert_eq!(run_main("fn main() { return 3 >= 4 }"), 0);
+    assert_eq!(run_main("fn main() { return 6 * 7 == 42 }"), 1);
+    assert_eq!(run_main("fn main() { return 6 != 6 }"), 0);
+}
+
+#[test]
+fn test_wasm_execution_float_comparisons() {
+    assert_eq!(run_main("fn main() { return 1.5 + 1.0 > 2.25 }"), 1);
+    assert_eq!(run_main("fn main() { return 0.5 == 0.25 }"), 0);
+}
+
+#[test]
+fn test_wasm_execution_string_comparisons() {
+    assert_eq!(run_main(r#"fn main() { return "ab" == "ac" }"#), 0);
+    assert_eq!(run_main(r#"fn main() { return "ab" == "ab" }"#), 1);
+    assert_eq!(run_main(r#"fn main() { return "ab" != "abc" }"#), 1);
+    assert_eq!(run_main(r#"fn main() { return "" == "" }"#), 1);
+    // Strings compare by their UTF-8 bytes
+    let source = r#"
+        fn same(a: string, b: string) -> bool { a == b }
+        fn main() -> bool { same("héllo", "héllo") && !same("héllo", "hello") }
+    "#;
+    assert_eq!(run_main(source), 1);
+}
+
+#[test]
+fn test_wasm_execution_logical_operators() {
+    assert_eq!(run_main("fn main() { return true && !false }"), 1);
+    assert_eq!(run_main("fn main() { return false || 1 > 2 }"), 0);
+    assert_eq!(
+        run_main("fn main() { return !(1 < 2) || 2 < 3 && true }"),
+        1
+    );
+}
+
+#[test]
+fn test_wasm_execution_short_circuit_skips_return() {
+    // The right operand returns early, so it must only run when reached
+    assert_eq!(run_main("fn main() { return false && { return true } }"), 0);
+    assert_eq!(run_main("fn main() { return true || { return false } }"), 1);
+}
+
+#[test]
+fn test_wasm_execution_if_else() {
+    assert_eq!(run_main("fn main() { if 1 < 2 { 10 } else { 20 } }"), 10);
+    assert_eq!(run_main("fn main() { if 2 < 1 { 10 } else { 20 } }"), 20);
+    assert_eq!(
+        run_main("fn main() { let x = 5 if x > 10 { 1 } else if x > 3 { 2 } else { 3 } }"),
+        2
+    );
+}
+
+#[test]
+fn test_wasm_execution_if_branch_locals_and_returns() {
+    let source = "fn main() { if true { let a = 40 return a + 2 } else { let b = 1 return b } }";
+    assert_eq!(run_main(source), 42);
+}
+
+#[test]
+fn test_wasm_execution_if_float_branches() {
+    assert_eq!(
+        run_main("fn main() { return if true { 1.5 } else { 2.5 } < 2.0 }"),
+        1
+    );
+}
+
+#[test]
+fn test_wasm_execution_temporal_arithmetic() {
+    assert_eq!(
+        run_main("fn main() { @2026-10-16T12:00:00Z - @2026-10-16T10:00:00Z == 2h }"),
+        1
+    );
+    assert_eq!(
+        run_main("fn main() { @2026-10-16T12:00:00[+02:00] == @2026-10-16T10:00:00[+00:00] }"),
+        1
+    );
+    assert_eq!(
+        run_main("fn main() { @2026-10-16 + 1w 24h == @2026-10-24 }"),
+        1
+    );
+    assert_eq!(
+        run_main("fn main() { @2026-10-16 - @2026-10-01 == 15d }"),
+        1
+    );
+    assert_eq!(run_main("fn main() { @23:30 + 45m == @00:15 }"), 1);
+    assert_eq!(run_main("fn main() { 3 * 20m / 2 < 1h }"), 1);
+}
+
+#[test]
+fn test_wasm_execution_zoned_date_times() {
+    // Paris is at +02:00 until the last Sunday of October, then at +01:00
+    assert_eq!(
+        run_main("fn main() { @2026-10-16T12:00:00[Europe/Paris] == @2026-10-16T10:00:00[UTC] }"),
+        1
+    );
+    assert_eq!(
+        run_main("fn main() { @2026-12-01T12:00:00[Europe/Paris] == @2026-12-01T11:00:00[UTC] }"),
+        1
+    );
+    assert_eq!(
+        run_main(
+            "fn main() { @2026-07-01T09:00[America/New_York] - @2026-07-01T15:00[Europe/Paris] == 0h }"
+        ),
+        1
+    );
+}
+
+#[test]
+fn test_wasm_rejects_durations_dates_cannot_hold() {
+    let source = "fn shift(day: Date, by: Duration) -> Date { day + by }
+        fn main() { shift(@2026-10-16, 3 * 1h) == @2026-10-16 }";
+    let error = flux_wasm::compile_to_module(source)
+        .unwrap_err()
+        .to_string();
+    assert!(
+        error.contains("this Duration may have a time part"),
+        "{}",
+        error
+    );
+
+    let whole_days = "fn shift(day: Date, by: int) -> Date { day + by * 1d }
+        fn main() { shift(@2026-10-16, 2) == @2026-10-18 }";
+    assert_eq!(run_main(whole_days), 1);
+
+    let months = flux_wasm::compile_to_module("fn main() { @2026-10-16 + 1mo == @2026-11-16 }");
+    assert!(months.unwrap_err().to_string().contains("Months and years"));
+}
+
+#[test]
+fn test_wasm_execution_match_on_enum() {
+    let shapes = "enum Shape { Circle(int), Rect(int, int), Empty }";
+    let area = |shape: &str| {
+        run_main(&format!(
+            "{shapes}\nfn main() {{ match {shape} {{ Circle(r) => 3 * r * r, \
+             Rect(w, h) => w * h, Empty => 0 }} }}"
+        ))
+    };
+    assert_eq!(area("Circle(2)"), 12);
+    assert_eq!(area("Rect(6, 7)"), 42);
+    assert_eq!(area("Empty"), 0);
+}
+
+#[test]
+fn test_wasm_execution_match_nested_patterns() {
+    let source = "enum Light { Red, Green }
+        enum Slot { Taken(Light, int), Free }
+        fn main() {
+            let slot = Taken(Green, 5)
+            match slot {
+                Free => 0,
+                Taken(Red, _) => 1,
+                Taken(Green, 0) => 2,
+                Taken(Green, n) => 10 + n,
+            }
+        }";
+    assert_eq!(run_main(source), 15);
+}
+
+#[test]
+fn test_wasm_execution_match_literals() {
+    assert_eq!(
+        run_main("fn main() { match 1 < 2 { true => 10, false => 20 } }"),
+        10
+    );
+    assert_eq!(
+        run_main("fn main() { match 3 { 1 => 10, 2 => 20, n => n * 100 } }"),
+        300
+    );
+}
+
+#[test]
+fn test_wasm_execution_closure_captures() {
+    let source = "fn main() {
+            let n = 10
+            let add = fn(x: int) -> int { x + n }
+            add(32)
+        }";
+    assert_eq!(run_main(source), 42);
+}
+
+#[test]
+fn test_wasm_rejects_values_of_generic_types() {
+    // A let-bound lambda is generalized, so nothing fixes the type of `x`
+    let error = compile_to_component("fn main() -> int { let id = fn(x) { x }  id(5) }")
+        .unwrap_err()
+        .to_string();
+    assert!(
+        error.contains("no single runtime representation"),
+        "{}",
+        error
+    );
+
+    let error = compile_to_component("fn id(x) { x }\nfn main() -> int { id(5) }")
+        .unwrap_err()
+        .to_string();
+    assert!(
+        error.contains("`id` is generic, ('a) -> 'a, but declares no type parameters"),
+        "{}",
+        error
+    );
+
+    // Annotated, both compile
+    assert_eq!(
+        run_main("fn main() -> int { let id = fn(x: int) { x }  id(5) }"),
+        5
+    );
+    assert_eq!(
+        run_main("fn id<T>(x: T) -> T { x }\nfn main() -> int { id(5) }"),
+        5
+    );
+}
+
+#[test]
+fn test_wasm_execution_nested_closures() {
+    let source = "fn main() {
+            let base = 100
+            let make = fn(step: int) { fn(x: int) -> int { base + step * x } }
+            let twice = make(2)
+            let thrice = make(3)
+            twice(5) + thrice(1) + fn(x: int) { x - 1 }(1)
+        }";
+    assert_eq!(run_main(source), 213);
+}
+
+#[test]
+fn test_wasm_execution_closure_capturing_enum_and_closure() {
+    let source = "enum Shape { Square(int), Empty }
+        fn main() {
+            let shape = Square(4)
+            let area = fn() { match shape { Square(s) => s * s, Empty => 0 } }
+            let scaled = fn(k: int) { k * area() }
+            5 |> scaled
+        }";
+    assert_eq!(run_main(source), 80);
+}
+
+#[test]
+fn test_wasm_execution_passes_functions_as_values() {
+    // A lambda passed to a named function, a closure returned from one, and
+    // named functions passed where a function is expected
+    let source = "
+        fn apply(f: (int) -> int, x: int) -> int { f(x) }
+        fn adder(k: int) -> (int) -> int { fn(x: int) { x + k } }
+        fn twice(x: int) -> int { x * 2 }
+        fn compose(f: (int) -> int, g: (int) -> int) -> (int) -> int { fn(x: int) { g(f(x)) } }
+        fn main() -> int {
+            let offset = 1
+            let first = apply(fn(x: int) { x + offset }, 40)
+            let second = apply(adder(100), 0) + adder(2)(3)
+            let third = apply(twice, 5) + compose(twice, adder(1))(10)
+            first * 1000000 + second * 1000 + third
+        }
+    ";
+    assert_eq!(run_main(source), 41_105_031);
+}
+
+#[test]
+fn test_wasm_compiles_linked_programs_with_types() {
+    let program = link(&[
+        (
+            "main.flux",
+            "import shapes
+            type Size = int
+            enum Shape { Square(Size), Empty }
+            fn main() -> int {
+                let shape = Square(4)
+                match shape { Square(s) => s * s + ranked(), Empty => 0 }
+            }",
+        ),
+        (
+            "shapes.flux",
+            "enum Rank { Low, High }
+            fn score(rank: Rank) -> int { match rank { Low => 1, High => 2 } }
+            export fn ranked() -> int { score(High) * 100 }",
+        ),
+    ]);
+    WasmCodegen::new()
+        .compile_component(&program)
+        .expect("Component compilation failed");
+
+    let wasm_bytes = WasmCodegen::new()
+        .compile_core_module(&program)
+        .expect("Compilation failed");
+    assert_eq!(run_module(&wasm_bytes), 216);
+}
+
+#[test]
+fn test_wasm_keeps_private_names_to_their_module() {
+    let program = link(&[
+        (
+            "main.flux",
+            "import util
+            enum Mode { On, Off }
+            fn helper() -> int { 1 }
+            fn main() -> int {
+                let mode = On
+                let on = match mode { On => 10, Off => 20 }
+                scaled() * 100 + helper() + on
+            }",
+        ),
+        (
+            "util.flux",
+            "enum Mode { Off, On }
+            fn helper() -> int { 7 }
+            export fn scaled() -> int { match Off { Off => helper() * 3, On => 0 } }",
+        ),
+    ]);
+    let wasm_bytes = WasmCodegen::new()
+        .compile_core_module(&program)
+        .expect("Compilation failed");
+    assert_eq!(run_module(&wasm_bytes), 2111);
+}
+
+#[test]
+fn test_wasm_execution_column_helpers() {
+    let source = "
+        fn total(scores: Column<int>) -> int {
+            scores
+                |> filter(#active)
+                |> map(fn(x: int) { x * 10 })
+                |> reduce(fn(a: int, b: int) { a + b }, 0)
+        }
+        fn big(scores: Column<int>) -> int {
+            let keep = scores |> map(fn(x: int) { x > 2 })
+            scores |> filter(keep) |> reduce(fn(a: int, b: int) { a + b }, 0)
+        }
+        fn double(x: int) -> int { x * 2 }
+        fn evens(scores: Column<int>) -> Column<int> { scores |> map(double) }
+        fn main() -> int {
+            total(#scores) * 10000 + big(#scores) * 100 + count(evens(#scores))
+        }
+    ";
+    // The helpers come from the std prelude
+    let program = link(&[("main.flux", source)]);
+    WasmCodegen::new()
+        .compile_component(&program)
+        .expect("Component compilation failed");
+    let wasm_bytes = WasmCodegen::new()
+        .compile_core_module(&program)
+        .expect("Compilation failed");
+    let columns: &[(&str, &[i64])] = &[("scores", &[1, 2, 3, 4]), ("active", &[1, 0, 1, 1])];
+    assert_eq!(run_module_with_columns(&wasm_bytes, columns), 800_704);
+
+    // Columns the host leaves alone are empty
+    assert_eq!(run_module(&wasm_bytes), 0);
+}
+
+#[test]
+fn test_wasm_execution_string_helpers() {
+    let run = |source: &str| run_module(&compile_program(source));
+    assert_eq!(run(r#"fn main() -> int { length("héllo") }"#), 5);
+    assert_eq!(run(r#"fn main() -> bool { is_empty("hello") }"#), 0);
+    assert_eq!(run(r#"fn main() -> bool { is_empty("") }"#), 1);
+    // Built strings compare like literals
+    assert_eq!(
+        run(r#"fn main() -> bool { concat("foo", "bar") == "foobar" }"#),
+        1
+    );
+    assert_eq!(
+        run(r#"fn main() -> int { length(concat(concat("ab", ""), "cde")) }"#),
+        5
+    );
+    assert_eq!(
+        run(
+            r#"fn main() -> bool { to_upper("Flux 1é") == "FLUX 1é" && to_lower("FLUX") == "flux" }"#
+        ),
+        1
+    );
+    let source = r#"fn main() -> bool {
+        contains("hello", "ell") && contains("hello", "") && contains("hello", "hello")
+            && !contains("hello", "hellos") && !contains("hello", "elo")
+    }"#;
+    assert_eq!(run(source), 1);
+}
+
+#[test]
+fn test_wasm_execution_grows_memory() {
+    // Memory starts at 1 MiB; doubling a string 21 times allocates about
+    // 4 MiB, the last string alone 2 MiB
+    let source = r#"
+        fn double(s: string, times: int) -> string {
+            if times == 0 { s } else { double(concat(s, s), times - 1) }
+        }
+        fn main() -> int { length(double("a", 21)) }
+    "#;
+    assert_eq!(run_module(&compile_program(source)), 1 << 21);
+}
+
+#[test]
+fn test_wasm_execution_lists_and_tuples() {
+    let source = "fn main() {
+            let xs = [10, 20, 30]
+            let pair = (xs, 2)
+            pair[0][pair[1]] + xs[0] + [[1, 2], [3]][1][0]
+        }";
+    assert_eq!(run_main(source), 43);
+    assert_eq!(run_main("fn main() { [1.5, 2.5][1] > [1.5, 2.5][0] }"), 1);
+}
+
+#[test]
+fn test_wasm_execution_index_out_of_bounds_traps() {
+    for index in ["3", "0 - 1"] {
+        let source = format!("fn main() {{ let xs = [1, 2, 3] xs[{index}] }}");
+        let wasm_bytes = flux_wasm::compile_to_module(&source).expect("Compilation failed");
+
+        let engine = Engine::default();
+        let module = Module::from_binary(&engine, &wasm_bytes).expect("Failed to create module");
+        let mut store = Store::new(&engine, ());
+        let instance = Instance::new(&mut store, &module, &[]).expect("Failed to instantiate");
+        let main = instance
+            .get_typed_func::<(), i64>(&mut store, "main")
+            .expect("Missing main export");
+        assert!(
+            main.call(&mut store, ()).is_err(),
+            "xs[{index}] should trap"
+        );
+    }
+}
+
+#[test]
+fn test_wasm_execution_option_result_and_try() {
+    let source = "fn main() {
+            let half = fn(x: int) -> Option<int> { if x / 2 * 2 == x { some(x / 2) } else { none } }
+            let quarter = fn(x: int) -> Option<int> { some(half(half(x)?)?) }
+            let check = fn(x: int) -> Result<int, int> { if x > 0 { ok(x) } else { err(0 - x) } }
+            let sum = fn(a: int, b: int) -> Result<int, int> { ok(check(a)? + check(b)?) }
+            let q = match quarter(12) { some(q) => q, none => 100 }
+            let r = match quarter(6) { some(r) => r, none => 100 }
+            let s = match sum(1, 0 - 7) { ok(s) => s, err(e) => e * 1000 }
+            q + r + s
+        }";
+    assert_eq!(run_main(source), 3 + 100 + 7000);
+
+    // The payload takes the type it has where the value is built
+    let source = "fn main() {
+            let halve = fn(x: float) -> Option<float> { some(x / 2.0) }
+            match halve(5.0) { some(x) => x == 2.5, none => false }
+        }";
+    assert_eq!(run_main(source), 1);
+}
+
+#[test]
+fn test_wasm_execution_runs_every_statement_of_a_block() {
+    // `?` leaves the function from a statement whose value is dropped
+    let source = "fn first(o: Option<int>) -> Option<int> { o?; some(5) }
+        fn main() -> int {
+            let a = match first(none) { some(x) => x, none => 0 }
+            let b = match first(some(1)) { some(x) => x, none => 0 }
+            a * 10 + b
+        }";
+    assert_eq!(run_main(source), 5);
+
+    assert_eq!(
+        run_main("fn main() -> int { if true { return 1 } else { 0 }; 42 }"),
+        1
+    );
+    assert!(main_traps(
+        "fn main() -> int { let xs = [1, 2, 3] xs[5]; 7 }"
+    ));
+}
+
+#[test]
+fn test_wasm_execution_empty_block() {
+    assert_eq!(run_main("fn main() -> int { {} }"), 0);
+    assert_eq!(run_main("fn main() -> int { let x = {} x + 1 }"), 1);
+}
+
+#[test]
+fn test_wasm_compiles_every_function() {
+    let source = "
+        fn helper(flag: bool, at: Time) -> Time { at }
+        export fn scale(x: float, times: int) -> float { x * 2.5 }
+        export fn answer() -> int { 42 }
+        fn main() { 1 }
+    ";
+    let wasm_bytes = flux_wasm::compile_to_module(source).expect("Compilation failed");
+
+    let engine = Engine::default();
+    let module = Module::from_binary(&engine, &wasm_bytes).expect("Failed to create module");
+    let exports: Vec<&str> = module
+        .exports()
+        .filter(|export| matches!(export.ty(), ExternType::Func(_)))
+        .map(|export| export.name())
+        .collect();
+    assert_eq!(exports, ["scale", "answer", "main"]);
+
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[]).expect("Failed to instantiate");
+    let scale = instance
+        .get_typed_func::<(f64, i64), f64>(&mut store, "scale")
+        .expect("Missing scale export");
+    assert_eq!(scale.call(&mut store, (1.5, 0)).unwrap(), 3.75);
+    let answer = instance
+        .get_typed_func::<(), i64>(&mut store, "answer")
+        .expect("Missing answer export");
+    assert_eq!(answer.call(&mut store, ()).unwrap(), 42);
+}
+
+#[test]
+fn test_wasm_execution_ints_are_64_bit() {
+    assert_eq!(run_main("fn main() { 3000000000 * 3 }"), 9_000_000_000);
+    assert_eq!(
+        run_main("fn main() { match 5000000000 { 5000000000 => 1, _ => 0 } }"),
+        1
+    );
+    assert_eq!(run_main("fn main() { 3 * 20m / 2 == 30m }"), 1);
+
+    // Locals take the type sema inferred for them, floats included
+    let source = "
+        fn main() -> int {
+            let half = 0.5
+            let scaled = fn(x) { x * half }
+            let big = [4000000000, 2]
+            if scaled(3.0) > 1.0 { big[0] + big[1] } else { 0 }
+        }
+    ";
+    assert_eq!(run_main(source), 4_000_000_002);
+}
+
+#[test]
+fn test_wasm_execution_calls_functions() {
+    let source = include_str!("../../../examples/let_binding.flux");
+    assert_eq!(run_main(source), 42);
+
+    // `main` calls `digits` before it is declared, and each argument
+    // lands in its own parameter
+    let source = "
+        fn main() -> int {
+            let xs = [1, 2]
+            digits(xs[0], xs[1] + twice(1))
+        }
+        fn digits(tens: int, ones: int) -> int { tens * 10 + ones }
+        fn twice(x: int) -> int { x + x }
+    ";
+    assert_eq!(run_main(source), 14);
+
+    let error = compile_to_component("fn f(x: int) { x } fn main() { f(1, 2) }")
+        .unwrap_err()
+        .to_string();
+    assert!(
+        error.contains("`f` expects 1 argument, found 2"),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn test_wasm_execution_skips_generic_functions() {
+    let source = r#"
+        fn identity<T>(x: T) -> T { x }
+        fn main() -> int {
+            let double = fn(x: int) { x * 2 }
+            double(21)
+        }
+    "#;
+    assert_eq!(run_main(source), 42);
+
+    let maybe = "enum Maybe<T> { Just(T), Nothing } fn main() { 1 }";
+    let error = compile_to_component(maybe).unwrap_err().to_string();
+    assert!(error.contains("type parameter `T`"), "{}", error);
+}
@@ -8,6 +8,9 @@ import {
 
 let client: LanguageClient;
 
+// Scheme of the read-only std library documents the server points into
+const STD_SCHEME = 'flux-std';
+
 export function activate(context: vscode.ExtensionContext) {
   // Get the LSP server executable path
   // In production, this would be the compiled flux-lsp binary
@@ -32,7 +35,21 @@ export function activate(context: vscode.ExtensionContext) {
     clientOptions
   );
 
-  client.start();
+  const started = client.start();
+
+  // Go to definition into the std library opens `flux-std:` documents,
+  // whose text only the server has
+  context.subscriptions.push(
+    vscode.workspace.registerTextDocumentContentProvider(STD_SCHEME, {
+      async provideTextDocumentContent(uri: vscode.Uri): Promise<string> {
+        await started;
+        const text = await client.sendRequest<string | null>('flux/virtualDocument', {
+          uri: uri.toString(),
+        });
+        return text ?? '';
+      },
+    })
+  );
 
   vscode.window.showInformationMessage('Flux Language Server activated');
 }